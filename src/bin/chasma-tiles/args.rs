@@ -0,0 +1,76 @@
+// src/bin/chasma-tiles/args.rs
+//! Tiny `--key value` / `--flag` parser (the tool has too few options to pull in a CLI crate).
+
+use std::collections::HashMap;
+use std::str::FromStr;
+
+use crate::CliError;
+
+pub struct Args {
+    opts: HashMap<String, String>,
+    flags: Vec<String>,
+}
+
+impl Args {
+    /// `flag_names` take no value; every other `--name` expects one.
+    pub fn parse(raw: impl IntoIterator<Item = String>, flag_names: &[&str]) -> Result<Self, CliError> {
+        let mut opts = HashMap::new();
+        let mut flags = Vec::new();
+
+        let mut it = raw.into_iter();
+        while let Some(arg) = it.next() {
+            let Some(name) = arg.strip_prefix("--") else {
+                return Err(CliError::Usage(format!("unexpected argument '{}'", arg)));
+            };
+            // `--key=value` and `--key value` are both accepted
+            if let Some((k, v)) = name.split_once('=') {
+                opts.insert(k.to_string(), v.to_string());
+            } else if flag_names.contains(&name) {
+                flags.push(name.to_string());
+            } else {
+                let v = it
+                    .next()
+                    .ok_or_else(|| CliError::Usage(format!("--{} needs a value", name)))?;
+                opts.insert(name.to_string(), v);
+            }
+        }
+        Ok(Self { opts, flags })
+    }
+
+    pub fn flag(&self, name: &str) -> bool {
+        self.flags.iter().any(|f| f == name)
+    }
+
+    pub fn get(&self, name: &str) -> Option<&str> {
+        self.opts.get(name).map(String::as_str)
+    }
+
+    pub fn required(&self, name: &str) -> Result<&str, CliError> {
+        self.get(name)
+            .ok_or_else(|| CliError::Usage(format!("missing --{}", name)))
+    }
+
+    /// Parse `--name` if given, else `default`.
+    pub fn parse_or<T: FromStr>(&self, name: &str, default: T) -> Result<T, CliError> {
+        match self.get(name) {
+            Some(v) => v
+                .parse()
+                .map_err(|_| CliError::Usage(format!("--{}: cannot parse '{}'", name, v))),
+            None => Ok(default),
+        }
+    }
+
+    /// `WxH` (or a single `N` for square) sizes.
+    pub fn size(&self, name: &str) -> Result<Option<(u32, u32)>, CliError> {
+        let Some(v) = self.get(name) else { return Ok(None) };
+        let bad = || CliError::Usage(format!("--{}: expected WxH, got '{}'", name, v));
+        let (w, h) = match v.split_once(['x', 'X']) {
+            Some((w, h)) => (w.parse().map_err(|_| bad())?, h.parse().map_err(|_| bad())?),
+            None => {
+                let n = v.parse().map_err(|_| bad())?;
+                (n, n)
+            }
+        };
+        Ok(Some((w, h)))
+    }
+}
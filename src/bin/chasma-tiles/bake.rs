@@ -0,0 +1,88 @@
+// src/bin/chasma-tiles/bake.rs
+//! `bake-edits`: merge the saved `.delta` edit layer into full RAW16 tiles.
+
+use std::path::PathBuf;
+
+use chasma::heightmap_data::HeightTileCache;
+use chasma::terrain::{HeightFormat, TileEditLayer};
+
+use crate::args::Args;
+use crate::{default_assets_root, open_map, write_file, CliError};
+
+pub fn run(raw: impl Iterator<Item = String>) -> Result<(), CliError> {
+    let args = Args::parse(raw, &["in-place"])?;
+
+    let manifest_path = PathBuf::from(args.required("manifest")?);
+    let assets = match args.get("assets") {
+        Some(a) => PathBuf::from(a),
+        None => default_assets_root(&manifest_path),
+    };
+    let in_place = args.flag("in-place");
+
+    let (manifest, mut cache) = open_map(&manifest_path, &assets)?;
+    let layer = cache.edits.get_or_insert_with(TileEditLayer::default).clone();
+    let Some(tiles) = cache.tile_set.clone() else {
+        return Err(CliError::Manifest("tile set could not be resolved".into()));
+    };
+
+    let hm = &manifest.heightmaps;
+    if in_place && (hm.format != HeightFormat::Raw16Le || hm.ext != ".r16") {
+        return Err(CliError::Usage(format!(
+            "--in-place needs a Raw16Le map with '.r16' tiles (this one is {:?} '{}'); use --out",
+            hm.format, hm.ext
+        )));
+    }
+
+    // Output naming: the same `{prefix}_y{cz}_x{cx}` scheme, always `.r16`
+    let out_folder = match (in_place, args.get("out")) {
+        (true, _) => cache.folder.clone(),
+        (false, Some(o)) => PathBuf::from(o),
+        (false, None) => assets.join(format!("{}_baked", hm.folder)),
+    };
+    let mut namer = HeightTileCache::new(&out_folder, hm.resolution);
+    namer.filename_prefix = hm.prefix.clone();
+    namer.filename_ext = ".r16".to_string();
+
+    let (mut written, mut baked) = (0usize, 0usize);
+    let mut keys = tiles.keys();
+    keys.sort_by_key(|&(cx, cz)| (cz, cx));
+    for (cx, cz) in keys {
+        // Fetching merges the tile's delta, if it has one
+        let tile = match cache.try_fetch_tile(cx, cz) {
+            Ok(t) => t,
+            Err(e) if e.is_missing() => continue,
+            Err(e) => return Err(e.into()),
+        };
+        let has_delta = layer.get(cx, cz).is_some();
+        if in_place && !has_delta {
+            continue;
+        }
+
+        let path = namer.tile_path(cx, cz);
+        write_file(&path, &HeightFormat::Raw16Le.encode(&path, &tile)?)?;
+        namer.remove_mip_files(cx, cz)?;
+        written += 1;
+
+        if has_delta {
+            baked += 1;
+            if in_place {
+                let delta = cache.delta_path(cx, cz);
+                std::fs::remove_file(&delta).map_err(|source| CliError::Io { path: delta, source })?;
+            }
+        }
+    }
+
+    println!(
+        "wrote {} RAW16 tiles to {} ({} with edits baked in{})",
+        written,
+        out_folder.display(),
+        baked,
+        if in_place { ", .delta files removed" } else { "" }
+    );
+    if !in_place && out_folder != cache.folder {
+        println!(
+            "point heightmaps.folder at the baked folder (and set ext: \".r16\", format: Raw16Le) to use it"
+        );
+    }
+    Ok(())
+}
@@ -0,0 +1,141 @@
+// src/bin/chasma-tiles/erode.rs
+//! `erode`: run hydraulic and/or thermal erosion over a rectangle of tiles and write the
+//! result as RAW16 tiles. Deterministic for a given seed; the printed checksum makes runs
+//! easy to compare.
+
+use std::path::PathBuf;
+
+use bevy::math::IVec2;
+
+use chasma::heightmap_data::HeightTileCache;
+use chasma::terrain::{erode_region, ErosionDef, HeightFormat, HydraulicErosion, ThermalErosion, TileRegion};
+
+use crate::args::Args;
+use crate::{default_assets_root, open_map, write_file, CliError};
+
+pub fn run(raw: impl Iterator<Item = String>) -> Result<(), CliError> {
+    let args = Args::parse(raw, &["in-place"])?;
+
+    let manifest_path = PathBuf::from(args.required("manifest")?);
+    let assets = match args.get("assets") {
+        Some(a) => PathBuf::from(a),
+        None => default_assets_root(&manifest_path),
+    };
+    let in_place = args.flag("in-place");
+
+    let (manifest, cache) = open_map(&manifest_path, &assets)?;
+    let Some(tiles) = cache.tile_set.clone() else {
+        return Err(CliError::Manifest("tile set could not be resolved".into()));
+    };
+    let data = manifest.heightmap_data(&tiles);
+
+    let region = TileRegion {
+        min: key(&args, "min")?.unwrap_or(IVec2::ZERO),
+        max: key(&args, "max")?.unwrap_or(tiles.extent - IVec2::ONE),
+    };
+    if region.tiles().cmple(IVec2::ZERO).any() {
+        return Err(CliError::Usage(format!("--min {} is past --max {}", region.min, region.max)));
+    }
+
+    let hydraulic = HydraulicErosion::default();
+    let thermal = ThermalErosion::default();
+    let (use_hydraulic, use_thermal) = match args.get("method") {
+        None | Some("hydraulic") => (true, false),
+        Some("thermal") => (false, true),
+        Some("both") => (true, true),
+        Some(other) => {
+            return Err(CliError::Usage(format!("--method {}: expected hydraulic, thermal or both", other)));
+        }
+    };
+    let defaults = ErosionDef::default();
+    let def = ErosionDef {
+        seed: args.parse_or("seed", defaults.seed)?,
+        hydraulic: use_hydraulic
+            .then(|| -> Result<_, CliError> {
+                Ok(HydraulicErosion {
+                    droplets_per_texel: args.parse_or("droplets", hydraulic.droplets_per_texel)?,
+                    ..hydraulic
+                })
+            })
+            .transpose()?,
+        thermal: use_thermal
+            .then(|| -> Result<_, CliError> {
+                Ok(ThermalErosion {
+                    iterations: args.parse_or("iterations", thermal.iterations)?,
+                    talus_deg: args.parse_or("talus", thermal.talus_deg)?,
+                    ..thermal
+                })
+            })
+            .transpose()?,
+        border: args.parse_or("border", defaults.border)?,
+    };
+
+    let hm = &manifest.heightmaps;
+    if in_place && (hm.format != HeightFormat::Raw16Le || hm.ext != ".r16") {
+        return Err(CliError::Usage(format!(
+            "--in-place needs a Raw16Le map with '.r16' tiles (this one is {:?} '{}'); use --out",
+            hm.format, hm.ext
+        )));
+    }
+    let out_folder = match (in_place, args.get("out")) {
+        (true, _) => cache.folder.clone(),
+        (false, Some(o)) => PathBuf::from(o),
+        (false, None) => assets.join(format!("{}_eroded", hm.folder)),
+    };
+    let mut namer = HeightTileCache::new(&out_folder, hm.resolution);
+    namer.filename_prefix = hm.prefix.clone();
+    namer.filename_ext = ".r16".to_string();
+
+    println!(
+        "eroding tiles ({}, {})..=({}, {}) with seed {}",
+        region.min.x, region.min.y, region.max.x, region.max.y, def.seed
+    );
+    let eroded = erode_region(region, &def, &data, &cache)?;
+
+    // FNV-1a over the written texels, in tile order
+    let mut checksum: u64 = 0xCBF2_9CE4_8422_2325;
+    let mut deltas_removed = 0usize;
+    for ((cx, cz), tile) in &eroded {
+        let path = namer.tile_path(*cx, *cz);
+        let bytes = HeightFormat::Raw16Le.encode(&path, tile)?;
+        for b in &bytes {
+            checksum = (checksum ^ *b as u64).wrapping_mul(0x0000_0100_0000_01B3);
+        }
+        write_file(&path, &bytes)?;
+        namer.remove_mip_files(*cx, *cz)?;
+
+        // The tiles were read with their edits merged, so the edits are in the new file;
+        // a .delta left next to it would be applied a second time
+        if in_place {
+            let delta = cache.delta_path(*cx, *cz);
+            match std::fs::remove_file(&delta) {
+                Ok(()) => deltas_removed += 1,
+                Err(e) if e.kind() == std::io::ErrorKind::NotFound => {}
+                Err(source) => return Err(CliError::Io { path: delta, source }),
+            }
+        }
+    }
+
+    println!(
+        "wrote {} eroded RAW16 tiles to {} (checksum {:016x}{})",
+        eroded.len(),
+        out_folder.display(),
+        checksum,
+        if deltas_removed > 0 { format!(", {} .delta files baked in and removed", deltas_removed) } else { String::new() }
+    );
+    if !in_place && out_folder != cache.folder {
+        println!("tiles outside the region weren't written; copy them over before pointing heightmaps.folder there");
+    }
+    Ok(())
+}
+
+/// `--name CX,CZ` tile key.
+fn key(args: &Args, name: &str) -> Result<Option<IVec2>, CliError> {
+    let Some(v) = args.get(name) else { return Ok(None) };
+    let bad = || CliError::Usage(format!("--{}: expected CX,CZ, got '{}'", name, v));
+    let (x, z) = v.split_once(',').ok_or_else(bad)?;
+    Ok(Some(IVec2::new(
+        x.trim().parse().map_err(|_| bad())?,
+        z.trim().parse().map_err(|_| bad())?,
+    )))
+}
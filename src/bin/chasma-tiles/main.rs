@@ -0,0 +1,159 @@
+// src/bin/chasma-tiles/main.rs
+//! `chasma-tiles`: offline tools that turn source terrain into the tile layout the game streams.
+//!
+//!     cargo run --release --bin chasma-tiles -- slice --height world.r16 --height-size 4097 \
+//!         --color world.png --name Chasma --tile-res 257 --mips 2
+//!     cargo run --release --bin chasma-tiles -- validate --manifest assets/terrain/chasma.terrain.ron --fix
+//!     cargo run --release --bin chasma-tiles -- bake-edits --manifest assets/terrain/chasma.terrain.ron --in-place
+//!     cargo run --release --bin chasma-tiles -- bake-meshes --manifest assets/terrain/chasma.terrain.ron
+//!     cargo run --release --bin chasma-tiles -- erode --manifest assets/terrain/chasma.terrain.ron --min 4,4 --max 5,5
+
+mod args;
+mod bake;
+mod erode;
+mod meshes;
+mod slice;
+mod validate;
+
+use std::path::{Path, PathBuf};
+use std::process::ExitCode;
+
+use chasma::heightmap_data::{HeightTileCache, HeightTileError, TileSource};
+use chasma::props::core::DEFAULT_WORLD_SEED;
+use chasma::terrain::TerrainManifest;
+
+const USAGE: &str = "\
+usage: chasma-tiles <command> [options]
+
+commands:
+  slice     Cut one large heightmap + color image into tiles and write a terrain manifest
+              --height <file>        RAW16 little-endian (.r16/.raw/.raw16), 16-bit PNG or TIFF
+              --height-size <WxH>    RAW input size (default: infer a square from the file size)
+              --color <file>         Color image covering the same area (any size)
+              --out <dir>            Assets root to write into (default: assets)
+              --name <name>          Map name; the manifest becomes terrain/<name>.terrain.ron
+              --tile-res <N>         Texels per tile edge incl. the shared border (default: 1025)
+              --color-res <N>        Pixels per color tile edge (default: tile-res - 1)
+              --chunk-size <m>       World size of one tile in meters (default: 256)
+              --height-scale <m>     Meters between raw 0 and 65535 (default: 600)
+              --water-level <m>      (default: 0)
+              --mips <N>             Also write N half-resolution levels to <height-folder>/mip<k>/
+              --height-folder <dir>  (default: Heightmaps)
+              --color-folder <dir>   (default: Textures)
+  validate  Check that neighbouring height tiles agree on their shared edges
+              --manifest <file>      Terrain manifest (*.terrain.ron)
+              --assets <dir>         Asset root the manifest paths are relative to
+                                     (default: the folder above the manifest's terrain/ folder)
+              --tolerance <raw>      Allowed edge mismatch in raw units (default: 0)
+              --fix                  Average mismatching edges and rewrite the tiles in place
+  bake-edits  Merge saved runtime edits (.delta files) into full RAW16 tiles
+              --manifest <file>      Terrain manifest (*.terrain.ron)
+              --assets <dir>         Asset root (default: as for validate)
+              --out <dir>            Write every tile here (default: <assets>/<height-folder>_baked)
+              --in-place             Rewrite only edited tiles in place and delete their .delta files
+  bake-meshes Build every chunk mesh into the manifest's mesh_cache folder (current ones are kept)
+              --manifest <file>      Terrain manifest (*.terrain.ron)
+              --assets <dir>         Asset root (default: as for validate)
+              --lod <near|mid|far>   Only this LoD (default: all three)
+  erode     Erode a rectangle of tiles and write them as RAW16 (same seed, same output)
+              --manifest <file>      Terrain manifest (*.terrain.ron)
+              --assets <dir>         Asset root (default: as for validate)
+              --min <cx,cz>          First tile of the region (default: 0,0)
+              --max <cx,cz>          Last tile of the region (default: the map's last tile)
+              --method <m>           hydraulic, thermal or both (default: hydraulic)
+              --seed <N>             (default: the default world seed)
+              --droplets <n>         Hydraulic droplets per texel (default: 0.25)
+              --iterations <N>       Thermal passes (default: 50)
+              --talus <deg>          Thermal talus angle (default: 35)
+              --border <m>           Fade-out width at the region's edge (default: 32)
+              --out <dir>            Write the tiles here (default: <assets>/<height-folder>_eroded)
+              --in-place             Overwrite the region's tiles
+  help      Show this message
+";
+
+#[derive(thiserror::Error, Debug)]
+pub enum CliError {
+    #[error("{0}")]
+    Usage(String),
+    #[error("{}: {source}", path.display())]
+    Io {
+        path: PathBuf,
+        #[source]
+        source: std::io::Error,
+    },
+    #[error("{}: {source}", path.display())]
+    Image {
+        path: PathBuf,
+        #[source]
+        source: image::ImageError,
+    },
+    #[error(transparent)]
+    Tile(#[from] HeightTileError),
+    #[error("manifest: {0}")]
+    Manifest(String),
+    #[error("{0}")]
+    Failed(String),
+}
+
+fn main() -> ExitCode {
+    let mut raw = std::env::args().skip(1);
+    let result = match raw.next().as_deref() {
+        Some("slice") => slice::run(raw),
+        Some("validate") => validate::run(raw),
+        Some("bake-edits") => bake::run(raw),
+        Some("bake-meshes") => meshes::run(raw),
+        Some("erode") => erode::run(raw),
+        None | Some("help" | "-h" | "--help") => {
+            print!("{}", USAGE);
+            return ExitCode::SUCCESS;
+        }
+        Some(other) => Err(CliError::Usage(format!("unknown command '{}'", other))),
+    };
+
+    match result {
+        Ok(()) => ExitCode::SUCCESS,
+        Err(e) => {
+            eprintln!("error: {}", e);
+            if matches!(e, CliError::Usage(_)) {
+                eprint!("\n{}", USAGE);
+            }
+            ExitCode::FAILURE
+        }
+    }
+}
+
+/// Write `bytes` to `path`, creating parent folders.
+pub fn write_file(path: &Path, bytes: &[u8]) -> Result<(), CliError> {
+    create_parent(path)?;
+    std::fs::write(path, bytes).map_err(|source| CliError::Io { path: path.to_path_buf(), source })
+}
+
+pub fn create_parent(path: &Path) -> Result<(), CliError> {
+    match path.parent() {
+        Some(dir) if !dir.as_os_str().is_empty() => std::fs::create_dir_all(dir)
+            .map_err(|source| CliError::Io { path: dir.to_path_buf(), source }),
+        _ => Ok(()),
+    }
+}
+
+/// Read a manifest from disk and open its height tiles straight from the filesystem.
+/// `assets` is the asset root the manifest's paths are relative to.
+pub fn open_map(manifest_path: &Path, assets: &Path) -> Result<(TerrainManifest, HeightTileCache), CliError> {
+    let manifest = TerrainManifest::from_file(manifest_path)
+        .map_err(|e| CliError::Manifest(format!("{}: {}", manifest_path.display(), e)))?;
+
+    let mut on_disk = manifest.clone();
+    on_disk.heightmaps.folder = assets.join(&manifest.heightmaps.folder).to_string_lossy().into_owned();
+    let cache = on_disk.tile_cache(TileSource::Fs, DEFAULT_WORLD_SEED)?;
+    Ok((manifest, cache))
+}
+
+/// `assets/terrain/chasma.terrain.ron` -> `assets`.
+pub fn default_assets_root(manifest_path: &Path) -> PathBuf {
+    manifest_path
+        .parent()
+        .and_then(Path::parent)
+        .filter(|p| !p.as_os_str().is_empty())
+        .map(Path::to_path_buf)
+        .unwrap_or_else(|| PathBuf::from("."))
+}
@@ -0,0 +1,62 @@
+// src/bin/chasma-tiles/meshes.rs
+//! `bake-meshes`: build every chunk mesh of a map into its `mesh_cache` folder, so the game
+//! loads them instead of meshing at runtime. Entries that are already current are kept.
+
+use std::path::PathBuf;
+
+use chasma::terrain::{ChunkMeshCache, LodLevel};
+
+use crate::args::Args;
+use crate::{default_assets_root, open_map, CliError};
+
+pub fn run(raw: impl Iterator<Item = String>) -> Result<(), CliError> {
+    let args = Args::parse(raw, &[])?;
+
+    let manifest_path = PathBuf::from(args.required("manifest")?);
+    let assets = match args.get("assets") {
+        Some(a) => PathBuf::from(a),
+        None => default_assets_root(&manifest_path),
+    };
+    let lods = match args.get("lod") {
+        None => vec![LodLevel::Near, LodLevel::Mid, LodLevel::Far],
+        Some("near") => vec![LodLevel::Near],
+        Some("mid") => vec![LodLevel::Mid],
+        Some("far") => vec![LodLevel::Far],
+        Some(other) => return Err(CliError::Usage(format!("--lod {}: expected near, mid or far", other))),
+    };
+
+    let (manifest, cache) = open_map(&manifest_path, &assets)?;
+    let Some(folder) = &manifest.mesh_cache else {
+        return Err(CliError::Manifest(format!(
+            "{} has no mesh_cache folder to bake into",
+            manifest_path.display()
+        )));
+    };
+    if manifest.quadtree.is_some() {
+        println!("note: this map streams as a quadtree, which doesn't use cached chunk meshes");
+    }
+    let Some(tiles) = cache.tile_set.clone() else {
+        return Err(CliError::Manifest("tile set could not be resolved".into()));
+    };
+    let data = manifest.heightmap_data(&tiles);
+    let mesh_cache = ChunkMeshCache::new(assets.join(folder), cache.source.clone());
+
+    let (mut written, mut current) = (0usize, 0usize);
+    for (cx, cz) in tiles.keys() {
+        for &lod in &lods {
+            if mesh_cache.bake(cx, cz, lod, manifest.mesher, &data, &cache)? {
+                written += 1;
+            } else {
+                current += 1;
+            }
+        }
+    }
+
+    println!(
+        "baked {} chunk meshes into {} ({} already current)",
+        written,
+        mesh_cache.folder.display(),
+        current
+    );
+    Ok(())
+}
@@ -0,0 +1,240 @@
+// src/bin/chasma-tiles/slice.rs
+//! `slice`: cut one large heightmap (+ color image) into per-chunk tiles and write a manifest.
+//!
+//! Tiles share their edge texels: tile (cx, cz) covers source texels
+//! `[cx * (res - 1), cx * (res - 1) + res)`, so its last column is its right neighbour's
+//! column 0 and chunk seams line up exactly.
+
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+
+use bevy::math::{UVec2, Vec2};
+use image::{Rgb, RgbImage};
+
+use chasma::heightmap_data::{HeightTileCache, Tile16};
+use chasma::terrain::{HeightFormat, HeightTilesDef, TerrainManifest, TileLayout};
+
+use crate::args::Args;
+use crate::{create_parent, write_file, CliError};
+
+pub fn run(raw: impl Iterator<Item = String>) -> Result<(), CliError> {
+    let args = Args::parse(raw, &[])?;
+
+    let height_path = PathBuf::from(args.required("height")?);
+    let color_path = PathBuf::from(args.required("color")?);
+    let out = PathBuf::from(args.get("out").unwrap_or("assets"));
+    let name = match args.get("name") {
+        Some(n) => n.to_string(),
+        None => height_path
+            .file_stem()
+            .and_then(|s| s.to_str())
+            .unwrap_or("map")
+            .to_string(),
+    };
+
+    let tile_res: u32 = args.parse_or("tile-res", 1025)?;
+    if tile_res < 3 {
+        return Err(CliError::Usage("--tile-res must be at least 3".into()));
+    }
+    let color_res: u32 = args.parse_or("color-res", tile_res - 1)?;
+    let chunk_size: f32 = args.parse_or("chunk-size", 256.0)?;
+    let height_scale: f32 = args.parse_or("height-scale", 600.0)?;
+    let water_level: f32 = args.parse_or("water-level", 0.0)?;
+    let mips: u32 = args.parse_or("mips", 0)?;
+    let step = tile_res - 1;
+    if mips > 0 && (mips >= 31 || step >> mips == 0) {
+        return Err(CliError::Usage(format!(
+            "--mips {}: a {}-texel tile can't be halved {} times",
+            mips, tile_res, mips
+        )));
+    }
+
+    // ---- Source ----
+    let src = read_height(&height_path, args.size("height-size")?)?;
+    let tiles = UVec2::new(
+        (src.res.x - 1).div_ceil(step).max(1),
+        (src.res.y - 1).div_ceil(step).max(1),
+    );
+    println!(
+        "{}: {}x{} texels -> {}x{} tiles of {}x{}",
+        height_path.display(),
+        src.res.x,
+        src.res.y,
+        tiles.x,
+        tiles.y,
+        tile_res,
+        tile_res
+    );
+    if tiles * step + 1 != src.res {
+        println!("note: the source doesn't divide evenly; the last row/column of tiles repeats the edge texels");
+    }
+
+    let manifest = TerrainManifest {
+        name: name.clone(),
+        heightmaps: HeightTilesDef {
+            folder: args.get("height-folder").unwrap_or("Heightmaps").to_string(),
+            prefix: "Heightmap".to_string(),
+            ext: ".r16".to_string(),
+            format: HeightFormat::Raw16Le,
+            resolution: UVec2::splat(tile_res),
+            mips,
+            mip_cache: true,
+        },
+        tiles: TileLayout::Grid(tiles),
+        void_height: 0.0,
+        chunk_size: Vec2::splat(chunk_size),
+        origin: Vec2::ZERO,
+        height_scale,
+        raw_min: 0.0,
+        raw_max: 65535.0,
+        water_level,
+        color_tiles: format!(
+            "{}/Texture_y{{cz}}_x{{cx}}.png",
+            args.get("color-folder").unwrap_or("Textures")
+        ),
+        tile_cache_budget_mb: 128,
+        edit_layer: true,
+        mesher: Default::default(),
+        quadtree: None,
+        mesh_cache: Some("MeshCache".to_string()),
+        procedural: None,
+        biomes: None,
+        hydrology: None,
+        analysis_spacing: 1.0,
+    };
+
+    // ---- Height tiles (+ mips) ----
+    // The cache is only used for its file naming, so the tiles land exactly where the game looks.
+    let hm = &manifest.heightmaps;
+    let mut namer = HeightTileCache::new(out.join(&hm.folder), hm.resolution);
+    namer.filename_prefix = hm.prefix.clone();
+    namer.filename_ext = hm.ext.clone();
+    namer.set_mip_levels(mips);
+    write_height_tiles(&src, tiles, tile_res, &namer)?;
+    println!("wrote {} height tiles to {}", tiles.x * tiles.y, namer.folder.display());
+    for (k, mip) in namer.mips.iter().enumerate() {
+        let res = mip.tile_resolution.x;
+        println!("wrote mip {} ({}x{} per tile) to {}", k + 1, res, res, mip.folder.display());
+    }
+
+    let src_res = src.res;
+
+    // ---- Color tiles ----
+    let color = image::open(&color_path)
+        .map_err(|source| CliError::Image { path: color_path.clone(), source })?
+        .to_rgb8();
+    write_color_tiles(&color, src_res, tiles, step, color_res, &manifest, &out)?;
+    println!("wrote {} color tiles ({}x{})", tiles.x * tiles.y, color_res, color_res);
+
+    // ---- Manifest ----
+    let file_stem = name.to_lowercase().replace(' ', "_");
+    let manifest_rel = format!("terrain/{}.terrain.ron", file_stem);
+    let text = ron::ser::to_string_pretty(&manifest, ron::ser::PrettyConfig::default())
+        .map_err(|e| CliError::Manifest(e.to_string()))?;
+    write_file(&out.join(&manifest_rel), text.as_bytes())?;
+    println!(
+        "wrote {}; point TerrainSettings::manifest_path at \"{}\"",
+        out.join(&manifest_rel).display(),
+        manifest_rel
+    );
+
+    Ok(())
+}
+
+/// Decode the source heightmap with the same `HeightFormat` code the game uses for tiles.
+fn read_height(path: &Path, size: Option<(u32, u32)>) -> Result<Tile16, CliError> {
+    let ext = path
+        .extension()
+        .and_then(|e| e.to_str())
+        .unwrap_or("")
+        .to_ascii_lowercase();
+    let format = match ext.as_str() {
+        "r16" | "raw" | "raw16" => HeightFormat::Raw16Le,
+        "png" => HeightFormat::Png16,
+        "tif" | "tiff" => HeightFormat::Tiff,
+        _ => {
+            return Err(CliError::Usage(format!(
+                "--height: unsupported extension '.{}' (expected .r16/.raw/.raw16, .png or .tif)",
+                ext
+            )))
+        }
+    };
+    let bytes = std::fs::read(path).map_err(|source| CliError::Io { path: path.to_path_buf(), source })?;
+    Ok(format.decode(path, &bytes, size.map(|(w, h)| UVec2::new(w, h)))?)
+}
+
+/// Cut `src` into `tiles` RAW16 tiles of `res` x `res` texels (edges clamp past the source),
+/// plus their mips into `namer.mips`. Mips are downsampled per tile (`Tile16::downsample`),
+/// exactly as the game generates missing ones; older mip files of a tile are deleted.
+fn write_height_tiles(src: &Tile16, tiles: UVec2, res: u32, namer: &HeightTileCache) -> Result<(), CliError> {
+    let step = (res - 1) as i32;
+    for cz in 0..tiles.y as i32 {
+        for cx in 0..tiles.x as i32 {
+            let mut data = Vec::with_capacity((res * res) as usize);
+            for j in 0..res as i32 {
+                for i in 0..res as i32 {
+                    data.push(src.get_clamped(cx * step + i, cz * step + j));
+                }
+            }
+            let mut tile = Tile16 { res: UVec2::splat(res), data: Arc::new(data) };
+            write_file(&namer.tile_path(cx, cz), &raw16_le(&tile))?;
+            namer.remove_mip_files(cx, cz)?;
+            for mip in &namer.mips {
+                tile = tile.downsample();
+                write_file(&mip.tile_path(cx, cz), &raw16_le(&tile))?;
+            }
+        }
+    }
+    Ok(())
+}
+
+fn raw16_le(tile: &Tile16) -> Vec<u8> {
+    tile.data.iter().flat_map(|v| v.to_le_bytes()).collect()
+}
+
+/// Resample the color image per tile. The color image and the heightmap cover the same
+/// world rectangle, so positions are matched through height texel space.
+fn write_color_tiles(
+    color: &RgbImage,
+    height_res: UVec2,
+    tiles: UVec2,
+    step: u32,
+    color_res: u32,
+    manifest: &TerrainManifest,
+    out: &Path,
+) -> Result<(), CliError> {
+    let px_per_texel = Vec2::new(
+        color.width() as f32 / (height_res.x - 1).max(1) as f32,
+        color.height() as f32 / (height_res.y - 1).max(1) as f32,
+    );
+    for cz in 0..tiles.y {
+        for cx in 0..tiles.x {
+            let tile = RgbImage::from_fn(color_res, color_res, |i, j| {
+                let hx = (cx * step) as f32 + (i as f32 + 0.5) / color_res as f32 * step as f32;
+                let hz = (cz * step) as f32 + (j as f32 + 0.5) / color_res as f32 * step as f32;
+                sample_bilinear(color, hx * px_per_texel.x - 0.5, hz * px_per_texel.y - 0.5)
+            });
+            let path = out.join(manifest.color_tile_path(cx as i32, cz as i32));
+            create_parent(&path)?;
+            tile.save(&path)
+                .map_err(|source| CliError::Image { path: path.clone(), source })?;
+        }
+    }
+    Ok(())
+}
+
+fn sample_bilinear(img: &RgbImage, x: f32, y: f32) -> Rgb<u8> {
+    let (max_x, max_y) = (img.width() as i32 - 1, img.height() as i32 - 1);
+    let px = |x: i32, y: i32| img.get_pixel(x.clamp(0, max_x) as u32, y.clamp(0, max_y) as u32).0;
+
+    let (x0, y0) = (x.floor(), y.floor());
+    let (fx, fy) = (x - x0, y - y0);
+    let (x0, y0) = (x0 as i32, y0 as i32);
+    let (a, b, c, d) = (px(x0, y0), px(x0 + 1, y0), px(x0, y0 + 1), px(x0 + 1, y0 + 1));
+
+    Rgb(std::array::from_fn(|k| {
+        let top = a[k] as f32 * (1.0 - fx) + b[k] as f32 * fx;
+        let bottom = c[k] as f32 * (1.0 - fx) + d[k] as f32 * fx;
+        (top * (1.0 - fy) + bottom * fy).round() as u8
+    }))
+}
@@ -0,0 +1,89 @@
+// src/bin/chasma-tiles/validate.rs
+//! `validate`: check that neighbouring height tiles agree on their shared edges,
+//! and list tiles that are missing, truncated or undecodable.
+
+use std::path::PathBuf;
+
+use chasma::terrain::{fix_tile_seams, validate_tile_seams, SeamAxis, SeamValidation, TerrainManifest};
+
+use crate::args::Args;
+use crate::{default_assets_root, open_map, CliError};
+
+pub fn run(raw: impl Iterator<Item = String>) -> Result<(), CliError> {
+    let args = Args::parse(raw, &["fix"])?;
+
+    let manifest_path = PathBuf::from(args.required("manifest")?);
+    let assets = match args.get("assets") {
+        Some(a) => PathBuf::from(a),
+        None => default_assets_root(&manifest_path),
+    };
+    let tolerance: u16 = args.parse_or("tolerance", 0)?;
+
+    let (manifest, mut cache) = open_map(&manifest_path, &assets)?;
+    // Check (and fix) the exported tiles themselves, not tiles + saved runtime edits
+    cache.edits = None;
+    let Some(tiles) = cache.tile_set.clone() else {
+        return Err(CliError::Manifest("tile set could not be resolved".into()));
+    };
+
+    let mut report = validate_tile_seams(&cache, &tiles);
+    print_report(&report, tolerance, &manifest);
+
+    if args.flag("fix") && report.cracked(tolerance).next().is_some() {
+        let rewritten = fix_tile_seams(&cache, &tiles)?;
+        println!("\n--fix: averaged shared edges, rewrote {} tiles", rewritten);
+        report = validate_tile_seams(&cache, &tiles);
+        print_report(&report, tolerance, &manifest);
+    }
+
+    if report.is_clean(tolerance) {
+        Ok(())
+    } else {
+        Err(CliError::Failed(format!(
+            "{} seams over tolerance, {} unreadable tiles",
+            report.cracked(tolerance).count(),
+            report.broken.len()
+        )))
+    }
+}
+
+fn print_report(report: &SeamValidation, tolerance: u16, manifest: &TerrainManifest) {
+    let span = (manifest.raw_max - manifest.raw_min).max(f32::EPSILON);
+    let meters = |raw: f32| raw / span * manifest.height_scale;
+
+    for ((cx, cz), err) in &report.broken {
+        println!("tile ({}, {}): {}", cx, cz, err);
+    }
+    for seam in report.cracked(tolerance) {
+        let (bx, bz) = seam.b();
+        let axis = match seam.axis {
+            SeamAxis::X => "x",
+            SeamAxis::Z => "z",
+        };
+        println!(
+            "seam ({}, {}) -> ({}, {}) along {}: max {} ({:.3} m), mean {:.1} ({:.3} m){}",
+            seam.a.0,
+            seam.a.1,
+            bx,
+            bz,
+            axis,
+            seam.max_mismatch,
+            meters(seam.max_mismatch as f32),
+            seam.mean_mismatch,
+            meters(seam.mean_mismatch),
+            if seam.length_mismatch { ", edge lengths differ" } else { "" }
+        );
+    }
+
+    let worst = report.seams.iter().map(|s| s.max_mismatch).max().unwrap_or(0);
+    println!(
+        "{} tiles, {} seams checked: {} over tolerance {}, {} unreadable tiles, worst mismatch {} ({:.3} m)",
+        report.tiles_checked,
+        report.seams.len(),
+        report.cracked(tolerance).count(),
+        tolerance,
+        report.broken.len(),
+        worst,
+        meters(worst as f32)
+    );
+}
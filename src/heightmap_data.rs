@@ -1,10 +1,18 @@
+use bevy::asset::io::{AssetReaderError, AssetWriterError, Reader};
+use bevy::asset::{AssetPath, UntypedHandle};
 use bevy::math::{UVec2, Vec2, Vec3};
 use bevy::prelude::*;
-use std::collections::HashMap;
-use std::fs::File;
-use std::io::{Read, Seek, SeekFrom};
+use bevy::tasks::{IoTaskPool, TaskPool};
+use futures_lite::future::block_on;
+use futures_lite::StreamExt;
+use std::collections::{HashMap, HashSet};
 use std::path::{Path, PathBuf};
-use std::sync::Arc;
+use std::sync::{Arc, Mutex, MutexGuard, OnceLock};
+
+use crate::terrain::{
+    sample_terrain, AnalysisScale, GeneratedTiles, HeightFormat, HeightTile, HeightTileLoaderSettings, ProceduralMode,
+    TerrainAttributes, TileAnalysis, TileDelta, TileEditLayer,
+};
 
 /// Global terrain metadata
 #[derive(Resource, Clone)]
@@ -14,6 +22,8 @@ pub struct HeightmapData {
     pub height_scale: f32,
     pub chunk_size: Vec2,
     pub raw_minmax: (f32, f32),
+    /// Height (meters) reported where no tile exists (sparse maps: ocean floor / void).
+    pub void_height: f32,
 }
 
 impl Default for HeightmapData {
@@ -24,6 +34,7 @@ impl Default for HeightmapData {
             height_scale: 1.0,
             chunk_size: Vec2::splat(1.0),
             raw_minmax: (0.0, 65535.0),
+            void_height: 0.0,
         }
     }
 }
@@ -42,30 +53,467 @@ impl Tile16 {
         let yi = y.clamp(0, self.res.y as i32 - 1) as u32;
         self.data[(yi * self.res.x + xi) as usize]
     }
+
+    /// FNV-1a over the resolution and texels: stable across builds and platforms, so it can
+    /// key on-disk caches. Caches compute it once per resident tile (`tile_hash`).
+    pub fn content_hash(&self) -> u64 {
+        let mut h: u64 = 0xcbf2_9ce4_8422_2325;
+        let mut eat = |bytes: &[u8]| {
+            for &b in bytes {
+                h = (h ^ b as u64).wrapping_mul(0x0100_0000_01b3);
+            }
+        };
+        eat(&self.res.x.to_le_bytes());
+        eat(&self.res.y.to_le_bytes());
+        for &v in self.data.iter() {
+            eat(&v.to_le_bytes());
+        }
+        h
+    }
+
+    /// Heap bytes held by the sample buffer (what the cache budget counts).
+    #[inline]
+    pub fn byte_size(&self) -> usize {
+        self.data.len() * std::mem::size_of::<u16>()
+    }
+
+    /// Half-resolution copy with a 1-2-1 tent filter. The `res - 1` cells per side become
+    /// `(res - 1) / 2`, so output texel `i` sits on texel `2i` when `res - 1` is even and a
+    /// little past it otherwise (e.g. 1024 -> 512; those positions are interpolated).
+    /// Border rows and columns are interpolated along the border only, never filtered
+    /// across it, so every level keeps the full tile's edge: neighbours still share edges,
+    /// and chunks built from different levels meet without cracks.
+    pub fn downsample(&self) -> Tile16 {
+        const TAPS: [(i32, f64); 3] = [(-1, 1.0), (0, 2.0), (1, 1.0)];
+
+        let step = self.res - UVec2::ONE;
+        let out_step = (step / 2).max(UVec2::ONE);
+        let res = out_step + UVec2::ONE;
+        // Output texel -> (source texel, fraction towards the next one)
+        let place = |i: u32, step: u32, out_step: u32| {
+            let num = i as u64 * step as u64;
+            ((num / out_step as u64) as i32, (num % out_step as u64) as f64 / out_step as f64)
+        };
+        let bilinear = |x: i32, fx: f64, z: i32, fz: f64| -> f64 {
+            let row = |z| {
+                let a = self.get_clamped(x, z) as f64;
+                if fx == 0.0 { a } else { a + (self.get_clamped(x + 1, z) as f64 - a) * fx }
+            };
+            let a = row(z);
+            if fz == 0.0 { a } else { a + (row(z + 1) - a) * fz }
+        };
+
+        let mut data = Vec::with_capacity((res.x * res.y) as usize);
+        for z in 0..res.y {
+            let (sz, fz) = place(z, step.y, out_step.y);
+            for x in 0..res.x {
+                let (sx, fx) = place(x, step.x, out_step.x);
+                if x == 0 || z == 0 || x == out_step.x || z == out_step.y {
+                    data.push(bilinear(sx, fx, sz, fz).round() as u16);
+                    continue;
+                }
+                let mut acc = 0.0;
+                for (dz, wz) in TAPS {
+                    for (dx, wx) in TAPS {
+                        acc += wz * wx * bilinear(sx + dx, fx, sz + dz, fz);
+                    }
+                }
+                data.push(((acc + 8.0) / 16.0).floor() as u16);
+            }
+        }
+        Tile16 { res, data: Arc::new(data) }
+    }
+}
+
+/// Why a height tile could not be produced.
+#[derive(thiserror::Error, Debug, Clone)]
+pub enum HeightTileError {
+    #[error("height tile '{}' not found", path.display())]
+    NotFound { path: PathBuf },
+    #[error("I/O while reading height tile '{}': {reason}", path.display())]
+    Io { path: PathBuf, reason: String },
+    #[error("height tile '{}' is truncated: expected {expected} bytes, got {actual}", path.display())]
+    Truncated { path: PathBuf, expected: usize, actual: usize },
+    #[error("height tile '{}' has {bytes} bytes, which is not a square RAW tile", path.display())]
+    UnknownResolution { path: PathBuf, bytes: usize },
+    #[error("could not decode height tile '{}': {reason}", path.display())]
+    Decode { path: PathBuf, reason: String },
+    #[error("could not write height tile '{}': {reason}", path.display())]
+    Write { path: PathBuf, reason: String },
+    #[error("tile ({cx}, {cz}) is not part of this map's tile set")]
+    NotInTileSet { cx: i32, cz: i32 },
+}
+
+impl HeightTileError {
+    /// True when the tile simply doesn't exist (sample the void height there)
+    /// rather than existing but being unreadable.
+    pub fn is_missing(&self) -> bool {
+        matches!(self, HeightTileError::NotFound { .. } | HeightTileError::NotInTileSet { .. })
+    }
+}
+
+/// Which tile keys a map actually has. Keys span `0..extent.x` by `0..extent.y`;
+/// sparse maps (islands, L-shapes) list only the keys that exist.
+#[derive(Clone, Debug)]
+pub struct TileSet {
+    pub extent: IVec2,
+    /// `None` = dense: every key inside `extent` is expected.
+    present: Option<Arc<HashSet<(i32, i32)>>>,
+}
+
+impl TileSet {
+    /// Every tile of an `extent.x` by `extent.y` grid.
+    pub fn full(extent: IVec2) -> Self {
+        Self { extent, present: None }
+    }
+
+    /// Exactly `keys` (negative keys are dropped). The extent is the bounding box from (0,0).
+    pub fn sparse(keys: impl IntoIterator<Item = (i32, i32)>) -> Self {
+        let present: HashSet<(i32, i32)> =
+            keys.into_iter().filter(|(x, z)| *x >= 0 && *z >= 0).collect();
+        let extent = present
+            .iter()
+            .fold(IVec2::ZERO, |e, (x, z)| e.max(IVec2::new(x + 1, z + 1)));
+        Self { extent, present: Some(Arc::new(present)) }
+    }
+
+    #[inline]
+    pub fn contains(&self, cx: i32, cz: i32) -> bool {
+        let in_extent = cx >= 0 && cz >= 0 && cx < self.extent.x && cz < self.extent.y;
+        in_extent && self.present.as_ref().is_none_or(|p| p.contains(&(cx, cz)))
+    }
+
+    pub fn is_sparse(&self) -> bool {
+        self.present.is_some()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    pub fn len(&self) -> usize {
+        match &self.present {
+            Some(p) => p.len(),
+            None => (self.extent.x.max(0) * self.extent.y.max(0)) as usize,
+        }
+    }
+
+    /// All present keys, row by row.
+    pub fn keys(&self) -> Vec<(i32, i32)> {
+        let mut out: Vec<(i32, i32)> = (0..self.extent.y)
+            .flat_map(|z| (0..self.extent.x).map(move |x| (x, z)))
+            .filter(|(x, z)| self.contains(*x, *z))
+            .collect();
+        out.sort_by_key(|(x, z)| (*z, *x));
+        out
+    }
+}
+
+/// Non-blocking view of one tile (see `HeightTileCache::request_tile`).
+#[derive(Clone)]
+pub enum TileStatus {
+    Ready(Tile16),
+    /// Being read on the IO task pool; ask again next frame.
+    Loading,
+    Unavailable(HeightTileError),
+}
+
+impl TileStatus {
+    pub fn ready(self) -> Option<Tile16> {
+        match self {
+            TileStatus::Ready(t) => Some(t),
+            _ => None,
+        }
+    }
+
+    pub fn is_loading(&self) -> bool {
+        matches!(self, TileStatus::Loading)
+    }
+}
+
+/// Where tile bytes come from.
+#[derive(Clone, Default)]
+pub enum TileSource {
+    /// `std::fs` relative to the working directory (CLI tools, headless runs).
+    #[default]
+    Fs,
+    /// Bevy asset sources: `folder` is an asset path, so `embedded://` and custom
+    /// sources work, and tiles hot-reload when the server watches for changes.
+    Assets(AssetServer),
+}
+
+impl TileSource {
+    /// Read a whole file.
+    pub fn read_bytes(&self, path: &Path) -> Result<Vec<u8>, HeightTileError> {
+        match self {
+            TileSource::Fs => std::fs::read(path).map_err(|e| match e.kind() {
+                std::io::ErrorKind::NotFound => HeightTileError::NotFound { path: path.to_path_buf() },
+                _ => HeightTileError::Io { path: path.to_path_buf(), reason: e.to_string() },
+            }),
+            TileSource::Assets(server) => {
+                let path_str = path.to_string_lossy();
+                let asset_path = AssetPath::parse(&path_str);
+                let io_err = |reason: String| HeightTileError::Io { path: path.to_path_buf(), reason };
+
+                let source = server
+                    .get_source(asset_path.source().clone())
+                    .map_err(|e| io_err(e.to_string()))?;
+                block_on(async {
+                    let mut reader = source.reader().read(asset_path.path()).await.map_err(|e| match e {
+                        AssetReaderError::NotFound(_) => HeightTileError::NotFound { path: path.to_path_buf() },
+                        other => io_err(other.to_string()),
+                    })?;
+                    let mut bytes = Vec::new();
+                    reader.read_to_end(&mut bytes).await.map_err(|e| io_err(e.to_string()))?;
+                    Ok::<_, HeightTileError>(bytes)
+                })
+            }
+        }
+    }
+
+    /// Write a whole file, creating its folder (asset sources use their writer).
+    pub fn write_bytes(&self, path: &Path, bytes: &[u8]) -> Result<(), HeightTileError> {
+        let write_err = |reason: String| HeightTileError::Write { path: path.to_path_buf(), reason };
+        match self {
+            TileSource::Fs => {
+                if let Some(dir) = path.parent() {
+                    std::fs::create_dir_all(dir).map_err(|e| write_err(e.to_string()))?;
+                }
+                std::fs::write(path, bytes).map_err(|e| write_err(e.to_string()))
+            }
+            TileSource::Assets(server) => {
+                let path_str = path.to_string_lossy();
+                let asset_path = AssetPath::parse(&path_str);
+                let source = server
+                    .get_source(asset_path.source().clone())
+                    .map_err(|e| write_err(e.to_string()))?;
+                let writer = source.writer().map_err(|e| write_err(e.to_string()))?;
+                block_on(writer.write_bytes(asset_path.path(), bytes)).map_err(|e| write_err(e.to_string()))
+            }
+        }
+    }
+
+    /// Delete a file; one that doesn't exist is fine.
+    pub fn remove_file(&self, path: &Path) -> Result<(), HeightTileError> {
+        let write_err = |reason: String| HeightTileError::Write { path: path.to_path_buf(), reason };
+        let result = match self {
+            TileSource::Fs => std::fs::remove_file(path),
+            TileSource::Assets(server) => {
+                let path_str = path.to_string_lossy();
+                let asset_path = AssetPath::parse(&path_str);
+                let source = server
+                    .get_source(asset_path.source().clone())
+                    .map_err(|e| write_err(e.to_string()))?;
+                let writer = source.writer().map_err(|e| write_err(e.to_string()))?;
+                block_on(writer.remove(asset_path.path())).map_err(|AssetWriterError::Io(e)| e)
+            }
+        };
+        match result {
+            Err(e) if e.kind() != std::io::ErrorKind::NotFound => Err(write_err(e.to_string())),
+            _ => Ok(()),
+        }
+    }
+}
+
+/// Default byte budget for resident tiles (64 tiles of 1024x1024).
+pub const DEFAULT_TILE_CACHE_BUDGET: usize = 128 * 1024 * 1024;
+
+/// Counters exposed by `HeightTileCache::stats`.
+#[derive(Clone, Copy, Debug, Default)]
+pub struct TileCacheStats {
+    pub hits: u64,
+    pub misses: u64,
+    pub evictions: u64,
+    pub resident_tiles: usize,
+    pub resident_bytes: usize,
+}
+
+/// A resident tile plus its last-use stamp for LRU eviction.
+struct CachedTile {
+    tile: Tile16,
+    /// `tile.content_hash()`, computed when the data arrived.
+    hash: u64,
+    /// Computed with the tile; `None` until first asked for after the data changed.
+    analysis: Option<Arc<TileAnalysis>>,
+    last_used: u64,
+}
+
+impl CachedTile {
+    fn byte_size(&self) -> usize {
+        self.tile.byte_size() + self.analysis.as_ref().map_or(0, |a| a.byte_size())
+    }
+}
+
+/// A load in progress; concurrent callers for the same key wait on the same cell.
+/// Holds (tile, made by the `HeightSource` rather than read from a file, its analysis,
+/// its content hash).
+type InflightLoad = Arc<OnceLock<Result<(Tile16, bool, Option<Arc<TileAnalysis>>, u64), HeightTileError>>>;
+
+/// Bookkeeping guarded by `TileStore::state`. Never held across disk IO.
+struct TileStoreState {
+    tiles: HashMap<(i32, i32), CachedTile>,
+    inflight: HashMap<(i32, i32), InflightLoad>,
+    /// Keys handed to the IO task pool by `request_tile` and not finished yet.
+    requested: HashSet<(i32, i32)>,
+    /// Negative cache: keys that failed to load (not retried until reloaded).
+    failed: HashMap<(i32, i32), HeightTileError>,
+    /// Asset handles kept alive so the asset server's watcher reports edits.
+    watched: HashMap<(i32, i32), UntypedHandle>,
+    pins: HashMap<(i32, i32), u32>,
+    /// Tiles changed at runtime (`commit_edited_tile`); each holds one pin for good.
+    edited: HashSet<(i32, i32)>,
+    /// Tiles made by `HeightTileCache::generated` instead of read from a file.
+    generated: HashSet<(i32, i32)>,
+    clock: u64,
+    resident_bytes: usize,
+    budget_bytes: usize,
+    stats: TileCacheStats,
+}
+
+impl TileStoreState {
+    /// Drop least-recently-used unpinned tiles until we fit the budget.
+    /// `keep` (the tile just touched) is never evicted, even if it alone exceeds the budget.
+    fn evict_to_budget(&mut self, keep: Option<(i32, i32)>) {
+        while self.resident_bytes > self.budget_bytes {
+            let victim = self
+                .tiles
+                .iter()
+                .filter(|(k, _)| Some(**k) != keep && !self.pins.contains_key(*k))
+                .min_by_key(|(_, e)| e.last_used)
+                .map(|(k, _)| *k);
+
+            let Some(victim) = victim else { break };
+            if let Some(entry) = self.tiles.remove(&victim) {
+                self.resident_bytes -= entry.byte_size();
+                self.watched.remove(&victim);
+                self.stats.evictions += 1;
+            }
+        }
+    }
+}
+
+/// Process-wide tile storage shared by every `HeightTileCache` clone.
+struct TileStore {
+    state: Mutex<TileStoreState>,
+}
+
+impl TileStore {
+    fn new(budget_bytes: usize) -> Self {
+        Self {
+            state: Mutex::new(TileStoreState {
+                tiles: HashMap::new(),
+                inflight: HashMap::new(),
+                requested: HashSet::new(),
+                failed: HashMap::new(),
+                watched: HashMap::new(),
+                pins: HashMap::new(),
+                edited: HashSet::new(),
+                generated: HashSet::new(),
+                clock: 0,
+                resident_bytes: 0,
+                budget_bytes,
+                stats: TileCacheStats::default(),
+            }),
+        }
+    }
+
+    fn lock(&self) -> MutexGuard<'_, TileStoreState> {
+        self.state.lock().expect("height tile store mutex poisoned")
+    }
 }
 
-/// IO + in-memory cache for RAW tiles
+/// IO + in-memory cache for height tiles (any `HeightFormat`).
+/// Cloning is cheap: all clones share one thread-safe store, so the main thread, chunk mesh
+/// tasks and placement tasks read each tile from disk once. Resident tiles are bounded by a
+/// byte budget; the least-recently-used unpinned tile is evicted first.
 #[derive(Resource, Clone)]
 pub struct HeightTileCache {
-    pub tiles: HashMap<(i32, i32), Tile16>,
+    store: Arc<TileStore>,
+    pub source: TileSource,
+    pub format: HeightFormat,
+    /// Known tile keys. `None` = unknown; every key is probed on disk.
+    pub tile_set: Option<TileSet>,
+    /// Persistent runtime edits merged into tiles as they load. `None` = edits aren't saved.
+    pub edits: Option<TileEditLayer>,
     pub folder: PathBuf,
     pub tile_resolution: UVec2,
     pub filename_prefix: String,
     pub filename_ext: String,
+    /// Downsampled copies of the map: `mips[k - 1]` holds level `k` (half the resolution of
+    /// level `k - 1`) in `{folder}/mip{k}`. See `set_mip_levels` and `try_fetch_tile_level`.
+    pub mips: Vec<HeightTileCache>,
+    /// Write mip tiles generated at runtime into their folder, so later runs just read them.
+    pub mip_disk_cache: bool,
+    /// Procedural tiles for gaps in the map, or for the whole map (see `terrain::procedural`).
+    pub generated: Option<GeneratedTiles>,
+    /// Compute each full-resolution tile's `TileAnalysis` as it loads, with this scaling.
+    /// `None` = no analysis layer. Analyses count against the byte budget.
+    pub analysis: Option<AnalysisScale>,
 }
 
 impl HeightTileCache {
     pub fn new(folder: impl AsRef<Path>, tile_resolution: UVec2) -> Self {
         Self {
-            tiles: HashMap::new(),
+            store: Arc::new(TileStore::new(DEFAULT_TILE_CACHE_BUDGET)),
+            source: TileSource::Fs,
+            format: HeightFormat::default(),
+            tile_set: None,
+            edits: None,
             folder: folder.as_ref().to_path_buf(),
             tile_resolution,
             filename_prefix: "Heightmap".to_string(),
             filename_ext: ".raw16".to_string(),
+            mips: Vec::new(),
+            mip_disk_cache: false,
+            generated: None,
+            analysis: None,
+        }
+    }
+
+    /// Set up `levels` mip levels (fewer if a tile would drop below 2 x 2 texels). Level `k`
+    /// has `(tile_resolution - 1) >> k` cells per side (see `Tile16::downsample`).
+    /// Each level is its own store sharing this cache's naming, source and tile set, with a
+    /// quarter of the byte budget of the level above. Call after those are configured.
+    pub fn set_mip_levels(&mut self, levels: u32) {
+        let step = self.tile_resolution - UVec2::ONE;
+        let budget = self.budget_bytes();
+        self.mips = (1..=levels.min(30))
+            .take_while(|k| step.min_element() >> k >= 1)
+            .map(|k| {
+                let mut mip = HeightTileCache::new(
+                    self.folder.join(format!("mip{}", k)),
+                    UVec2::new(step.x >> k, step.y >> k) + UVec2::ONE,
+                );
+                mip.source = self.source.clone();
+                mip.format = self.format;
+                mip.tile_set = self.tile_set.clone();
+                mip.filename_prefix = self.filename_prefix.clone();
+                mip.filename_ext = self.filename_ext.clone();
+                mip.set_budget_bytes((budget >> (2 * k)).max(1024 * 1024));
+                mip
+            })
+            .collect();
+    }
+
+    /// The store for mip `level` (0 = this cache); clamped to the levels that exist.
+    pub fn level(&self, level: u32) -> &HeightTileCache {
+        match level.min(self.mips.len() as u32) {
+            0 => self,
+            k => &self.mips[k as usize - 1],
         }
     }
 
-    fn tile_path(&self, cx: i32, cz: i32) -> PathBuf {
+    /// Coarsest level that still has at least `cells` texel steps across a tile.
+    pub fn level_for_cells(&self, cells: u32) -> u32 {
+        let step = self.tile_resolution.x.min(self.tile_resolution.y).saturating_sub(1);
+        let mut level = 0;
+        while (level as usize) < self.mips.len() && step >> (level + 1) >= cells {
+            level += 1;
+        }
+        level
+    }
+
+    pub fn tile_path(&self, cx: i32, cz: i32) -> PathBuf {
         let name = format!(
             "{}_y{}_x{}{}",
             self.filename_prefix, cz, cx, self.filename_ext
@@ -73,51 +521,636 @@ impl HeightTileCache {
         self.folder.join(name)
     }
 
-    fn load_raw16(&self, path: &Path) -> Option<Tile16> {
-        let expected_pixels = (self.tile_resolution.x * self.tile_resolution.y) as usize;
-        let expected_bytes = expected_pixels * 2;
+    /// Where a tile's edit delta lives: next to the tile, with a `.delta` extension.
+    pub fn delta_path(&self, cx: i32, cz: i32) -> PathBuf {
+        self.folder
+            .join(format!("{}_y{}_x{}.delta", self.filename_prefix, cz, cx))
+    }
+
+    /// Inverse of `tile_path`: parse `{prefix}_y{cz}_x{cx}{ext}` back into a key.
+    pub fn key_for_path(&self, path: &Path) -> Option<(i32, i32)> {
+        let name = path.file_name()?.to_str()?;
+        let rest = name.strip_prefix(self.filename_prefix.as_str())?;
+        let rest = rest.strip_suffix(self.filename_ext.as_str())?;
+        let (y, x) = rest.strip_prefix("_y")?.split_once("_x")?;
+        Some((x.parse().ok()?, y.parse().ok()?))
+    }
+
+    fn load_tile(&self, path: &Path) -> Result<Tile16, HeightTileError> {
+        let bytes = self.source.read_bytes(path)?;
+        self.format.decode(path, &bytes, Some(self.tile_resolution))
+    }
+
+    /// The tile's file, or the `HeightSource`'s tile where it replaces files or fills a gap.
+    /// The flag says whether the tile was generated.
+    fn load_or_generate(&self, cx: i32, cz: i32, path: &Path) -> Result<(Tile16, bool), HeightTileError> {
+        let generate = |g: &GeneratedTiles| (g.source.tile(cx, cz, self.tile_resolution), true);
+        match &self.generated {
+            Some(g) if g.mode == ProceduralMode::Replace => Ok(generate(g)),
+            Some(g) if self.tile_set.as_ref().is_some_and(|set| !set.contains(cx, cz)) => Ok(generate(g)),
+            Some(g) => match self.load_tile(path) {
+                Err(e) if e.is_missing() => Ok(generate(g)),
+                other => other.map(|t| (t, false)),
+            },
+            None => self.load_tile(path).map(|t| (t, false)),
+        }
+    }
+
+    /// Whether (cx, cz) is part of the map: in the tile set or, when gaps are generated,
+    /// anywhere inside its extent.
+    fn in_map(&self, cx: i32, cz: i32) -> bool {
+        let Some(set) = &self.tile_set else { return true };
+        let fills_gaps = self.generated.as_ref().is_some_and(|g| g.mode == ProceduralMode::FillGaps);
+        set.contains(cx, cz) || (fills_gaps && TileSet::full(set.extent).contains(cx, cz))
+    }
+
+    /// Whether the tile's mips must be derived in memory: mip files on disk describe the
+    /// tile files, not edits or generated tiles.
+    fn mips_in_memory(&self, cx: i32, cz: i32) -> bool {
+        self.generated.as_ref().is_some_and(|g| g.mode == ProceduralMode::Replace)
+            || self.store.lock().generated.contains(&(cx, cz))
+            || self.has_edits(cx, cz)
+    }
+
+    /// Source tile + its saved edit delta (read from disk the first time the key loads).
+    fn merge_edits(&self, cx: i32, cz: i32, tile: Tile16) -> Tile16 {
+        let Some(layer) = &self.edits else { return tile };
+        self.probe_edits(layer, cx, cz);
+        layer.apply(cx, cz, tile)
+    }
+
+    /// Look for the tile's `.delta` file once.
+    fn probe_edits(&self, layer: &TileEditLayer, cx: i32, cz: i32) {
+        if layer.needs_probe(cx, cz) {
+            let path = self.delta_path(cx, cz);
+            let delta = match self.source.read_bytes(&path) {
+                Ok(bytes) => TileDelta::decode(&path, &bytes, self.tile_resolution)
+                    .map_err(|e| warn!("Terrain: ignoring edit delta: {}", e))
+                    .ok(),
+                Err(e) if e.is_missing() => None,
+                Err(e) => {
+                    warn!("Terrain: ignoring edit delta: {}", e);
+                    None
+                }
+            };
+            layer.insert_probed(cx, cz, delta);
+        }
+    }
 
-        let mut f = File::open(path).ok()?;
-        if f.metadata().ok()?.len() < expected_bytes as u64 {
+    /// Whether the tile differs from its file (edited this session or has a saved delta).
+    /// Mip files on disk are derived from the file, so such tiles get theirs in memory.
+    fn has_edits(&self, cx: i32, cz: i32) -> bool {
+        self.is_edited(cx, cz)
+            || self.edits.as_ref().is_some_and(|layer| {
+                self.probe_edits(layer, cx, cz);
+                layer.has_delta(cx, cz)
+            })
+    }
+
+    /// Register the tile's path with the asset server so its file watcher reports edits
+    /// (see `reload_changed_height_tiles`). Only done when hot reload is on. The asset is
+    /// `watch_only`: the file is read once, by this cache, not again for the asset.
+    fn watch_tile(&self, path: &Path) -> Option<UntypedHandle> {
+        let TileSource::Assets(server) = &self.source else { return None };
+        if !server.watching_for_changes() {
             return None;
         }
+        let handle: Handle<HeightTile> = server.load_with_settings(
+            path.to_string_lossy().into_owned(),
+            |s: &mut HeightTileLoaderSettings| s.watch_only = true,
+        );
+        Some(handle.untyped())
+    }
+
+    pub fn fetch_tile(&self, cx: i32, cz: i32) -> Option<Tile16> {
+        self.try_fetch_tile(cx, cz).ok()
+    }
+
+    /// Like `fetch_tile`, but says why a tile is unavailable.
+    /// Failures are remembered, so a missing file is only probed (and logged) once.
+    pub fn try_fetch_tile(&self, cx: i32, cz: i32) -> Result<Tile16, HeightTileError> {
+        let key = (cx, cz);
+        if !self.in_map(cx, cz) {
+            return Err(HeightTileError::NotInTileSet { cx, cz });
+        }
+
+        // Fast path: resident. Otherwise join (or start) the in-flight load for this key.
+        let load = {
+            let mut st = self.store.lock();
+            st.clock += 1;
+            let now = st.clock;
+            if let Some(entry) = st.tiles.get_mut(&key) {
+                entry.last_used = now;
+                let tile = entry.tile.clone();
+                st.stats.hits += 1;
+                return Ok(tile);
+            }
+            if let Some(err) = st.failed.get(&key) {
+                return Err(err.clone());
+            }
+            if let Some(load) = st.inflight.get(&key) {
+                load.clone()
+            } else {
+                st.stats.misses += 1;
+                let load = InflightLoad::default();
+                st.inflight.insert(key, load.clone());
+                load
+            }
+        };
+
+        // Disk IO happens outside the lock; other callers for this key block here instead.
+        let path = self.tile_path(cx, cz);
+        let result = load
+            .get_or_init(|| {
+                self.load_or_generate(cx, cz, &path).map(|(t, generated)| {
+                    let t = self.merge_edits(cx, cz, t);
+                    let analysis = self.analysis.map(|scale| Arc::new(TileAnalysis::compute(&t, &scale)));
+                    let hash = t.content_hash();
+                    (t, generated, analysis, hash)
+                })
+            })
+            .clone();
+
+        let mut st = self.store.lock();
+        let owner = st.inflight.get(&key).is_some_and(|l| Arc::ptr_eq(l, &load));
+        if owner {
+            st.inflight.remove(&key);
+            // Failed files are watched too, so fixing one clears its failure
+            if !matches!(result, Ok((_, true, _, _))) {
+                if let Some(h) = self.watch_tile(&path) {
+                    st.watched.insert(key, h);
+                }
+            }
+            match &result {
+                Ok((t, generated, analysis, hash)) => {
+                    st.clock += 1;
+                    let now = st.clock;
+                    let entry = CachedTile { tile: t.clone(), hash: *hash, analysis: analysis.clone(), last_used: now };
+                    st.resident_bytes += entry.byte_size();
+                    st.tiles.insert(key, entry);
+                    if *generated {
+                        st.generated.insert(key);
+                    }
+                    st.evict_to_budget(Some(key));
+                }
+                Err(e) => {
+                    if e.is_missing() {
+                        debug!("Terrain: height tile ({}, {}) missing, using void height: {}", cx, cz, e);
+                    } else {
+                        warn!("Terrain: height tile ({}, {}) unavailable: {}", cx, cz, e);
+                    }
+                    st.failed.insert(key, e.clone());
+                }
+            }
+        }
+        result.map(|(t, _, _, _)| t)
+    }
+
+    /// Non-blocking fetch for main-thread systems. Resident tiles come back right away;
+    /// anything else is read on the IO task pool (once, however often it is asked for)
+    /// and reported as `Loading` until it lands in the store.
+    pub fn request_tile(&self, cx: i32, cz: i32) -> TileStatus {
+        let key = (cx, cz);
+        if !self.in_map(cx, cz) {
+            return TileStatus::Unavailable(HeightTileError::NotInTileSet { cx, cz });
+        }
+        {
+            let mut st = self.store.lock();
+            st.clock += 1;
+            let now = st.clock;
+            if let Some(entry) = st.tiles.get_mut(&key) {
+                entry.last_used = now;
+                let tile = entry.tile.clone();
+                st.stats.hits += 1;
+                return TileStatus::Ready(tile);
+            }
+            if let Some(err) = st.failed.get(&key) {
+                return TileStatus::Unavailable(err.clone());
+            }
+            if !st.requested.insert(key) {
+                return TileStatus::Loading;
+            }
+        }
+
+        let cache = self.clone();
+        IoTaskPool::get_or_init(TaskPool::new)
+            .spawn(async move {
+                // The result lands in the store (failures in the negative cache, logged there)
+                let _ = cache.try_fetch_tile(cx, cz);
+                cache.store.lock().requested.remove(&(cx, cz));
+            })
+            .detach();
+        TileStatus::Loading
+    }
+
+    /// `try_fetch_tile` at mip `level` (clamped to the levels set up). A mip tile missing
+    /// from its folder is downsampled from the level above, cached in memory and, with
+    /// `mip_disk_cache`, written to the folder for next time.
+    pub fn try_fetch_tile_level(&self, cx: i32, cz: i32, level: u32) -> Result<Tile16, HeightTileError> {
+        let level = level.min(self.mips.len() as u32);
+        if level == 0 {
+            return self.try_fetch_tile(cx, cz);
+        }
+        let mip = self.level(level);
+        let in_memory = self.mips_in_memory(cx, cz);
+        let stored = if in_memory { mip.resident_tile(cx, cz) } else { mip.try_fetch_tile(cx, cz).ok() };
+        if let Some(tile) = stored {
+            return Ok(tile);
+        }
+
+        let tile = self.try_fetch_tile_level(cx, cz, level - 1)?.downsample();
+        // Generating the level above may have generated the full tile
+        if self.mip_disk_cache && !in_memory && !self.mips_in_memory(cx, cz) {
+            if let Err(e) = mip.write_mip_file(cx, cz, &tile) {
+                warn!("Terrain: could not cache mip {} of tile ({}, {}): {}", level, cx, cz, e);
+            }
+        }
+        mip.insert_tile(cx, cz, tile.clone());
+        Ok(tile)
+    }
+
+    /// `request_tile` at mip `level`: resident tiles come back right away, anything else
+    /// is read (or generated) on the IO task pool.
+    pub fn request_tile_level(&self, cx: i32, cz: i32, level: u32) -> TileStatus {
+        let level = level.min(self.mips.len() as u32);
+        if level == 0 {
+            return self.request_tile(cx, cz);
+        }
+        if !self.in_map(cx, cz) {
+            return TileStatus::Unavailable(HeightTileError::NotInTileSet { cx, cz });
+        }
+        let mip = self.level(level);
+        if let Some(tile) = mip.resident_tile(cx, cz) {
+            return TileStatus::Ready(tile);
+        }
+        // Mips are generated from the full tile, so its failure is theirs too
+        if let Some(err) = self.store.lock().failed.get(&(cx, cz)) {
+            return TileStatus::Unavailable(err.clone());
+        }
+        if !mip.store.lock().requested.insert((cx, cz)) {
+            return TileStatus::Loading;
+        }
+
+        let cache = self.clone();
+        IoTaskPool::get_or_init(TaskPool::new)
+            .spawn(async move {
+                let _ = cache.try_fetch_tile_level(cx, cz, level);
+                cache.level(level).store.lock().requested.remove(&(cx, cz));
+            })
+            .detach();
+        TileStatus::Loading
+    }
+
+    /// Resident copy of a tile (counts as a use), without touching the disk.
+    fn resident_tile(&self, cx: i32, cz: i32) -> Option<Tile16> {
+        let mut st = self.store.lock();
+        st.clock += 1;
+        let now = st.clock;
+        let entry = st.tiles.get_mut(&(cx, cz))?;
+        entry.last_used = now;
+        let tile = entry.tile.clone();
+        st.stats.hits += 1;
+        Some(tile)
+    }
 
-        let mut buf = vec![0u8; expected_bytes];
-        f.seek(SeekFrom::Start(0)).ok()?;
-        f.read_exact(&mut buf).ok()?;
+    /// Make `tile` resident (replacing any old copy) and forget a cached failure.
+    fn insert_tile(&self, cx: i32, cz: i32, tile: Tile16) {
+        let hash = tile.content_hash();
+        let mut guard = self.store.lock();
+        let st = &mut *guard;
+        let key = (cx, cz);
+        st.failed.remove(&key);
+        st.clock += 1;
+        let now = st.clock;
+        let new_bytes = tile.byte_size();
+        match st.tiles.get_mut(&key) {
+            Some(entry) => {
+                let old_bytes = entry.byte_size();
+                entry.tile = tile;
+                entry.hash = hash;
+                entry.analysis = None;
+                entry.last_used = now;
+                st.resident_bytes = st.resident_bytes - old_bytes + new_bytes;
+            }
+            None => {
+                st.resident_bytes += new_bytes;
+                st.tiles.insert(key, CachedTile { tile, hash, analysis: None, last_used: now });
+            }
+        }
+        st.evict_to_budget(Some(key));
+    }
+
+    fn remove_tile(&self, cx: i32, cz: i32) {
+        let mut st = self.store.lock();
+        if let Some(entry) = st.tiles.remove(&(cx, cz)) {
+            st.resident_bytes -= entry.byte_size();
+        }
+    }
+
+    fn write_mip_file(&self, cx: i32, cz: i32, tile: &Tile16) -> Result<(), HeightTileError> {
+        let path = self.tile_path(cx, cz);
+        let bytes = self.format.encode(&path, tile)?;
+        self.source.write_bytes(&path, &bytes)
+    }
 
-        let data = buf
-            .chunks_exact(2)
-            .map(|b| u16::from_le_bytes([b[0], b[1]]))
-            .collect::<Vec<_>>();
+    /// Regenerate the mips of a tile whose full-resolution data just changed.
+    /// `write` also refreshes the mip files (the tile file itself changed), or deletes
+    /// them without `mip_disk_cache`, so the next run doesn't read stale ones.
+    fn rebuild_mips(&self, cx: i32, cz: i32, tile: &Tile16, write: bool) {
+        let mut tile = tile.clone();
+        for (k, mip) in self.mips.iter().enumerate() {
+            tile = tile.downsample();
+            if write {
+                let result = if self.mip_disk_cache {
+                    mip.write_mip_file(cx, cz, &tile)
+                } else {
+                    mip.source.remove_file(&mip.tile_path(cx, cz))
+                };
+                if let Err(e) = result {
+                    warn!("Terrain: could not update mip {} of tile ({}, {}): {}", k + 1, cx, cz, e);
+                }
+            }
+            mip.insert_tile(cx, cz, tile.clone());
+        }
+    }
+
+    /// Delete the tile's files in the `mip{k}` folders next to it, for tools that rewrite
+    /// the full tile: mip files are derived from it and would otherwise be read as they are.
+    /// Filesystem sources clear every `mip{k}` folder present, asset sources the levels set
+    /// up. Resident mips of the tile are dropped too.
+    pub fn remove_mip_files(&self, cx: i32, cz: i32) -> Result<(), HeightTileError> {
+        let is_mip_folder = |p: &Path| {
+            let name = p.file_name().and_then(|n| n.to_str()).and_then(|n| n.strip_prefix("mip"));
+            p.is_dir() && name.is_some_and(|k| k.parse::<u32>().is_ok())
+        };
+        let folders: Vec<PathBuf> = match &self.source {
+            TileSource::Fs => match std::fs::read_dir(&self.folder) {
+                Ok(entries) => entries.filter_map(|e| e.ok().map(|e| e.path())).filter(|p| is_mip_folder(p)).collect(),
+                Err(_) => Vec::new(),
+            },
+            TileSource::Assets(_) => self.mips.iter().map(|m| m.folder.clone()).collect(),
+        };
+        let path = self.tile_path(cx, cz);
+        if let Some(name) = path.file_name() {
+            for folder in folders {
+                self.source.remove_file(&folder.join(name))?;
+            }
+        }
+        for mip in &self.mips {
+            mip.remove_tile(cx, cz);
+        }
+        Ok(())
+    }
+
+    /// The analysis of `tile`, the data this cache holds for (cx, cz). Normally computed
+    /// when the tile loaded; tiles changed since (edits, hot reload) are analysed again
+    /// here, once. `None` when the cache has no `analysis` scaling.
+    pub fn analysis_for(&self, cx: i32, cz: i32, tile: &Tile16) -> Option<Arc<TileAnalysis>> {
+        let scale = self.analysis?;
+        let key = (cx, cz);
+        let current = |e: &&mut CachedTile| Arc::ptr_eq(&e.tile.data, &tile.data);
+        if let Some(analysis) = self.store.lock().tiles.get_mut(&key).filter(current).and_then(|e| e.analysis.clone()) {
+            return Some(analysis);
+        }
+
+        // Computed outside the lock; a tile replaced meanwhile just doesn't get it
+        let analysis = Arc::new(TileAnalysis::compute(tile, &scale));
+        let mut guard = self.store.lock();
+        let st = &mut *guard;
+        if let Some(entry) = st.tiles.get_mut(&key).filter(current) {
+            if entry.analysis.is_none() {
+                entry.analysis = Some(analysis.clone());
+                st.resident_bytes += analysis.byte_size();
+                st.evict_to_budget(Some(key));
+            }
+        }
+        Some(analysis)
+    }
+
+    /// `tile.content_hash()` for a tile fetched from this cache: the stored hash while
+    /// the resident copy still holds that data, else computed.
+    pub fn tile_hash(&self, cx: i32, cz: i32, tile: &Tile16) -> u64 {
+        let current = |e: &&CachedTile| Arc::ptr_eq(&e.tile.data, &tile.data);
+        let stored = self.store.lock().tiles.get(&(cx, cz)).filter(current).map(|e| e.hash);
+        stored.unwrap_or_else(|| tile.content_hash())
+    }
 
-        Some(Tile16 {
-            res: self.tile_resolution,
-            data: Arc::new(data),
-        })
+    /// Scan `folder` for `{prefix}_y{cz}_x{cx}{ext}` files and return the keys found.
+    pub fn discover_tiles(&self) -> Result<TileSet, HeightTileError> {
+        let io_err = |reason: String| HeightTileError::Io { path: self.folder.clone(), reason };
+        let names: Vec<PathBuf> = match &self.source {
+            TileSource::Fs => std::fs::read_dir(&self.folder)
+                .map_err(|e| io_err(e.to_string()))?
+                .filter_map(|entry| entry.ok().map(|e| e.path()))
+                .collect(),
+            TileSource::Assets(server) => {
+                let folder_str = self.folder.to_string_lossy();
+                let asset_path = AssetPath::parse(&folder_str);
+                let source = server
+                    .get_source(asset_path.source().clone())
+                    .map_err(|e| io_err(e.to_string()))?;
+                block_on(async {
+                    let stream = source
+                        .reader()
+                        .read_directory(asset_path.path())
+                        .await
+                        .map_err(|e| io_err(e.to_string()))?;
+                    Ok::<_, HeightTileError>(stream.collect::<Vec<PathBuf>>().await)
+                })?
+            }
+        };
+        Ok(TileSet::sparse(names.iter().filter_map(|p| self.key_for_path(p))))
     }
 
-    fn get_or_load(&mut self, cx: i32, cz: i32) -> Option<&Tile16> {
-        if !self.tiles.contains_key(&(cx, cz)) {
-            let path = self.tile_path(cx, cz);
-            let tile = self.load_raw16(&path)?;
-            self.tiles.insert((cx, cz), tile);
+    /// Swap in new data for a tile (hot reload). Non-resident tiles are only
+    /// un-failed, so the next fetch reads the new file. Mips are regenerated either way.
+    pub fn replace_tile(&self, cx: i32, cz: i32, tile: Tile16) {
+        let edited = self.has_edits(cx, cz);
+        let tile = self.merge_edits(cx, cz, tile);
+        self.rebuild_mips(cx, cz, &tile, !edited);
+        let hash = tile.content_hash();
+        let mut guard = self.store.lock();
+        let st = &mut *guard;
+        let key = (cx, cz);
+        st.failed.remove(&key);
+        st.generated.remove(&key);
+        let new_bytes = tile.byte_size();
+        if let Some(entry) = st.tiles.get_mut(&key) {
+            let old_bytes = entry.byte_size();
+            entry.tile = tile;
+            entry.hash = hash;
+            entry.analysis = None;
+            st.resident_bytes = st.resident_bytes - old_bytes + new_bytes;
+            st.evict_to_budget(Some(key));
         }
-        self.tiles.get(&(cx, cz))
     }
 
-    pub fn fetch_tile(&mut self, cx: i32, cz: i32) -> Option<Tile16> {
-        self.get_or_load(cx, cz).cloned()
+    /// Forget a cached load failure, so the next fetch tries the file again.
+    pub fn clear_failure(&self, cx: i32, cz: i32) {
+        self.store.lock().failed.remove(&(cx, cz));
+    }
+
+    /// The tile's file changed on disk (hot reload): forget a cached failure, then read it
+    /// again on the IO task pool and swap it in with `replace_tile`.
+    pub fn reload_tile(&self, cx: i32, cz: i32) {
+        self.clear_failure(cx, cz);
+        let cache = self.clone();
+        IoTaskPool::get_or_init(TaskPool::new)
+            .spawn(async move {
+                match cache.load_tile(&cache.tile_path(cx, cz)) {
+                    Ok(tile) => cache.replace_tile(cx, cz, tile),
+                    Err(e) => warn!("Terrain: could not reload height tile ({}, {}): {}", cx, cz, e),
+                }
+            })
+            .detach();
+    }
+
+    /// Encode `tile` in this cache's format, overwrite its file and update the store.
+    /// Only filesystem sources are writable (offline tools).
+    pub fn write_tile(&self, cx: i32, cz: i32, tile: Tile16) -> Result<(), HeightTileError> {
+        let path = self.tile_path(cx, cz);
+        if !matches!(self.source, TileSource::Fs) {
+            return Err(HeightTileError::Write { path, reason: "asset sources are read-only".into() });
+        }
+        let bytes = self.format.encode(&path, &tile)?;
+        std::fs::write(&path, bytes)
+            .map_err(|e| HeightTileError::Write { path: path.clone(), reason: e.to_string() })?;
+        self.remove_mip_files(cx, cz)?;
+        self.replace_tile(cx, cz, tile);
+        Ok(())
+    }
+
+    /// Install runtime-edited data for a tile (terrain deformation). Unlike `replace_tile`
+    /// the tile becomes resident even if it wasn't, and it stays pinned for the rest of the
+    /// session: the file on disk is unchanged, so evicting it would lose the edit.
+    /// Its resident mips are dropped and regenerated in memory when next asked for.
+    pub fn commit_edited_tile(&self, cx: i32, cz: i32, tile: Tile16) {
+        for mip in &self.mips {
+            mip.remove_tile(cx, cz);
+        }
+        {
+            let mut st = self.store.lock();
+            if st.edited.insert((cx, cz)) {
+                *st.pins.entry((cx, cz)).or_insert(0) += 1;
+            }
+        }
+        self.insert_tile(cx, cz, tile);
+    }
+
+    /// True once a tile has been changed with `commit_edited_tile`.
+    pub fn is_edited(&self, cx: i32, cz: i32) -> bool {
+        self.store.lock().edited.contains(&(cx, cz))
+    }
+
+    /// Write every edit delta changed since the last save next to its tile.
+    /// Returns how many were written.
+    pub fn save_edits(&self) -> Result<usize, HeightTileError> {
+        let Some(layer) = &self.edits else { return Ok(0) };
+        let unsaved = layer.take_unsaved();
+        for (i, ((cx, cz), delta)) in unsaved.iter().enumerate() {
+            if let Err(e) = self.source.write_bytes(&self.delta_path(*cx, *cz), &delta.encode()) {
+                // Keep the rest for the next attempt
+                layer.mark_unsaved(unsaved[i..].iter().map(|(k, _)| *k));
+                return Err(e);
+            }
+        }
+        Ok(unsaved.len())
+    }
+
+    pub fn budget_bytes(&self) -> usize {
+        self.store.lock().budget_bytes
+    }
+
+    /// Change the byte budget, evicting immediately if we are now over it.
+    pub fn set_budget_bytes(&self, budget_bytes: usize) {
+        let mut st = self.store.lock();
+        st.budget_bytes = budget_bytes;
+        st.evict_to_budget(None);
+    }
+
+    /// Protect a tile from eviction (ref-counted; pinning a non-resident key is fine).
+    pub fn pin_tile(&self, cx: i32, cz: i32) {
+        *self.store.lock().pins.entry((cx, cz)).or_insert(0) += 1;
+    }
+
+    /// Release one pin taken with `pin_tile`.
+    pub fn unpin_tile(&self, cx: i32, cz: i32) {
+        let mut st = self.store.lock();
+        if let Some(n) = st.pins.get_mut(&(cx, cz)) {
+            *n -= 1;
+            if *n == 0 {
+                st.pins.remove(&(cx, cz));
+                st.evict_to_budget(None);
+            }
+        }
+    }
+
+    pub fn is_resident(&self, cx: i32, cz: i32) -> bool {
+        self.store.lock().tiles.contains_key(&(cx, cz))
+    }
+
+    pub fn stats(&self) -> TileCacheStats {
+        let st = self.store.lock();
+        TileCacheStats {
+            resident_tiles: st.tiles.len(),
+            resident_bytes: st.resident_bytes,
+            ..st.stats
+        }
     }
 }
 
-/// Bilinear height sampling in world space
+/// Bilinear height sampling in world space. Reads the tile from disk if it isn't resident,
+/// so main-thread systems should use `sample_height_ready` instead.
 pub fn sample_height(
     world_x: f32,
     world_z: f32,
     data: &HeightmapData,
-    cache: &mut HeightTileCache,
+    cache: &HeightTileCache,
+) -> Option<f32> {
+    let ((cx, cz), uv) = locate_in_tile(world_x, world_z, data)?;
+    match cache.try_fetch_tile(cx, cz) {
+        Ok(tile) => Some(height_in_tile(&tile, uv, data)),
+        Err(e) if e.is_missing() => Some(data.void_height),
+        Err(_) => None,
+    }
+}
+
+/// `sample_height` from the coarsest mip whose texels are at most `spacing` meters apart
+/// (distant queries, coarse meshes). Blocking, like `sample_height`.
+pub fn sample_height_lod(
+    world_x: f32,
+    world_z: f32,
+    spacing: f32,
+    data: &HeightmapData,
+    cache: &HeightTileCache,
+) -> Option<f32> {
+    let ((cx, cz), uv) = locate_in_tile(world_x, world_z, data)?;
+    let cells = (data.chunk_size.x.min(data.chunk_size.y) / spacing.max(f32::EPSILON)).ceil() as u32;
+    match cache.try_fetch_tile_level(cx, cz, cache.level_for_cells(cells)) {
+        Ok(tile) => Some(height_in_tile(&tile, uv, data)),
+        Err(e) if e.is_missing() => Some(data.void_height),
+        Err(_) => None,
+    }
+}
+
+/// Non-blocking `sample_height`: `None` while the tile is still loading (it has been
+/// requested on the IO task pool), off the map, or when the tile is unreadable.
+pub fn sample_height_ready(
+    world_x: f32,
+    world_z: f32,
+    data: &HeightmapData,
+    cache: &HeightTileCache,
 ) -> Option<f32> {
+    let ((cx, cz), uv) = locate_in_tile(world_x, world_z, data)?;
+    match cache.request_tile(cx, cz) {
+        TileStatus::Ready(tile) => Some(height_in_tile(&tile, uv, data)),
+        TileStatus::Unavailable(e) if e.is_missing() => Some(data.void_height),
+        _ => None,
+    }
+}
+
+/// Tile key and normalized (u, v) inside it; `None` outside the map.
+pub(crate) fn locate_in_tile(world_x: f32, world_z: f32, data: &HeightmapData) -> Option<((i32, i32), Vec2)> {
     let lx = world_x - data.origin.x;
     let lz = world_z - data.origin.y;
 
@@ -131,9 +1164,14 @@ pub fn sample_height(
     let local_x = lx - (cx as f32 * data.chunk_size.x);
     let local_z = lz - (cz as f32 * data.chunk_size.y);
 
-    let tile = cache.fetch_tile(cx, cz)?;
     let u = (local_x / data.chunk_size.x).clamp(0.0, 1.0);
     let v = (local_z / data.chunk_size.y).clamp(0.0, 1.0);
+    Some(((cx, cz), Vec2::new(u, v)))
+}
+
+/// Bilinear height (meters) at normalized `uv` inside `tile`.
+pub(crate) fn height_in_tile(tile: &Tile16, uv: Vec2, data: &HeightmapData) -> f32 {
+    let (u, v) = (uv.x, uv.y);
 
     let max_x = (tile.res.x - 1) as i32;
     let max_y = (tile.res.y - 1) as i32;
@@ -165,10 +1203,12 @@ pub fn sample_height(
         0.0
     };
 
-    Some(norm * data.height_scale)
+    norm * data.height_scale
 }
 
-/// Adapter that satisfies HeightSampler and SlopeSampler traits
+/// Adapter that satisfies HeightSampler and SlopeSampler traits.
+/// Holds a handle to the shared tile store, so clones are cheap and tiles loaded
+/// inside a worker stay resident for everyone else.
 #[derive(Clone)]
 pub struct TerrainSampleAdapter {
     pub data: HeightmapData,
@@ -186,15 +1226,23 @@ impl TerrainSampleAdapter {
 
 impl HeightSampler for TerrainSampleAdapter {
     fn sample_height(&self, x: f32, z: f32) -> f32 {
-        sample_height(x, z, &self.data, &mut self.cache.clone()).unwrap_or(0.0)
+        sample_height(x, z, &self.data, &self.cache).unwrap_or(0.0)
     }
 }
 
 impl SlopeSampler for TerrainSampleAdapter {
+    fn attributes(&self, x: f32, z: f32) -> Option<TerrainAttributes> {
+        sample_terrain(x, z, &self.data, &self.cache)
+    }
+
     fn sample_normal(&self, x: f32, z: f32) -> Option<Vec3> {
+        if let Some(ground) = self.attributes(x, z) {
+            return Some(ground.normal());
+        }
+        // No analysis layer (or off the map): difference the heights
         let d = 0.25;
 
-        let h = |x, z| sample_height(x, z, &self.data, &mut self.cache.clone()).unwrap_or(0.0);
+        let h = |x, z| sample_height(x, z, &self.data, &self.cache).unwrap_or(0.0);
 
         let hx1 = h(x + d, z);
         let hx0 = h(x - d, z);
@@ -218,7 +1266,14 @@ pub trait SlopeSampler: Send + Sync + 'static {
     fn sample_normal(&self, x: f32, z: f32) -> Option<Vec3>;
 
     fn slope_deg(&self, x: f32, z: f32) -> Option<f32> {
-        self.sample_normal(x, z)
-            .map(|n| n.angle_between(Vec3::Y).to_degrees())
+        match self.attributes(x, z) {
+            Some(ground) => Some(ground.slope_deg),
+            None => self.sample_normal(x, z).map(|n| n.angle_between(Vec3::Y).to_degrees()),
+        }
+    }
+
+    /// Full terrain analysis (aspect, curvature, roughness), where the sampler has it.
+    fn attributes(&self, _x: f32, _z: f32) -> Option<TerrainAttributes> {
+        None
     }
 }
@@ -3,8 +3,9 @@ use bevy::prelude::*;
 use bevy::input::{mouse::MouseMotion, keyboard::KeyCode, ButtonInput};
 
 use crate::actions::{PlayerAction, ActionState};
-use crate::heightmap_data::{HeightmapData, HeightTileCache, sample_height};
+use crate::heightmap_data::{HeightmapData, HeightTileCache, sample_height_ready};
 use crate::setup::MainCamera;
+use crate::terrain::raycast;
 use crate::state::GameState;
 
 pub const MOVE_SPEED: f32 = 250.0;
@@ -52,7 +53,7 @@ pub fn camera_controller(
     mut scroll_evr: EventReader<MouseWheel>,
     action_state: Res<ActionState>,
     heightmap: Res<HeightmapData>,
-    mut cache: ResMut<HeightTileCache>,
+    cache: Res<HeightTileCache>,
     mut query: Query<(&mut Transform, &mut CameraOrbit), With<MainCamera>>,
 ) {
     // 0) Clamp delta
@@ -79,8 +80,10 @@ pub fn camera_controller(
         orbit.focus.z += delta.y;
     }
 
-    // 2) Ground the focus Y
-    orbit.focus.y = sample_height(orbit.focus.x, orbit.focus.z, &heightmap, &mut cache).unwrap_or(0.0);
+    // 2) Ground the focus Y (keeps the last height while the tile below is still loading)
+    if let Some(y) = sample_height_ready(orbit.focus.x, orbit.focus.z, &heightmap, &cache) {
+        orbit.focus.y = y;
+    }
 
     // 3) Zoom
     for ev in scroll_evr.read() {
@@ -114,10 +117,20 @@ pub fn camera_controller(
 
     tf.translation = orbit.focus + offset;
 
-    // 6) Prevent underground camera
-    let terrain_y = sample_height(tf.translation.x, tf.translation.z, &heightmap, &mut cache).unwrap_or(0.0);
-    if tf.translation.y < terrain_y + 2.5 {
-        tf.translation.y = terrain_y + 2.5;
+    // 6) Pull the camera in front of terrain that would hide the focus
+    let eye = orbit.focus + Vec3::Y;
+    if let Ok(back) = Dir3::new(tf.translation - eye) {
+        let dist = eye.distance(tf.translation);
+        if let Some(hit) = raycast(Ray3d::new(eye, back), dist, &heightmap, &cache) {
+            tf.translation = eye + back * (hit.distance - 1.0).max(0.5);
+        }
+    }
+
+    // 7) Prevent underground camera
+    if let Some(terrain_y) = sample_height_ready(tf.translation.x, tf.translation.z, &heightmap, &cache) {
+        if tf.translation.y < terrain_y + 2.5 {
+            tf.translation.y = terrain_y + 2.5;
+        }
     }
 
     tf.look_at(orbit.focus, Vec3::Y);
@@ -0,0 +1,13 @@
+// src/lib.rs
+//! Chasma as a library: the game binary (`main.rs`) and the offline tools in
+//! `src/bin/` (e.g. `chasma-tiles`) share these modules.
+
+pub mod setup;
+pub mod input;
+pub mod actions;
+pub mod state;
+pub mod ui;
+pub mod heightmap_data;
+pub mod terrain;
+pub mod unit;
+pub mod props;
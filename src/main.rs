@@ -1,25 +1,17 @@
 use bevy::prelude::*;
 use bevy::window::{Window, WindowPlugin};
 
-mod setup;
-mod input;
-mod actions;
-mod state;
-mod ui;
-mod heightmap_data;
-mod terrain;
-mod unit;
-mod props;
-
-// re-export the bits we actually need in main
-use actions::ActionState;
-use input::{camera_controller, input_mapping_system, pause_toggle_system};
-use state::GameState;
-use ui::{spawn_pause_overlay, despawn_pause_overlay};
-use terrain::TerrainPlugin;
-use unit::UnitPlugin;
-use props::PropsStackPlugin;
+// game modules live in the library crate (src/lib.rs) so the tools in src/bin can share them
+use chasma::setup;
+use chasma::actions::ActionState;
+use chasma::input::{camera_controller, input_mapping_system, pause_toggle_system};
+use chasma::state::GameState;
+use chasma::ui::{spawn_pause_overlay, despawn_pause_overlay};
+use chasma::terrain::TerrainPlugin;
+use chasma::unit::UnitPlugin;
+use chasma::props::PropsStackPlugin;
 use bevy::render::{RenderPlugin, settings::WgpuSettings};
+use chasma::heightmap_data::HeightmapData;
 
 fn main() {
         // Start with Bevy’s default settings…
@@ -63,7 +55,12 @@ fn main() {
         .add_systems(Update, pause_toggle_system)
         .add_systems(
             Update,
-            (input_mapping_system, camera_controller).run_if(in_state(GameState::Running))
+            (
+                input_mapping_system,
+                // terrain resources arrive once the map manifest has loaded
+                camera_controller.run_if(resource_exists::<HeightmapData>),
+            )
+                .run_if(in_state(GameState::Running))
         )
         .run();
 }
@@ -8,7 +8,10 @@ use crate::heightmap_data::{HeightSampler, SlopeSampler};
 
 // ---------- World, chunks, ids ----------
 
-/// Global world seed; changing this reshuffles all procedural props.
+/// Seed used when nothing else picks one (`PropsSettings`, offline tools).
+pub const DEFAULT_WORLD_SEED: u64 = 1337;
+
+/// Global world seed; changing this reshuffles all procedural props and procedural terrain.
 #[derive(Resource, Clone, Copy, Debug, PartialEq, Eq, Hash, Serialize, Deserialize)]
 pub struct WorldSeed(pub u64);
 
@@ -114,6 +117,19 @@ impl BiomeMask {
     pub fn any(self, other: Self) -> bool { (self.0 & other.0) != 0 }
 }
 
+/// Biome lookups for placement (e.g. `terrain::BiomeLayer`).
+pub trait BiomeSampler: Send + Sync + 'static {
+    /// Biomes at (x, z); `None` where the sampler knows nothing (off the map).
+    fn biome_mask(&self, x: f32, z: f32) -> Option<BiomeMask>;
+}
+
+/// River lookups for placement (e.g. `terrain::DrainageMap`).
+pub trait RiverSampler: Send + Sync + 'static {
+    /// Meters from (x, z) to the nearest river bank, negative inside a channel; `None`
+    /// where the sampler knows nothing (off the map, no rivers).
+    fn river_distance(&self, x: f32, z: f32) -> Option<f32>;
+}
+
 // ---------- Placement I/O ----------
 
 /// Raw placement sample before height snap (XZ only).
@@ -165,6 +181,18 @@ pub struct CommonFilters {
     pub slope_min_deg: Option<f32>,
     pub slope_max_deg: Option<f32>,
     pub biome_mask_any: Option<BiomeMask>, // pass if any bit overlaps
+    /// Downhill bearing window in degrees (0 = -Z, 90 = +X), e.g. `Some((90.0, 270.0))`
+    /// for slopes facing away from -Z. May wrap past 360. Flat ground always passes.
+    pub aspect_range_deg: Option<(f32, f32)>,
+    /// Max local roughness (RMS meters off the best-fit plane, see `TileAnalysis`).
+    pub roughness_max: Option<f32>,
+    /// Plan curvature bounds (1/m): negative keeps to gullies, positive to ridges and spurs.
+    pub plan_curvature_min: Option<f32>,
+    pub plan_curvature_max: Option<f32>,
+    /// Distance bounds (meters) from the nearest river bank, negative inside the channel:
+    /// `river_distance_max: Some(4.0)` keeps reeds along the banks.
+    pub river_distance_min: Option<f32>,
+    pub river_distance_max: Option<f32>,
 }
 
 impl Default for CommonFilters {
@@ -175,6 +203,12 @@ impl Default for CommonFilters {
             slope_min_deg: None,
             slope_max_deg: None,
             biome_mask_any: None,
+            aspect_range_deg: None,
+            roughness_max: None,
+            plan_curvature_min: None,
+            plan_curvature_max: None,
+            river_distance_min: None,
+            river_distance_max: None,
         }
     }
 }
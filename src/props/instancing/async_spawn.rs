@@ -6,41 +6,57 @@ use bevy::tasks::{AsyncComputeTaskPool, Task};
 use futures_lite::future;
 
 use crate::props::plugin::{TerrainChunkLoaded, PropsRegistryHandle};
-use crate::props::core::{ChunkCoord, WorldSeed, PlacementResult, PropArchetypeId};
+use crate::props::core::{BiomeSampler, ChunkArea, ChunkCoord, RiverSampler, WorldSeed, PlacementResult, PropArchetypeId};
 use crate::props::registry::{PropsRegistry, RenderRef};
 use crate::props::queue::{SpawnQueue, SpawnRequest};
 use crate::props::placement::runner::{PlacementContext, run_placement_for_chunk};
 use crate::heightmap_data::{HeightSampler, SlopeSampler, HeightmapData, HeightTileCache, TerrainSampleAdapter};
+use crate::terrain::{BiomeLayer, Hydrology};
 
 #[derive(Resource, Default)]
 pub struct PropPlacementTasks {
     tasks: HashMap<ChunkCoord, Task<Vec<PlacementResult>>>,
 }
 
+/// Chunks whose placement filters on biomes wait until the chunk has been classified, so
+/// placement tasks never classify (and read tiles) themselves.
 pub fn schedule_async_placement_tasks(
     mut tasks: ResMut<PropPlacementTasks>,
     mut events: EventReader<TerrainChunkLoaded>,
+    mut waiting: Local<Vec<ChunkArea>>,
     registries: Res<Assets<PropsRegistry>>,
     handle: Res<PropsRegistryHandle>,
     seed: Res<WorldSeed>,
     heightmap: Res<HeightmapData>,
     cache: Res<HeightTileCache>,
+    biomes: Option<Res<BiomeLayer>>,
+    hydrology: Option<Res<Hydrology>>,
 ) {
     let Some(registry) = registries.get(&handle.0) else { return };
     let archetypes = registry.archetypes.clone(); // clone just what we need
 
+    let wants_biomes = archetypes.iter().any(|a| a.filters.biome_mask_any.is_some());
+
     let pool = AsyncComputeTaskPool::get();
-    for TerrainChunkLoaded(chunk) in events.read() {
+    let chunks: Vec<ChunkArea> = waiting.drain(..).chain(events.read().map(|ev| ev.0)).collect();
+    for chunk in chunks {
         let coord = chunk.coord;
         if tasks.tasks.contains_key(&coord) {
             continue;
         }
+        if let Some(layer) = biomes.as_deref().filter(|_| wants_biomes) {
+            if layer.request_raster(coord.x, coord.z).is_none() {
+                waiting.push(chunk);
+                continue;
+            }
+        }
 
-        let chunk = *chunk;
         let seed = *seed;
         let heightmap = heightmap.clone();
-        let cache = cache.clone(); // Arc-backed clone
+        let cache = cache.clone(); // shares the tile store, no tile copies
         let archetypes = archetypes.clone(); // 👈 Move this inside loop
+        let biomes = biomes.as_deref().cloned(); // shares the rasters, classified on first use
+        let drainage = hydrology.as_ref().map(|h| h.0.clone());
 
         let task = pool.spawn(async move {
             let adapter = TerrainSampleAdapter::new(&heightmap, &cache);
@@ -55,6 +71,8 @@ pub fn schedule_async_placement_tasks(
                     def,
                     sampler: &adapter,
                     slope: &adapter,
+                    biomes: biomes.as_ref().map(|b| b as &dyn BiomeSampler),
+                    rivers: drainage.as_deref().map(|d| d as &dyn RiverSampler),
                 };
                 let results = run_placement_for_chunk(ctx);
                 info!(
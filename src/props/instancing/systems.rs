@@ -4,7 +4,7 @@ use bevy::tasks::{AsyncComputeTaskPool, Task};
 use futures_lite::future::{block_on, poll_once};
 
 use crate::props::core::{ChunkCoord, PropArchetypeId};
-use crate::props::plugin::{PropsRegistryHandle, TerrainChunkUnloaded};
+use crate::props::plugin::{ChunkPropsInvalidated, PropsRegistryHandle, TerrainChunkUnloaded};
 use crate::props::queue::{SpawnQueue, SpawnQueueConfig, SpawnRequest};
 use crate::props::registry::{PropsRegistry, RenderRef};
 use super::components::{InstanceBatch, BatchStats};
@@ -180,12 +180,13 @@ pub fn integrate_finished_merges(
 
 pub fn cleanup_batches_on_chunk_unloaded(
     mut evr: EventReader<TerrainChunkUnloaded>,
+    mut invalidated: EventReader<ChunkPropsInvalidated>,
     mut commands: Commands,
     mut batches: ResMut<InstanceBatches>,
     q_has: Query<(), With<InstanceBatch>>,
 ) {
-    for ev in evr.read() {
-        let chunk = ev.0;
+    let unloaded = evr.read().map(|ev| ev.0);
+    for chunk in unloaded.chain(invalidated.read().map(|ev| ev.0)) {
         let keys: Vec<_> = batches.by_key
             .keys()
             .copied()
@@ -13,6 +13,10 @@ pub struct PlacementContext<'a> {
     pub def: &'a PropArchetypeDef,
     pub sampler: &'a dyn HeightSampler,
     pub slope: &'a dyn SlopeSampler,
+    /// `None` = the map has no biome rules; `biome_mask_any` filters then pass everything.
+    pub biomes: Option<&'a dyn BiomeSampler>,
+    /// `None` = no drainage for the map (yet); river distance filters then pass everything.
+    pub rivers: Option<&'a dyn RiverSampler>,
 }
 
 /// Run placement, filters, and transform snapping for a single prop in a chunk
@@ -54,6 +58,52 @@ pub fn run_placement_for_chunk(ctx: PlacementContext) -> Vec<PlacementResult> {
             }
         }
 
+        // --- Biome Filter ---
+        if let (Some(any), Some(biomes)) = (filters.biome_mask_any, ctx.biomes) {
+            if let Some(mask) = biomes.biome_mask(probe.x, probe.z) {
+                if !mask.any(any) {
+                    continue;
+                }
+            }
+        }
+
+        // --- River Filter ---
+        if filters.river_distance_min.is_some() || filters.river_distance_max.is_some() {
+            if let Some(d) = ctx.rivers.and_then(|r| r.river_distance(probe.x, probe.z)) {
+                if filters.river_distance_min.is_some_and(|min| d < min) {
+                    continue;
+                }
+                if filters.river_distance_max.is_some_and(|max| d > max) {
+                    continue;
+                }
+            }
+        }
+
+        // --- Terrain analysis filters (samplers without analysis pass everything) ---
+        let wants_ground = filters.aspect_range_deg.is_some()
+            || filters.roughness_max.is_some()
+            || filters.plan_curvature_min.is_some()
+            || filters.plan_curvature_max.is_some();
+        if wants_ground {
+            if let Some(ground) = ctx.slope.attributes(probe.x, probe.z) {
+                if let Some((from, to)) = filters.aspect_range_deg {
+                    let span = (to - from).rem_euclid(360.0);
+                    if ground.gradient != Vec2::ZERO && (ground.aspect_deg - from).rem_euclid(360.0) > span {
+                        continue;
+                    }
+                }
+                if filters.roughness_max.is_some_and(|max| ground.roughness > max) {
+                    continue;
+                }
+                if filters.plan_curvature_min.is_some_and(|min| ground.plan_curvature < min) {
+                    continue;
+                }
+                if filters.plan_curvature_max.is_some_and(|max| ground.plan_curvature > max) {
+                    continue;
+                }
+            }
+        }
+
         let (pos, rot, scale) = finalize_transform(
             &probe,
             ctx.sampler,
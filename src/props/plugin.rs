@@ -1,9 +1,12 @@
 use bevy::prelude::*;
 
-use super::core::{ChunkArea, ChunkCoord, WorldSeed};
+use super::core::{ChunkCoord, WorldSeed, DEFAULT_WORLD_SEED};
 use super::registry::{PropsRegistry, PropsRegistryAssetPlugin};
 use super::queue::{SpawnQueue, SpawnQueueConfig};
 
+use crate::heightmap_data::HeightmapData;
+use crate::terrain::TerrainDeformed;
+
 use crate::props::instancing::resources::{InstanceBatches, PropsInstancingConfig, MergeIntegrationQueue};
 use crate::props::instancing::systems::{
     drain_spawn_queue_into_batches,
@@ -36,7 +39,7 @@ impl Default for PropsSettings {
     fn default() -> Self {
         Self {
             registry_path: "props/archetypes.props.ron".to_string(),
-            world_seed: 1337,
+            world_seed: DEFAULT_WORLD_SEED,
         }
     }
 }
@@ -44,11 +47,13 @@ impl Default for PropsSettings {
 #[derive(Resource, Default)]
 pub struct PropsRegistryHandle(pub Handle<PropsRegistry>);
 
-#[derive(Event, Clone, Copy)]
-pub struct TerrainChunkLoaded(pub ChunkArea);
+// Chunk lifecycle events are owned by terrain; re-exported here for the props systems.
+pub use crate::terrain::{TerrainChunkLoaded, TerrainChunkUnloaded};
 
-#[derive(Event, Clone, Copy)]
-pub struct TerrainChunkUnloaded(pub ChunkCoord);
+/// The chunk's props stand on terrain that changed (`TerrainDeformed`): drop them. The chunk
+/// itself stays loaded; its rebuilt mesh's `TerrainChunkLoaded` places them again.
+#[derive(Event, Clone, Copy, Debug)]
+pub struct ChunkPropsInvalidated(pub ChunkCoord);
 
 pub struct PropsPlugin;
 
@@ -65,6 +70,7 @@ impl Plugin for PropsPlugin {
             .init_resource::<PropPlacementTasks>()
             .add_event::<TerrainChunkLoaded>()
             .add_event::<TerrainChunkUnloaded>()
+            .add_event::<ChunkPropsInvalidated>()
             .add_systems(Startup, (
                 init_world_seed_from_settings,
                 load_registry,
@@ -85,10 +91,15 @@ impl Plugin for PropsPlugin {
                 log_chunk_events,
             ))
 
+            // ---------- Terrain edits: drop props on remeshed chunks; the rebuilt chunk's
+            //            TerrainChunkLoaded places them again on the new surface ----------
+            .add_systems(Update, clear_props_on_terrain_deformed.before(PropSystemSet::CleanupBatches))
+
             // ---------- Async Placement ----------
             .add_systems(Update, (
                 schedule_async_placement_tasks
                     .run_if(registry_ready)
+                    .run_if(resource_exists::<HeightmapData>)
                     .in_set(PropSystemSet::AsyncPlacement),
                 collect_placement_results
                     .run_if(registry_ready)
@@ -141,6 +152,17 @@ fn monitor_registry_ready(
     }
 }
 
+fn clear_props_on_terrain_deformed(
+    mut evr: EventReader<TerrainDeformed>,
+    mut evw: EventWriter<ChunkPropsInvalidated>,
+) {
+    for ev in evr.read() {
+        for &(x, z) in &ev.chunks {
+            evw.write(ChunkPropsInvalidated(ChunkCoord { x, z }));
+        }
+    }
+}
+
 fn log_chunk_events(mut evr: EventReader<TerrainChunkLoaded>) {
     for ev in evr.read() {
         info!(
@@ -3,7 +3,7 @@
 
 use bevy::prelude::*;
 use crate::props::core::{PropId, ChunkCoord};
-use crate::props::plugin::TerrainChunkUnloaded;
+use crate::props::plugin::{ChunkPropsInvalidated, TerrainChunkUnloaded};
 use crate::props::registry::RenderRef;
 use super::components::PropChunkTag;
 use super::systems::spawn_render_ref;
@@ -23,14 +23,15 @@ pub fn spawn_prop_instance(
     root
 }
 
-/// Despawn all props that belong to an unloaded chunk.
+/// Despawn all props that belong to an unloaded (or deformed) chunk.
 pub fn despawn_chunk_props(
     mut evr: EventReader<TerrainChunkUnloaded>,
+    mut invalidated: EventReader<ChunkPropsInvalidated>,
     q: Query<(Entity, &PropChunkTag)>,
     mut commands: Commands,
 ) {
-    for ev in evr.read() {
-        let coord = ev.0;
+    let unloaded = evr.read().map(|ev| ev.0);
+    for coord in unloaded.chain(invalidated.read().map(|ev| ev.0)) {
         for (e, tag) in q.iter() {
             if tag.0 == coord {
                 commands.entity(e).despawn();
@@ -26,7 +26,8 @@ impl Plugin for VegetationPlugin {
     fn build(&self, app: &mut App) {
         app
             .init_resource::<FlatGround>()
-            .add_systems(Startup, init_terrain_height_sampler);
+            // (re)build whenever the terrain swaps its tile cache (map load/switch)
+            .add_systems(Update, init_terrain_height_sampler.run_if(resource_exists_and_changed::<HeightTileCache>));
             // NOTE: Removed sync vegetation system to avoid CPU freeze
     }
 }
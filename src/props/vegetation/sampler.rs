@@ -1,6 +1,5 @@
 // src/props/vegetation/samplers.rs
 use bevy::prelude::*;
-use std::sync::Mutex;
 
 use crate::heightmap_data::{HeightmapData, HeightTileCache, sample_height, HeightSampler, SlopeSampler};
 
@@ -9,27 +8,21 @@ use crate::heightmap_data::{HeightmapData, HeightTileCache, sample_height, Heigh
 #[derive(Resource)]
 pub struct TerrainHeightSampler {
     data: HeightmapData,
-    cache: Mutex<HeightTileCache>,
+    cache: HeightTileCache,
 }
 
 impl TerrainHeightSampler {
-    pub fn new_from(data: &HeightmapData, cache_cfg: &HeightTileCache) -> Self {
-        // Recreate an empty cache with the same config (we only copy the IO config, not tiles)
-        let mut new_cache = HeightTileCache::new(&cache_cfg.folder, cache_cfg.tile_resolution);
-        new_cache.filename_prefix = cache_cfg.filename_prefix.clone();
-        new_cache.filename_ext = cache_cfg.filename_ext.clone();
-
+    pub fn new_from(data: &HeightmapData, cache: &HeightTileCache) -> Self {
         Self {
             data: data.clone(),             // HeightmapData is Clone in your code
-            cache: Mutex::new(new_cache),   // keep our own cache for thread-safe access
+            cache: cache.clone(),           // shares the thread-safe tile store; no private copy
         }
     }
 }
 
 impl HeightSampler for TerrainHeightSampler {
     fn sample_height(&self, x: f32, z: f32) -> f32 {
-        // lock the cache and sample; fall back to 0.0 if OOB or tile missing
-        let mut guard = self.cache.lock().expect("height cache mutex poisoned");
-        sample_height(x, z, &self.data, &mut *guard).unwrap_or(0.0)
+        // fall back to 0.0 if OOB or tile missing
+        sample_height(x, z, &self.data, &self.cache).unwrap_or(0.0)
     }
-}
\ No newline at end of file
+}
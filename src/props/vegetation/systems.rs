@@ -2,15 +2,19 @@ use bevy::prelude::*;
 use crate::props::core::{WorldSeed, finalize_transform, make_prop_id, PropArchetypeId};
 use crate::props::placement::make_strategy;
 use crate::props::registry::PropsRegistry;
+use crate::props::core::ChunkArea;
 use crate::props::plugin::{TerrainChunkLoaded, PropsRegistryHandle};
 use crate::props::queue::{SpawnQueue, SpawnRequest};
-use crate::heightmap_data::{HeightSampler, SlopeSampler};
+use crate::heightmap_data::sample_height_ready;
 
 use super::rules::height_rule_from_filters;
 use super::plugin::VegSampler;
 
+/// Chunks wait until the tiles under them are resident, so the frame never blocks on a
+/// tile read.
 pub fn spawn_veg_on_chunk_loaded(
     mut evr: EventReader<TerrainChunkLoaded>,
+    mut waiting: Local<Vec<ChunkArea>>,
     regs: Res<Assets<PropsRegistry>>,
     handle: Res<PropsRegistryHandle>,
     seed: Res<WorldSeed>,
@@ -19,8 +23,17 @@ pub fn spawn_veg_on_chunk_loaded(
 ) {
     let Some(reg) = regs.get(&handle.0) else { return };
 
-    for ev in evr.read() {
-        let area = &ev.0;
+    let areas: Vec<ChunkArea> = waiting.drain(..).chain(evr.read().map(|ev| ev.0)).collect();
+    for area in areas {
+        // The chunk's tile and the right/up neighbours its far edges sample
+        let loading = [(0, 0), (1, 0), (0, 1), (1, 1)]
+            .iter()
+            .any(|(dx, dz)| sampler.0.cache.request_tile(area.coord.x + dx, area.coord.z + dz).is_loading());
+        if loading {
+            waiting.push(area);
+            continue;
+        }
+        let area = &area;
 
         for (idx, arche) in reg.archetypes.iter().enumerate() {
             if arche.category.as_deref() != Some("vegetation") {
@@ -41,7 +54,7 @@ pub fn spawn_veg_on_chunk_loaded(
             let mut h_cnt = 0;
 
             for probe in probes {
-                let h = sampler.0.sample_height(probe.x, probe.z);
+                let h = sample_height_ready(probe.x, probe.z, &sampler.0.data, &sampler.0.cache).unwrap_or(0.0);
 
                 if h.is_finite() {
                     if h < h_min { h_min = h; }
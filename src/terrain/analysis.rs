@@ -0,0 +1,334 @@
+// src/terrain/analysis.rs
+//! Terrain analysis rasters: per-texel slope, aspect, plan and profile curvature and local
+//! roughness of a height tile. `HeightTileCache` computes a tile's `TileAnalysis` while the
+//! tile loads and keeps it resident next to it, so slope queries, prop filters, unit
+//! movement and AI site selection read precomputed values instead of differencing heights.
+//!
+//! The rasters have their own texel size (`AnalysisScale::spacing`, 1 m for manifests by
+//! default), usually coarser than the height tile's: that keeps them small next to the
+//! tile, and measures slope and roughness at a scale units and props care about.
+//! Derivatives are Zevenbergen-Thorne finite differences over each texel's 3x3
+//! neighbourhood. A tile only sees its own texels, so on its border rows the stencils are
+//! one-sided (first derivatives) or shifted one texel inward (curvature, roughness).
+
+use bevy::prelude::*;
+
+use crate::heightmap_data::{locate_in_tile, sample_height, HeightTileCache, HeightmapData, Tile16, TileStatus};
+
+/// Below this squared gradient a texel counts as flat: aspect and curvature are 0.
+const FLAT_GRADIENT_SQ: f32 = 1e-8;
+
+/// How tile texels map to meters, and how fine the analysis is.
+#[derive(Clone, Copy, Debug)]
+pub struct AnalysisScale {
+    /// World size of one tile (X, Z).
+    pub chunk_size: Vec2,
+    /// Meters between `raw_minmax.0` and `raw_minmax.1`.
+    pub height_scale: f32,
+    pub raw_minmax: (f32, f32),
+    /// Target texel size of the rasters in meters (never finer than the tile; 0 = the tile's).
+    pub spacing: f32,
+}
+
+impl AnalysisScale {
+    /// Raster resolution for a tile of `tile_res` texels.
+    pub fn resolution(&self, tile_res: UVec2) -> UVec2 {
+        if self.spacing <= 0.0 {
+            return tile_res;
+        }
+        let cells = (self.chunk_size / self.spacing).ceil().as_uvec2().max(UVec2::ONE);
+        (cells + UVec2::ONE).min(tile_res)
+    }
+
+    /// Bilinear height (meters) at normalized `uv` inside `tile`.
+    fn height(&self, tile: &Tile16, uv: Vec2) -> f32 {
+        let p = uv * (tile.res - UVec2::ONE).as_vec2();
+        let p0 = p.floor();
+        let f = p - p0;
+        let (x, z) = (p0.x as i32, p0.y as i32);
+        let at = |dx: i32, dz: i32| tile.get_clamped(x + dx, z + dz) as f32;
+        let a = at(0, 0) + (at(1, 0) - at(0, 0)) * f.x;
+        let b = at(0, 1) + (at(1, 1) - at(0, 1)) * f.x;
+        let raw = a + (b - a) * f.y;
+
+        let (rmin, rmax) = self.raw_minmax;
+        if rmax > rmin {
+            ((raw - rmin) / (rmax - rmin)).clamp(0.0, 1.0) * self.height_scale
+        } else {
+            0.0
+        }
+    }
+}
+
+/// Analysis rasters of one tile, row-major, `res` texels spanning the whole tile (like a
+/// `Tile16`, edge texels sit on the tile's edges).
+#[derive(Clone, Debug)]
+pub struct TileAnalysis {
+    pub res: UVec2,
+    /// Steepest slope, in degrees from horizontal.
+    pub slope_deg: Vec<f32>,
+    /// Compass bearing of the downhill direction in degrees: 0 = -Z ("north"), 90 = +X.
+    /// 0 on flat ground.
+    pub aspect_deg: Vec<f32>,
+    /// Curvature across the slope (of the contour lines), 1/m. Positive = convex (spurs,
+    /// ridges), negative = concave (gullies, valleys).
+    pub plan_curvature: Vec<f32>,
+    /// Curvature along the slope, 1/m. Positive = convex (crests, where the ground steepens
+    /// downhill), negative = concave (foot slopes, where it levels out).
+    pub profile_curvature: Vec<f32>,
+    /// RMS deviation (meters) of the 3x3 neighbourhood from its best-fit plane: 0 on any
+    /// smooth slope, high on scree, boulders and broken ground.
+    pub roughness: Vec<f32>,
+}
+
+impl TileAnalysis {
+    pub fn compute(tile: &Tile16, scale: &AnalysisScale) -> Self {
+        let res = scale.resolution(tile.res);
+        let cells = (res - UVec2::ONE).max(UVec2::ONE).as_vec2();
+        let heights: Vec<f32> = (0..res.y)
+            .flat_map(|z| (0..res.x).map(move |x| Vec2::new(x as f32, z as f32) / cells))
+            .map(|uv| scale.height(tile, uv))
+            .collect();
+        let max = res.as_ivec2() - IVec2::ONE;
+        let spacing = scale.chunk_size / max.max(IVec2::ONE).as_vec2();
+        let h = |x: i32, z: i32| heights[(z.clamp(0, max.y) * res.x as i32 + x.clamp(0, max.x)) as usize];
+        // The 3x3 windows need a texel on each side of their centre
+        let windowed = max.x >= 2 && max.y >= 2;
+
+        let texels = heights.len();
+        let mut out = Self {
+            res,
+            slope_deg: Vec::with_capacity(texels),
+            aspect_deg: Vec::with_capacity(texels),
+            plan_curvature: Vec::with_capacity(texels),
+            profile_curvature: Vec::with_capacity(texels),
+            roughness: Vec::with_capacity(texels),
+        };
+        for z in 0..=max.y {
+            for x in 0..=max.x {
+                // Gradient: central differences, one-sided on the border
+                let (x0, x1) = ((x - 1).max(0), (x + 1).min(max.x));
+                let (z0, z1) = ((z - 1).max(0), (z + 1).min(max.y));
+                let p = if x1 > x0 { (h(x1, z) - h(x0, z)) / ((x1 - x0) as f32 * spacing.x) } else { 0.0 };
+                let q = if z1 > z0 { (h(x, z1) - h(x, z0)) / ((z1 - z0) as f32 * spacing.y) } else { 0.0 };
+                let g = p * p + q * q;
+
+                out.slope_deg.push(g.sqrt().atan().to_degrees());
+                out.aspect_deg.push(if g > FLAT_GRADIENT_SQ { aspect_of(Vec2::new(p, q)) } else { 0.0 });
+
+                if !windowed {
+                    out.plan_curvature.push(0.0);
+                    out.profile_curvature.push(0.0);
+                    out.roughness.push(0.0);
+                    continue;
+                }
+                let (cx, cz) = (x.clamp(1, max.x - 1), z.clamp(1, max.y - 1));
+                let w = |dx: i32, dz: i32| h(cx + dx, cz + dz);
+
+                let r = (w(1, 0) - 2.0 * w(0, 0) + w(-1, 0)) / (spacing.x * spacing.x);
+                let t = (w(0, 1) - 2.0 * w(0, 0) + w(0, -1)) / (spacing.y * spacing.y);
+                let s = (w(1, 1) - w(-1, 1) - w(1, -1) + w(-1, -1)) / (4.0 * spacing.x * spacing.y);
+                let (plan, profile) = if g > FLAT_GRADIENT_SQ {
+                    (
+                        -(q * q * r - 2.0 * p * q * s + p * p * t) / g.powf(1.5),
+                        -(p * p * r + 2.0 * p * q * s + q * q * t) / (g * (1.0 + g).powf(1.5)),
+                    )
+                } else {
+                    (0.0, 0.0)
+                };
+                out.plan_curvature.push(plan);
+                out.profile_curvature.push(profile);
+
+                // Least-squares plane through the window, then the RMS of what it misses
+                let (mut sum, mut sum_x, mut sum_z) = (0.0, 0.0, 0.0);
+                for dz in -1..=1 {
+                    for dx in -1..=1 {
+                        sum += w(dx, dz);
+                        sum_x += dx as f32 * w(dx, dz);
+                        sum_z += dz as f32 * w(dx, dz);
+                    }
+                }
+                let (mean, fit_x, fit_z) = (sum / 9.0, sum_x / 6.0, sum_z / 6.0);
+                let mut residual = 0.0;
+                for dz in -1..=1 {
+                    for dx in -1..=1 {
+                        let e = w(dx, dz) - (mean + fit_x * dx as f32 + fit_z * dz as f32);
+                        residual += e * e;
+                    }
+                }
+                out.roughness.push((residual / 9.0).sqrt());
+            }
+        }
+        out
+    }
+
+    pub fn byte_size(&self) -> usize {
+        5 * self.slope_deg.len() * std::mem::size_of::<f32>()
+    }
+
+    /// Attributes at normalized `uv` inside the tile. Scalar rasters are interpolated
+    /// bilinearly; aspect comes from the interpolated gradient, so it doesn't jump where
+    /// the bearing wraps from 360 to 0.
+    pub fn sample(&self, uv: Vec2) -> TerrainAttributes {
+        let max = (self.res - UVec2::ONE).as_vec2();
+        let p = (uv.clamp(Vec2::ZERO, Vec2::ONE) * max).min(max);
+        let p0 = p.floor();
+        let f = p - p0;
+        let (x0, z0) = (p0.x as u32, p0.y as u32);
+        let (x1, z1) = ((x0 + 1).min(self.res.x - 1), (z0 + 1).min(self.res.y - 1));
+        let corners = [
+            (x0, z0, (1.0 - f.x) * (1.0 - f.y)),
+            (x1, z0, f.x * (1.0 - f.y)),
+            (x0, z1, (1.0 - f.x) * f.y),
+            (x1, z1, f.x * f.y),
+        ];
+
+        let mut out = TerrainAttributes::FLAT;
+        for (x, z, weight) in corners {
+            let i = (z * self.res.x + x) as usize;
+            out.slope_deg += weight * self.slope_deg[i];
+            out.plan_curvature += weight * self.plan_curvature[i];
+            out.profile_curvature += weight * self.profile_curvature[i];
+            out.roughness += weight * self.roughness[i];
+            out.gradient += weight * gradient_of(self.slope_deg[i], self.aspect_deg[i]);
+        }
+        if out.gradient.length_squared() > FLAT_GRADIENT_SQ {
+            out.aspect_deg = aspect_of(out.gradient);
+        }
+        out
+    }
+}
+
+/// Bearing of the downhill direction for an uphill gradient (dh/dx, dh/dz).
+fn aspect_of(gradient: Vec2) -> f32 {
+    // Downhill is -gradient; its "east" part is along +X, its "north" part along -Z
+    (-gradient.x).atan2(gradient.y).to_degrees().rem_euclid(360.0)
+}
+
+/// Inverse of `aspect_of`, scaled by the slope.
+fn gradient_of(slope_deg: f32, aspect_deg: f32) -> Vec2 {
+    let (sin, cos) = aspect_deg.to_radians().sin_cos();
+    Vec2::new(-sin, cos) * slope_deg.to_radians().tan()
+}
+
+/// Terrain analysis at one world position.
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub struct TerrainAttributes {
+    pub slope_deg: f32,
+    /// See `TileAnalysis::aspect_deg`.
+    pub aspect_deg: f32,
+    pub plan_curvature: f32,
+    pub profile_curvature: f32,
+    pub roughness: f32,
+    /// Height change per meter along +X and +Z.
+    pub gradient: Vec2,
+}
+
+impl TerrainAttributes {
+    /// Level, smooth ground (also what void reports).
+    pub const FLAT: Self = Self {
+        slope_deg: 0.0,
+        aspect_deg: 0.0,
+        plan_curvature: 0.0,
+        profile_curvature: 0.0,
+        roughness: 0.0,
+        gradient: Vec2::ZERO,
+    };
+
+    /// Unit surface normal.
+    pub fn normal(&self) -> Vec3 {
+        Vec3::new(-self.gradient.x, 1.0, -self.gradient.y).normalize()
+    }
+
+    /// Rise per meter when moving along `dir` (XZ); negative going downhill.
+    pub fn grade_along(&self, dir: Vec2) -> f32 {
+        self.gradient.dot(dir.normalize_or_zero())
+    }
+}
+
+/// Analysis at a world position, loading the tile if needed (blocking, like
+/// `sample_height`). Void reads as `FLAT`. `None` off the map, for unreadable tiles and
+/// when the cache computes no analysis.
+pub fn sample_terrain(
+    world_x: f32,
+    world_z: f32,
+    data: &HeightmapData,
+    cache: &HeightTileCache,
+) -> Option<TerrainAttributes> {
+    let ((cx, cz), uv) = locate_in_tile(world_x, world_z, data)?;
+    match cache.try_fetch_tile(cx, cz) {
+        Ok(tile) => cache.analysis_for(cx, cz, &tile).map(|a| a.sample(uv)),
+        Err(e) if e.is_missing() => Some(TerrainAttributes::FLAT),
+        Err(_) => None,
+    }
+}
+
+/// Non-blocking `sample_terrain`: also `None` while the tile is still loading.
+pub fn sample_terrain_ready(
+    world_x: f32,
+    world_z: f32,
+    data: &HeightmapData,
+    cache: &HeightTileCache,
+) -> Option<TerrainAttributes> {
+    let ((cx, cz), uv) = locate_in_tile(world_x, world_z, data)?;
+    match cache.request_tile(cx, cz) {
+        TileStatus::Ready(tile) => cache.analysis_for(cx, cz, &tile).map(|a| a.sample(uv)),
+        TileStatus::Unavailable(e) if e.is_missing() => Some(TerrainAttributes::FLAT),
+        _ => None,
+    }
+}
+
+/// What a building site (or camp, or rally point) needs from the ground under it.
+#[derive(Clone, Copy, Debug)]
+pub struct SiteRequirements {
+    /// Radius of the footprint that is checked, in meters.
+    pub radius: f32,
+    pub max_slope_deg: f32,
+    pub max_roughness: f32,
+    /// Only accept sites at or above this height (e.g. above the water level).
+    pub min_height: Option<f32>,
+}
+
+/// How well the ground at (x, z) suits a site: `None` when any point of the footprint
+/// breaks the requirements, otherwise a score in `0..=1` (1 = level and smooth throughout).
+/// Blocking; meant for AI planning off the main thread.
+pub fn site_score(x: f32, z: f32, req: &SiteRequirements, data: &HeightmapData, cache: &HeightTileCache) -> Option<f32> {
+    // Centre plus two rings, so a ditch or a spur inside the footprint isn't missed
+    let mut points = vec![Vec2::new(x, z)];
+    for ring in [0.5, 1.0] {
+        for k in 0..8 {
+            let angle = k as f32 * std::f32::consts::FRAC_PI_4;
+            points.push(Vec2::new(x, z) + Vec2::from_angle(angle) * req.radius * ring);
+        }
+    }
+
+    let mut score = 0.0;
+    for p in &points {
+        let ground = sample_terrain(p.x, p.y, data, cache)?;
+        if ground.slope_deg > req.max_slope_deg || ground.roughness > req.max_roughness {
+            return None;
+        }
+        if let Some(min) = req.min_height {
+            if sample_height(p.x, p.y, data, cache)? < min {
+                return None;
+            }
+        }
+        let flat = 1.0 - ground.slope_deg / req.max_slope_deg.max(f32::EPSILON);
+        let smooth = 1.0 - ground.roughness / req.max_roughness.max(f32::EPSILON);
+        score += flat * smooth;
+    }
+    Some(score / points.len() as f32)
+}
+
+/// The best-scoring of `candidates` (see `site_score`), with its score.
+pub fn best_site(
+    candidates: impl IntoIterator<Item = Vec2>,
+    req: &SiteRequirements,
+    data: &HeightmapData,
+    cache: &HeightTileCache,
+) -> Option<(Vec2, f32)> {
+    candidates
+        .into_iter()
+        .filter_map(|c| site_score(c.x, c.y, req, data, cache).map(|s| (c, s)))
+        .max_by(|a, b| a.1.total_cmp(&b.1))
+}
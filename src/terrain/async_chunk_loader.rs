@@ -1,18 +1,30 @@
 // src/terrain/async_chunk_loader.rs
+use std::cmp::Ordering;
+use std::collections::{BinaryHeap, HashMap};
+
 use bevy::prelude::*;
+use bevy::math::Affine3A;
 use bevy::render::mesh::{Indices, Mesh, PrimitiveTopology};
+use bevy::render::primitives::{Aabb, Frustum};
 use bevy::tasks::{futures::check_ready, AsyncComputeTaskPool, Task};
 
-use crate::heightmap_data::{HeightTileCache, HeightmapData, Tile16};
+use crate::heightmap_data::{HeightTileCache, HeightmapData, Tile16, TileStatus};
+use crate::input::CameraOrbit;
 use crate::props::core::{ChunkArea, ChunkCoord};
-use crate::props::plugin::TerrainChunkLoaded;
 use crate::setup::MainCamera;
 use crate::terrain::chunking::{
     chunk_origin_world, chunk_world_aabb, needed_chunks_around, ChunkManager,
 };
 use crate::terrain::components::{ChunkAabb, ChunkKey, ChunkReady, Terrain};
-use crate::terrain::lod::{ChunkLod, LodLevel};
-use crate::terrain::plugin::{COLOR_EXT, COLOR_FOLDER, COLOR_PREFIX};
+use crate::terrain::deform::DirtyChunks;
+use crate::terrain::events::{
+    TerrainChunkLoaded, TerrainChunkLodChanged, TerrainChunkMeshBuilt, TerrainChunkRequested,
+    TerrainChunkUnloaded,
+};
+use crate::terrain::lod::{ChunkLod, EdgeLods, LodLevel};
+use crate::terrain::manifest::TerrainManifest;
+use crate::terrain::mesh_cache::{chunk_mesh_key, ChunkMeshCache};
+use crate::terrain::rtin::{ChunkMesher, Rtin};
 
 /// How many mesh builds we allow *in flight* to complete and be accepted per frame.
 #[derive(Resource)]
@@ -32,14 +44,58 @@ impl Default for MeshBuildBudget {
     }
 }
 
+/// Seconds of camera travel the scheduler looks ahead for prefetching.
+const PREFETCH_SECS: f32 = 1.5;
+/// Camera speed (m/s) below which there is no prefetch ring.
+const PREFETCH_MIN_SPEED: f32 = 20.0;
+/// Priority multiplier for chunks outside the view frustum.
+const OUT_OF_VIEW_PENALTY: f32 = 3.0;
+/// Priority multiplier for chunks that already have a mesh up (LoD swaps, re-stitching, edits).
+const REBUILD_PENALTY: f32 = 2.0;
+
 /// Chunk task metadata (which chunk & which LoD this task is for)
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
 struct ChunkTaskInfo {
     cx: i32,
     cz: i32,
     lod: LodLevel,
+    /// Border resolutions (stitched to coarser neighbours)
+    edges: EdgeLods,
+    /// Mip level the heights are read from (`HeightTileCache::level_for_cells`).
+    level: u32,
+    /// No height tile here: flat chunk at `void_height`.
+    void: bool,
+    /// Rebuild after a terrain edit (re-announced with `TerrainChunkLoaded`).
+    rebuild: bool,
+}
+
+impl ChunkTaskInfo {
+    /// Tiles a build for this chunk reads (self + right/up/up_right edge donors).
+    fn tile_keys(&self) -> [(i32, i32); 4] {
+        [
+            (self.cx, self.cz),
+            (self.cx + 1, self.cz),
+            (self.cx, self.cz + 1),
+            (self.cx + 1, self.cz + 1),
+        ]
+    }
+
+    fn pin_tiles(&self, cache: &HeightTileCache) {
+        for (tx, tz) in self.tile_keys() {
+            cache.level(self.level).pin_tile(tx, tz);
+        }
+    }
+
+    fn unpin_tiles(&self, cache: &HeightTileCache) {
+        for (tx, tz) in self.tile_keys() {
+            cache.level(self.level).unpin_tile(tx, tz);
+        }
+    }
 }
 
+/// What the scheduler reads off the main camera (frustum and orbit focus when present).
+type CameraView = (&'static Transform, Option<&'static Frustum>, Option<&'static CameraOrbit>);
+
 /// Tracks async work and finished-but-not-integrated meshes.
 #[derive(Resource, Default)]
 pub struct AsyncChunkLoader {
@@ -47,111 +103,260 @@ pub struct AsyncChunkLoader {
     pub tasks: Vec<(ChunkTaskInfo, Task<Mesh>)>,
     /// Finished meshes waiting to be integrated into the world
     pub pending: Vec<(ChunkTaskInfo, Mesh)>,
+    /// Priority of every desired chunk this frame (lower builds and integrates first)
+    pub priority: HashMap<(i32, i32), f32>,
+}
+
+/// Smoothed camera motion, for prefetching along the direction of travel.
+#[derive(Default)]
+pub struct CameraMotion {
+    last: Option<Vec3>,
+    velocity: Vec3,
+}
+
+/// Heap entry; `BinaryHeap` is a max-heap, so lower scores compare greater.
+struct ChunkJob {
+    score: f32,
+    key: (i32, i32),
+    lod: LodLevel,
+    edges: EdgeLods,
 }
 
-/// Decide desired chunks/LoD, despawn mismatches, and spawn async jobs for missing pieces.
+impl PartialEq for ChunkJob {
+    fn eq(&self, other: &Self) -> bool {
+        self.cmp(other) == Ordering::Equal
+    }
+}
+impl Eq for ChunkJob {}
+impl PartialOrd for ChunkJob {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+impl Ord for ChunkJob {
+    fn cmp(&self, other: &Self) -> Ordering {
+        other.score.total_cmp(&self.score).then_with(|| other.key.cmp(&self.key))
+    }
+}
+
+/// Decide desired chunks/LoD, despawn mismatches, and spawn async jobs for missing pieces,
+/// most urgent first: in view, close to the focus point, ahead of the camera's motion.
 pub fn async_schedule_chunks(
     mut commands: Commands,
     mut loader: ResMut<AsyncChunkLoader>,
     mut chunk_mgr: ResMut<ChunkManager>,
-    cam_q: Query<&Transform, With<MainCamera>>,
+    cam_q: Query<CameraView, With<MainCamera>>,
+    time: Res<Time>,
+    mut motion: Local<CameraMotion>,
     data: Res<HeightmapData>,
-    mut cache: ResMut<HeightTileCache>,
+    cache: Res<HeightTileCache>,
+    mut dirty: ResMut<DirtyChunks>,
+    manifest: Res<TerrainManifest>,
+    mesh_cache: Option<Res<ChunkMeshCache>>,
     build_budget: Option<Res<MeshBuildBudget>>,
+    mut evw_requested: EventWriter<TerrainChunkRequested>,
+    mut evw_built: EventWriter<TerrainChunkMeshBuilt>,
+    mut evw_unloaded: EventWriter<TerrainChunkUnloaded>,
 ) {
-    let Ok(cam_tf) = cam_q.single() else { return };
+    let Ok((cam_tf, frustum, orbit)) = cam_q.single() else { return };
+
+    // 0) Camera velocity (smoothed so a single jittery frame doesn't swing the prefetch)
+    let eye = cam_tf.translation;
+    let dt = time.delta_secs();
+    if let (Some(last), true) = (motion.last, dt > 0.0) {
+        let v = (eye - last) / dt;
+        let k = (dt * 4.0).min(1.0);
+        motion.velocity = motion.velocity.lerp(v, k);
+    }
+    motion.last = Some(eye);
+    let travel = Vec3::new(motion.velocity.x, 0.0, motion.velocity.z) * PREFETCH_SECS;
+    let lookahead = eye + travel;
 
-    // 1) Compute desired set with LoD
+    // 1) Compute desired set with LoD (plus a ring ahead of a moving camera)
     chunk_mgr.desired.clear();
-    for (cx, cz) in needed_chunks_around(
-        cam_tf.translation,
-        &data,
-        crate::terrain::chunking::CHUNK_RADIUS,
-    ) {
-        let origin = chunk_origin_world(cx, cz, &data);
-        let center = Vec3::new(
-            origin.x + data.chunk_size.x * 0.5,
-            0.0,
-            origin.y + data.chunk_size.y * 0.5,
-        );
-        let dist = cam_tf.translation.truncate().distance(center.truncate());
-        let lod = LodLevel::pick(dist);
+    let mut keys: Vec<(i32, i32)> =
+        needed_chunks_around(eye, &data, crate::terrain::chunking::CHUNK_RADIUS).collect();
+    if travel.length() > PREFETCH_MIN_SPEED * PREFETCH_SECS {
+        keys.extend(needed_chunks_around(lookahead, &data, 1));
+    }
+    for (cx, cz) in keys {
+        // Ground-plane distance: LoD doesn't change with camera height
+        let center = chunk_origin_world(cx, cz, &data) + data.chunk_size * 0.5;
+        let dist = eye.xz().distance(center);
+        let current = chunk_mgr.loaded.get(&(cx, cz)).map(|(_, l)| *l);
+        let lod = LodLevel::pick_sticky(dist, current);
         chunk_mgr.desired.insert((cx, cz), lod);
     }
 
-    // 2) Despawn loaded chunks that are no longer desired or have wrong LoD.
-    //    Also drop any queued/finished work for those chunks.
-    let loaded_keys: Vec<(i32, i32)> = chunk_mgr.loaded.keys().copied().collect();
-    for key in loaded_keys {
-        let loaded_lod = chunk_mgr.loaded.get(&key).map(|(_, l)| *l);
-        let desired_lod = chunk_mgr.desired.get(&key).copied();
-
-        let should_remove = match (loaded_lod, desired_lod) {
-            (Some(ll), Some(dl)) => ll != dl,
-            (Some(_), None) => true,
-            _ => false,
+    // 1b) Each chunk stitches its borders to the LoD its neighbours actually display (not
+    //     the one they are heading to), so when a neighbour's swap lands in
+    //     async_receive_chunks this chunk's edges change and it is rebuilt to match.
+    let wanted_edges: HashMap<(i32, i32), EdgeLods> = chunk_mgr
+        .desired
+        .iter()
+        .map(|(&(cx, cz), &lod)| {
+            let edges = EdgeLods::for_chunk(lod, |dx, dz| {
+                let key = (cx + dx, cz + dz);
+                // Loaded chunks leaving the set are despawned below
+                let shown = chunk_mgr.loaded.get(&key).filter(|_| chunk_mgr.desired.contains_key(&key));
+                shown.map(|(_, l)| *l)
+            });
+            ((cx, cz), edges)
+        })
+        .collect();
+
+    // 2) Despawn loaded chunks that are no longer desired. Chunks at the wrong LoD stay
+    //    up until their replacement is integrated (async_receive_chunks swaps them), so
+    //    LoD changes never leave holes. Queued/finished work that no longer matches the
+    //    desired LoD and edge stitching (or a chunk that left the set) is dropped.
+    {
+        let desired = &chunk_mgr.desired;
+        let current = |info: &ChunkTaskInfo| {
+            let key = (info.cx, info.cz);
+            desired.get(&key) == Some(&info.lod) && wanted_edges.get(&key) == Some(&info.edges)
         };
-
-        if should_remove {
-            if let Some((ent, _)) = chunk_mgr.loaded.remove(&key) {
-                commands.entity(ent).despawn();
+        let AsyncChunkLoader { tasks, pending, .. } = &mut *loader;
+        tasks.retain(|(info, _)| {
+            let keep = current(info);
+            if !keep {
+                info.unpin_tiles(&cache);
             }
-            // Drop outstanding tasks/pending for this (cx,cz)
-            loader.tasks.retain(|(info, _)| !(info.cx == key.0 && info.cz == key.1));
-            loader.pending.retain(|(info, _)| !(info.cx == key.0 && info.cz == key.1));
+            keep
+        });
+        pending.retain(|(info, _)| current(info));
+    }
+
+    let gone: Vec<(i32, i32)> = chunk_mgr
+        .loaded
+        .keys()
+        .filter(|k| !chunk_mgr.desired.contains_key(*k))
+        .copied()
+        .collect();
+    for key in gone {
+        chunk_mgr.edges.remove(&key);
+        if let Some((ent, _)) = chunk_mgr.loaded.remove(&key) {
+            commands.entity(ent).despawn();
+            evw_unloaded.write(TerrainChunkUnloaded(ChunkCoord { x: key.0, z: key.1 }));
         }
     }
 
-    // 3) Launch async jobs for desired-but-missing chunks/LoD (respect a creation budget if provided)
-    let mut started_this_frame = 0usize;
-    let start_budget = build_budget.map(|b| b.0).unwrap_or(usize::MAX);
+    // 2b) Edited chunks: only visible ones get rebuilt; builds already in flight read the
+    //     old heights, so drop them (the loaded mesh stays up until the rebuild lands).
+    dirty.0.retain(|key| chunk_mgr.desired.contains_key(key));
+    for &key in dirty.0.iter() {
+        cancel_chunk_work(&mut loader, key, &cache);
+    }
 
-    'launch: for (&(cx, cz), &lod) in &chunk_mgr.desired {
-        // Already loaded at correct LoD?
+    // 3) Prioritize: distance to the focus (or to where the camera is heading, if closer),
+    //    scaled up for chunks out of view and for chunks that already show a mesh
+    let focus = orbit.map_or(eye, |o| o.focus);
+    let focus_xz = Vec2::new(focus.x, focus.z);
+    let ahead_xz = focus_xz + Vec2::new(travel.x, travel.z);
+    let y_lo = data.void_height.min(0.0);
+    let y_hi = data.void_height.max(data.height_scale);
+    loader.priority.clear();
+    let mut queue: BinaryHeap<ChunkJob> = BinaryHeap::new();
+    for (&(cx, cz), &lod) in &chunk_mgr.desired {
+        let (min_w, max_w) = chunk_world_aabb(cx, cz, &data);
+        let center = (min_w + max_w) * 0.5;
+        let mut score = center.distance(focus_xz).min(center.distance(ahead_xz));
+        let in_view = frustum.is_none_or(|f| {
+            let aabb = Aabb::from_min_max(Vec3::new(min_w.x, y_lo, min_w.y), Vec3::new(max_w.x, y_hi, max_w.y));
+            f.intersects_obb(&aabb, &Affine3A::IDENTITY, true, true)
+        });
+        if !in_view {
+            score *= OUT_OF_VIEW_PENALTY;
+        }
+        if chunk_mgr.loaded.contains_key(&(cx, cz)) {
+            score *= REBUILD_PENALTY;
+        }
+        loader.priority.insert((cx, cz), score);
+
+        let edges = wanted_edges[&(cx, cz)];
+
+        // Already loaded at correct LoD and stitching (and not edited since)?
         if let Some((_, current_lod)) = chunk_mgr.loaded.get(&(cx, cz)) {
-            if *current_lod == lod {
+            if *current_lod == lod
+                && chunk_mgr.edges.get(&(cx, cz)) == Some(&edges)
+                && !dirty.0.contains(&(cx, cz))
+            {
                 continue;
             }
         }
 
-        // Already queued (tasks or pending) for this (cx,cz,lod)?
-        let already_queued =
-            loader.tasks.iter().any(|(i, _)| i.cx == cx && i.cz == cz && i.lod == lod)
-            || loader.pending.iter().any(|(i, _)| i.cx == cx && i.cz == cz && i.lod == lod);
-        if already_queued {
-            continue;
+        // Already queued (tasks or pending) for this (cx,cz,lod,edges)?
+        let same = |i: &ChunkTaskInfo| i.cx == cx && i.cz == cz && i.lod == lod && i.edges == edges;
+        let already_queued = loader.tasks.iter().any(|(i, _)| same(i))
+            || loader.pending.iter().any(|(i, _)| same(i));
+        if !already_queued {
+            queue.push(ChunkJob { score, key: (cx, cz), lod, edges });
         }
+    }
 
+    // 3b) Launch jobs in priority order (respect a creation budget if provided)
+    let mut started_this_frame = 0usize;
+    let start_budget = build_budget.map(|b| b.0).unwrap_or(usize::MAX);
+
+    while let Some(ChunkJob { key: (cx, cz), lod, edges, .. }) = queue.pop() {
         if started_this_frame >= start_budget {
-            break 'launch;
+            break;
         }
 
-        // Snapshot the tiles required for this chunk
-        let cur = cache.fetch_tile(cx, cz);
-        let right = cache.fetch_tile(cx + 1, cz);
-        let up = cache.fetch_tile(cx, cz + 1);
-        let up_right = cache.fetch_tile(cx + 1, cz + 1);
-        let Some(cur) = cur else { continue };
+        // Snapshot the tiles required for this chunk once they are all resident (reads run
+        // on the IO task pool; until then the chunk waits without using the budget).
+        // A tile that doesn't exist (sparse map) becomes a void chunk; one that exists but
+        // can't be read is skipped. Tiles come from the coarsest mip that still has a texel
+        // per vertex; mips keep the full tile's edge texels, so mixed levels don't crack.
+        let level = chunk_tile_level(&cache, lod);
+        let tiles = [(0, 0), (1, 0), (0, 1), (1, 1)]
+            .map(|(dx, dz)| cache.request_tile_level(cx + dx, cz + dz, level));
+        if tiles.iter().any(TileStatus::is_loading) {
+            continue;
+        }
+        let [cur, right, up, up_right] = tiles;
+        let cur = match cur {
+            TileStatus::Ready(t) => Some(t),
+            TileStatus::Unavailable(e) if e.is_missing() => None,
+            _ => continue,
+        };
+        let (right, up, up_right) = (right.ready(), up.ready(), up_right.ready());
+        let void = cur.is_none();
+        let rebuild = dirty.0.contains(&(cx, cz));
+        // Content hashes stored with the resident tiles, for the mesh cache key
+        let store = cache.level(level);
+        let hash = |dx: i32, dz: i32, t: &Option<Tile16>| t.as_ref().map(|t| store.tile_hash(cx + dx, cz + dz, t));
+        let hashes = [hash(0, 0, &cur), hash(1, 0, &right), hash(0, 1, &up), hash(1, 1, &up_right)];
 
         let data_c = data.clone();
-        let grid = lod.grid_res();
+        let mesher = manifest.mesher;
+        let mesh_cache_c = mesh_cache.as_deref().cloned();
 
         let future = async move {
-            build_chunk_mesh_from_tiles(cx, cz, grid, &data_c, cur, right, up, up_right)
-                .unwrap_or_else(|| debug_fallback_quad(cx, cz, &data_c))
+            let tiles = [cur, right, up, up_right];
+            chunk_mesh(cx, cz, lod, edges, mesher, &data_c, tiles, hashes, mesh_cache_c.as_ref())
         };
 
+        let info = ChunkTaskInfo { cx, cz, lod, edges, level, void, rebuild };
+        info.pin_tiles(&cache);
+
         let task = AsyncComputeTaskPool::get().spawn(future);
-        loader.tasks.push((ChunkTaskInfo { cx, cz, lod }, task));
+        loader.tasks.push((info, task));
+        dirty.0.remove(&(cx, cz));
         started_this_frame += 1;
+        evw_requested.write(TerrainChunkRequested { coord: ChunkCoord { x: cx, z: cz }, lod });
     }
 
-    // 4) Poll active tasks; move finished ones into pending
+    // 4) Poll active tasks; move finished ones into pending (and release their tile pins)
     let mut i = 0usize;
     while i < loader.tasks.len() {
         let (info, task) = &mut loader.tasks[i];
         if let Some(mesh) = check_ready(task) {
             let info_c = *info;
+            info_c.unpin_tiles(&cache);
+            evw_built.write(TerrainChunkMeshBuilt {
+                coord: ChunkCoord { x: info_c.cx, z: info_c.cz },
+                lod: info_c.lod,
+            });
             loader.pending.push((info_c, mesh));
             loader.tasks.swap_remove(i);
             continue; // don't advance i when we removed an element
@@ -160,7 +365,8 @@ pub fn async_schedule_chunks(
     }
 }
 
-/// Integrate up to IntegrationBudget finished meshes per frame and emit TerrainChunkLoaded.
+/// Integrate up to IntegrationBudget finished meshes per frame and emit
+/// TerrainChunkLoaded / TerrainChunkLodChanged (see `terrain::events`).
 pub fn async_receive_chunks(
     mut commands: Commands,
     mut loader: ResMut<AsyncChunkLoader>,
@@ -168,30 +374,45 @@ pub fn async_receive_chunks(
     mut materials: ResMut<Assets<StandardMaterial>>,
     mut chunk_mgr: ResMut<ChunkManager>,
     data: Res<HeightmapData>,
+    manifest: Res<TerrainManifest>,
     asset_server: Res<AssetServer>,
     mut evw_chunks_loaded: EventWriter<TerrainChunkLoaded>,
+    mut evw_lod_changed: EventWriter<TerrainChunkLodChanged>,
     integ_budget: Res<IntegrationBudget>,
 ) {
     let mut integrated = 0usize;
-    let mut i = 0usize;
-    while i < loader.pending.len() && integrated < integ_budget.0 {
-        let (info, mesh) = loader.pending.remove(i);
+    while !loader.pending.is_empty() && integrated < integ_budget.0 {
+        // Most urgent finished mesh first (same order the scheduler uses)
+        let urgency = |info: &ChunkTaskInfo| {
+            loader.priority.get(&(info.cx, info.cz)).copied().unwrap_or(f32::INFINITY)
+        };
+        let next = (0..loader.pending.len())
+            .min_by(|&a, &b| urgency(&loader.pending[a].0).total_cmp(&urgency(&loader.pending[b].0)))
+            .unwrap_or(0);
+        let (info, mesh) = loader.pending.swap_remove(next);
         integrated += 1;
 
         // Material + mesh
         let mesh_handle = meshes.add(mesh);
-        let color_path = format!(
-            "{}/{}_y{}_x{}{}",
-            COLOR_FOLDER, COLOR_PREFIX, info.cz, info.cx, COLOR_EXT
-        );
-        let tex: Handle<Image> = asset_server.load(color_path);
-        let mat = materials.add(StandardMaterial {
-            base_color_texture: Some(tex),
-            base_color: Color::WHITE,
-            perceptual_roughness: 1.0,
-            metallic: 0.0,
-            ..default()
-        });
+        let mat = if info.void {
+            // No color tile exists for void chunks either
+            materials.add(StandardMaterial {
+                base_color: Color::srgb(0.18, 0.2, 0.22),
+                perceptual_roughness: 1.0,
+                metallic: 0.0,
+                ..default()
+            })
+        } else {
+            let color_path = manifest.color_tile_path(info.cx, info.cz);
+            let tex: Handle<Image> = asset_server.load(color_path);
+            materials.add(StandardMaterial {
+                base_color_texture: Some(tex),
+                base_color: Color::WHITE,
+                perceptual_roughness: 1.0,
+                metallic: 0.0,
+                ..default()
+            })
+        };
 
         // World placement / AABB
         let (min_w, max_w) = chunk_world_aabb(info.cx, info.cz, &data);
@@ -216,8 +437,25 @@ pub fn async_receive_chunks(
             ))
             .id();
 
-        // Track in manager
-        chunk_mgr.loaded.insert((info.cx, info.cz), (e, info.lod));
+        let key = (info.cx, info.cz);
+        let coord = ChunkCoord { x: info.cx, z: info.cz };
+        // Track in manager; the chunk it replaces (other LoD or pre-edit mesh) goes now,
+        // in the same frame the new one appears
+        let previous_lod = chunk_mgr.loaded.insert(key, (e, info.lod)).map(|(old, old_lod)| {
+            commands.entity(old).despawn();
+            old_lod
+        });
+        chunk_mgr.edges.insert(key, info.edges);
+
+        if let Some(from) = previous_lod {
+            if from != info.lod {
+                evw_lod_changed.write(TerrainChunkLodChanged { coord, from, to: info.lod });
+            }
+            // Already announced; only edits re-announce (props dropped theirs on TerrainDeformed)
+            if !info.rebuild {
+                continue;
+            }
+        }
 
         // Notify props/vegetation
         let min_x = data.origin.x + (info.cx as f32) * data.chunk_size.x;
@@ -226,19 +464,72 @@ pub fn async_receive_chunks(
         let max_z = min_z + data.chunk_size.y;
 
         evw_chunks_loaded.send(TerrainChunkLoaded(ChunkArea {
-            coord: ChunkCoord { x: info.cx, z: info.cz },
+            coord,
             min_xz: Vec2::new(min_x, min_z),
             max_xz: Vec2::new(max_x, max_z),
         }));
     }
 }
 
+/// Drop queued and finished-but-unintegrated builds for a chunk; cancelled tasks release their pins.
+fn cancel_chunk_work(loader: &mut AsyncChunkLoader, key: (i32, i32), cache: &HeightTileCache) {
+    loader.tasks.retain(|(info, _)| {
+        let keep = (info.cx, info.cz) != key;
+        if !keep {
+            info.unpin_tiles(cache);
+        }
+        keep
+    });
+    loader.pending.retain(|(info, _)| (info.cx, info.cz) != key);
+}
+
 // ---------- Mesh building helpers (with `grid_res`) ----------
 
-fn build_chunk_mesh_from_tiles(
+/// Mip level chunk meshes of `lod` read their tiles from: the coarsest with a texel per
+/// vertex. Mips keep the full tile's edge texels, so neighbours on other levels don't crack.
+pub(crate) fn chunk_tile_level(cache: &HeightTileCache, lod: LodLevel) -> u32 {
+    cache.level_for_cells(lod.grid_res().min_element() - 1)
+}
+
+/// Mesh for a chunk: flat at `void_height` without a tile, else from the disk cache when it
+/// holds a current entry, else built (and cached when unstitched).
+fn chunk_mesh(
+    cx: i32,
+    cz: i32,
+    lod: LodLevel,
+    edges: EdgeLods,
+    mesher: ChunkMesher,
+    data: &HeightmapData,
+    [cur, right, up, up_right]: [Option<Tile16>; 4],
+    tile_hashes: [Option<u64>; 4],
+    mesh_cache: Option<&ChunkMeshCache>,
+) -> Mesh {
+    let Some(cur) = cur else { return flat_chunk_quad(cx, cz, data, data.void_height) };
+    let cached = mesh_cache.filter(|_| edges == EdgeLods::uniform(lod)).map(|c| {
+        let key = chunk_mesh_key(lod, mesher, data, tile_hashes);
+        (c, key)
+    });
+    if let Some(mesh) = cached.and_then(|(c, key)| c.load(cx, cz, lod, key)) {
+        return mesh;
+    }
+
+    match build_chunk_mesh_from_tiles(cx, cz, lod.grid_res(), edges, mesher, data, cur, right, up, up_right) {
+        Some(mesh) => {
+            if let Some((c, key)) = cached {
+                c.store_quietly(cx, cz, lod, key, &mesh);
+            }
+            mesh
+        }
+        None => debug_fallback_quad(cx, cz, data),
+    }
+}
+
+pub(crate) fn build_chunk_mesh_from_tiles(
     cx: i32,
     cz: i32,
     grid_res: UVec2,
+    edges: EdgeLods,
+    mesher: ChunkMesher,
     data: &HeightmapData,
     cur: Tile16,
     right: Option<Tile16>,
@@ -344,16 +635,28 @@ fn build_chunk_mesh_from_tiles(
         }
     }
 
-    let mut indices: Vec<u32> = Vec::with_capacity((nx - 1) * (nz - 1) * 6);
-    for j in 0..(nz - 1) {
-        for i in 0..(nx - 1) {
-            let i0 = (j * nx + i) as u32;
-            let i1 = (j * nx + i + 1) as u32;
-            let i2 = ((j + 1) * nx + i) as u32;
-            let i3 = ((j + 1) * nx + i + 1) as u32;
-            indices.extend_from_slice(&[i0, i2, i1, i1, i2, i3]);
+    stitch_edges(&mut positions, nx, nz, edges);
+
+    let indices = match mesher {
+        ChunkMesher::Adaptive { max_error } if Rtin::supports(nx, nz) => {
+            let heights: Vec<f32> = positions.iter().map(|p| p[1]).collect();
+            let indices = Rtin::new(&heights, nx).indices(max_error);
+            compact_vertices(&mut positions, &mut normals, &mut uvs, indices)
         }
-    }
+        _ => {
+            let mut indices: Vec<u32> = Vec::with_capacity((nx - 1) * (nz - 1) * 6);
+            for j in 0..(nz - 1) {
+                for i in 0..(nx - 1) {
+                    let i0 = (j * nx + i) as u32;
+                    let i1 = (j * nx + i + 1) as u32;
+                    let i2 = ((j + 1) * nx + i) as u32;
+                    let i3 = ((j + 1) * nx + i + 1) as u32;
+                    indices.extend_from_slice(&[i0, i2, i1, i1, i2, i3]);
+                }
+            }
+            indices
+        }
+    };
 
     let mut mesh = Mesh::new(PrimitiveTopology::TriangleList, Default::default());
     mesh.insert_attribute(Mesh::ATTRIBUTE_POSITION, positions);
@@ -363,16 +666,76 @@ fn build_chunk_mesh_from_tiles(
     Some(mesh)
 }
 
+/// Drop vertices no triangle uses and renumber `indices` to match.
+fn compact_vertices(
+    positions: &mut Vec<[f32; 3]>,
+    normals: &mut Vec<[f32; 3]>,
+    uvs: &mut Vec<[f32; 2]>,
+    mut indices: Vec<u32>,
+) -> Vec<u32> {
+    let mut remap = vec![u32::MAX; positions.len()];
+    let (mut p, mut n, mut t) = (Vec::new(), Vec::new(), Vec::new());
+    for idx in indices.iter_mut() {
+        let old = *idx as usize;
+        if remap[old] == u32::MAX {
+            remap[old] = p.len() as u32;
+            p.push(positions[old]);
+            n.push(normals[old]);
+            t.push(uvs[old]);
+        }
+        *idx = remap[old];
+    }
+    *positions = p;
+    *normals = n;
+    *uvs = t;
+    indices
+}
+
+/// Snap border vertices that a coarser neighbour doesn't have onto the straight line
+/// between the ones it does, so both meshes share the exact same edge.
+fn stitch_edges(positions: &mut [[f32; 3]], nx: usize, nz: usize, edges: EdgeLods) {
+    // (edge resolution, vertex count along the edge, index of the k-th vertex)
+    type Side<'a> = (LodLevel, usize, &'a dyn Fn(usize) -> usize);
+    let sides: [Side; 4] = [
+        (edges.left, nz, &|k| k * nx),
+        (edges.right, nz, &|k| k * nx + nx - 1),
+        (edges.down, nx, &|k| k),
+        (edges.up, nx, &|k| (nz - 1) * nx + k),
+    ];
+    for (lod, n, index) in sides {
+        let coarse_n = lod.grid_res().x as usize;
+        if coarse_n < 2 || coarse_n >= n || (n - 1) % (coarse_n - 1) != 0 {
+            continue;
+        }
+        let step = (n - 1) / (coarse_n - 1);
+        for k in 0..n {
+            let r = k % step;
+            if r == 0 {
+                continue;
+            }
+            let (k0, k1) = (k - r, k - r + step);
+            let t = r as f32 / step as f32;
+            let h = positions[index(k0)][1] * (1.0 - t) + positions[index(k1)][1] * t;
+            positions[index(k)][1] = h;
+        }
+    }
+}
+
 fn debug_fallback_quad(cx: i32, cz: i32, data: &HeightmapData) -> Mesh {
+    flat_chunk_quad(cx, cz, data, 0.0)
+}
+
+/// One quad covering the chunk at height `y` (void chunks of sparse maps).
+fn flat_chunk_quad(cx: i32, cz: i32, data: &HeightmapData, y: f32) -> Mesh {
     let (min_w, max_w) = chunk_world_aabb(cx, cz, data);
     let width = max_w.x - min_w.x;
     let depth = max_w.y - min_w.y;
 
     let positions = vec![
-        [min_w.x, 0.0, min_w.y],
-        [min_w.x + width, 0.0, min_w.y],
-        [min_w.x, 0.0, min_w.y + depth],
-        [min_w.x + width, 0.0, min_w.y + depth],
+        [min_w.x, y, min_w.y],
+        [min_w.x + width, y, min_w.y],
+        [min_w.x, y, min_w.y + depth],
+        [min_w.x + width, y, min_w.y + depth],
     ];
     let uvs = vec![[0.0, 0.0], [1.0, 0.0], [0.0, 1.0], [1.0, 1.0]];
     let normals = vec![[0.0, 1.0, 0.0]; 4];
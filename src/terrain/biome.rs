@@ -0,0 +1,627 @@
+// src/terrain/biome.rs
+//! Biome classification. A `BiomeRules` asset (`*.biomes.ron`, named by the manifest's
+//! `biomes`) lists biomes Whittaker-style: each one claims the locations whose temperature
+//! and moisture fall in its ranges, optionally narrowed by altitude, slope and distance to
+//! water. Temperature and moisture come from a small climate model (`ClimateDef`): a lapse
+//! rate with altitude, warmer sun-facing slopes, moisture rising towards water, and seeded
+//! noise on both.
+//!
+//! `BiomeLayer` classifies a chunk into a `BiomeRaster` the first time it is asked about
+//! it, and answers `BiomeMask` queries for prop placement (`CommonFilters::biome_mask_any`).
+//! Distance to water is computed once per map, on a coarse grid (`water_cell_size`).
+//! Press B to tint loaded chunks with each biome's debug color.
+
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex, OnceLock};
+
+use bevy::asset::{io::Reader, AssetLoader, LoadContext, RenderAssetUsages};
+use bevy::ecs::system::SystemParam;
+use bevy::image::ImageSampler;
+use bevy::prelude::*;
+use bevy::render::render_resource::{Extent3d, TextureDimension, TextureFormat};
+use bevy::tasks::{AsyncComputeTaskPool, IoTaskPool};
+use serde::{Deserialize, Serialize};
+
+use crate::heightmap_data::{
+    height_in_tile, locate_in_tile, HeightTileCache, HeightTileError, HeightmapData, Tile16, TileStatus,
+};
+use crate::props::core::{BiomeMask, BiomeSampler, WorldSeed, DEFAULT_WORLD_SEED};
+use crate::terrain::analysis::TerrainAttributes;
+use crate::terrain::components::ChunkKey;
+use crate::terrain::deform::TerrainDeformed;
+use crate::terrain::events::TerrainChunkUnloaded;
+use crate::terrain::grid::{nearest_source, sample_grid, FAR};
+use crate::terrain::manifest::TerrainManifest;
+use crate::terrain::procedural::{gradient_noise, mix64};
+use crate::terrain::water::SettledWaterLevel;
+
+/// `BiomeRaster` cell with no matching biome.
+pub const NO_BIOME: u8 = u8::MAX;
+
+// ---------- Public plugin to register asset+loader ----------
+
+pub struct BiomeRulesAssetPlugin;
+
+impl Plugin for BiomeRulesAssetPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_asset::<BiomeRules>()
+            .register_asset_loader(BiomeRulesLoader);
+    }
+}
+
+// ---------- Rules (data form) ----------
+
+/// Biome table of a map, tried in order: the first biome whose ranges all hold wins, so
+/// list narrow biomes (cliffs, beaches) before the broad temperature/moisture ones.
+#[derive(Asset, TypePath, Clone, Debug, Serialize, Deserialize)]
+pub struct BiomeRules {
+    #[serde(default)]
+    pub climate: ClimateDef,
+    /// Raster cells per chunk side (edge cells sit on the chunk's edges).
+    #[serde(default = "default_resolution")]
+    pub resolution: u32,
+    pub biomes: Vec<BiomeDef>,
+}
+
+fn default_resolution() -> u32 {
+    65
+}
+
+/// Temperature and moisture model.
+#[derive(Clone, Copy, Debug, Serialize, Deserialize)]
+#[serde(default)]
+pub struct ClimateDef {
+    /// Mean temperature (°C) at the water level.
+    pub sea_level_temperature: f32,
+    /// Cooling (°C) per 1000 m above the water level.
+    pub lapse_rate: f32,
+    /// Extra warmth (°C) of a 45° slope facing `sun_bearing`; the opposite side is as much colder.
+    pub aspect_warming: f32,
+    /// Downhill bearing facing the sun (degrees, as `TerrainAttributes::aspect_deg`).
+    pub sun_bearing: f32,
+    /// Moisture (0..1) far from water.
+    pub base_moisture: f32,
+    /// Moisture added at the shore, fading with distance.
+    pub water_moisture: f32,
+    /// Distance (meters) over which the shore's moisture falls to about a third.
+    pub water_falloff: f32,
+    /// How far (meters) to look for water; anything farther counts as this far.
+    pub max_water_distance: f32,
+    /// Grid spacing (meters) of the map-wide distance to water.
+    pub water_cell_size: f32,
+    /// Wavelength (meters) of the climate noise.
+    pub noise_scale: f32,
+    /// Noise amplitude on temperature (°C) and on moisture.
+    pub temperature_noise: f32,
+    pub moisture_noise: f32,
+}
+
+impl Default for ClimateDef {
+    fn default() -> Self {
+        Self {
+            sea_level_temperature: 15.0,
+            lapse_rate: 6.5,
+            aspect_warming: 2.0,
+            sun_bearing: 180.0,
+            base_moisture: 0.4,
+            water_moisture: 0.4,
+            water_falloff: 150.0,
+            max_water_distance: 256.0,
+            water_cell_size: 8.0,
+            noise_scale: 2000.0,
+            temperature_noise: 3.0,
+            moisture_noise: 0.2,
+        }
+    }
+}
+
+/// One biome: where it occurs and how it shows up.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct BiomeDef {
+    pub name: String,
+    /// Bit this biome sets in `BiomeMask` (0..32).
+    pub bit: u8,
+    /// Debug overlay color (sRGB, 0..1).
+    pub color: [f32; 3],
+    /// °C
+    #[serde(default)]
+    pub temperature: Bounds,
+    /// 0..1
+    #[serde(default)]
+    pub moisture: Bounds,
+    /// Meters.
+    #[serde(default)]
+    pub altitude: Bounds,
+    #[serde(default)]
+    pub slope_deg: Bounds,
+    /// Meters to the nearest ground under the water level.
+    #[serde(default)]
+    pub water_distance: Bounds,
+}
+
+impl BiomeDef {
+    pub fn mask(&self) -> BiomeMask {
+        BiomeMask(1 << self.bit)
+    }
+
+    pub fn matches(&self, s: &BiomeSample) -> bool {
+        self.temperature.contains(s.temperature)
+            && self.moisture.contains(s.moisture)
+            && self.altitude.contains(s.altitude)
+            && self.slope_deg.contains(s.slope_deg)
+            && self.water_distance.contains(s.water_distance)
+    }
+}
+
+/// Inclusive range; a missing end is open (`(max: Some(40.0))`).
+#[derive(Clone, Copy, Debug, Default, Serialize, Deserialize)]
+pub struct Bounds {
+    #[serde(default)]
+    pub min: Option<f32>,
+    #[serde(default)]
+    pub max: Option<f32>,
+}
+
+impl Bounds {
+    pub fn contains(&self, v: f32) -> bool {
+        self.min.is_none_or(|min| v >= min) && self.max.is_none_or(|max| v <= max)
+    }
+}
+
+/// What the rules see at one location.
+#[derive(Clone, Copy, Debug)]
+pub struct BiomeSample {
+    pub altitude: f32,
+    pub slope_deg: f32,
+    pub water_distance: f32,
+    pub temperature: f32,
+    pub moisture: f32,
+}
+
+impl BiomeRules {
+    /// Index of the first biome matching `sample`.
+    pub fn classify(&self, sample: &BiomeSample) -> Option<usize> {
+        self.biomes.iter().position(|b| b.matches(sample))
+    }
+
+    pub fn validate(&self) -> Result<(), BiomeRulesLoadError> {
+        if self.resolution < 2 {
+            return Err(BiomeRulesLoadError::Invalid("resolution must be at least 2".into()));
+        }
+        if self.biomes.len() >= NO_BIOME as usize {
+            return Err(BiomeRulesLoadError::Invalid(format!("at most {} biomes", NO_BIOME)));
+        }
+        if let Some(b) = self.biomes.iter().find(|b| b.bit >= 32) {
+            return Err(BiomeRulesLoadError::Invalid(format!("biome '{}': bit must be below 32", b.name)));
+        }
+        Ok(())
+    }
+}
+
+// ---------- Classification ----------
+
+/// Biome indices (into `BiomeRules::biomes`, or `NO_BIOME`) over one chunk, row-major.
+#[derive(Clone, Debug)]
+pub struct BiomeRaster {
+    pub res: UVec2,
+    pub cells: Vec<u8>,
+}
+
+impl BiomeRaster {
+    /// Nearest cell at normalized `uv` inside the chunk.
+    pub fn at(&self, uv: Vec2) -> u8 {
+        let max = (self.res - UVec2::ONE).as_vec2();
+        let p = (uv.clamp(Vec2::ZERO, Vec2::ONE) * max).round().as_uvec2();
+        self.cells[(p.y * self.res.x + p.x) as usize]
+    }
+}
+
+type RasterSlot = Arc<OnceLock<Arc<BiomeRaster>>>;
+
+/// Meters to the nearest ground under the water level, capped at
+/// `ClimateDef::max_water_distance`, on a grid over the whole map.
+struct WaterDistances {
+    origin: Vec2,
+    cell: f32,
+    size: UVec2,
+    distance: Vec<f32>,
+}
+
+impl WaterDistances {
+    fn compute(data: &HeightmapData, cache: &HeightTileCache, water_level: f32, climate: &ClimateDef) -> Self {
+        let cell = climate.water_cell_size.max(0.5);
+        let size = (data.size / cell).ceil().as_uvec2() + UVec2::ONE;
+        let wet: Vec<bool> = sample_grid(data.origin, Vec2::splat(cell), size, data, cache)
+            .into_iter()
+            .map(|h| h.is_some_and(|h| h <= water_level))
+            .collect();
+        let max_dist = climate.max_water_distance.max(0.0);
+        let distance = nearest_source(&wet, size, Vec2::splat(cell))
+            .into_iter()
+            .map(|(d, _)| if d >= FAR { max_dist } else { (d.sqrt() as f32).min(max_dist) })
+            .collect();
+        Self { origin: data.origin, cell, size, distance }
+    }
+
+    /// Bilinear distance at a world position (clamped to the grid).
+    fn at(&self, p: Vec2) -> f32 {
+        let max = (self.size - UVec2::ONE).as_vec2();
+        let g = ((p - self.origin) / self.cell).clamp(Vec2::ZERO, max);
+        let i0 = g.floor().as_uvec2();
+        let i1 = (i0 + UVec2::ONE).min(self.size - UVec2::ONE);
+        let f = g - i0.as_vec2();
+        let at = |i: u32, j: u32| self.distance[(j * self.size.x + i) as usize];
+        let a = at(i0.x, i0.y) + (at(i1.x, i0.y) - at(i0.x, i0.y)) * f.x;
+        let b = at(i0.x, i1.y) + (at(i1.x, i1.y) - at(i0.x, i1.y)) * f.x;
+        a + (b - a) * f.y
+    }
+}
+
+/// Biome classification of the current map. Cheap to clone; clones share the rasters.
+#[derive(Resource, Clone)]
+pub struct BiomeLayer {
+    pub rules: Arc<BiomeRules>,
+    pub data: HeightmapData,
+    pub cache: HeightTileCache,
+    pub water_level: f32,
+    pub seed: u64,
+    rasters: Arc<Mutex<HashMap<(i32, i32), RasterSlot>>>,
+    water: Arc<OnceLock<WaterDistances>>,
+}
+
+impl BiomeLayer {
+    pub fn new(rules: BiomeRules, data: HeightmapData, cache: HeightTileCache, water_level: f32, seed: u64) -> Self {
+        Self {
+            rules: Arc::new(rules),
+            data,
+            cache,
+            water_level,
+            seed,
+            rasters: Arc::default(),
+            water: Arc::default(),
+        }
+    }
+
+    /// Start computing the distance to water on the IO task pool (it reads every tile, at
+    /// the mip matching `water_cell_size`); `request_raster` waits for it.
+    pub fn prepare(&self) {
+        let layer = self.clone();
+        IoTaskPool::get()
+            .spawn(async move {
+                layer.water_distances();
+            })
+            .detach();
+    }
+
+    /// The map's distance to water, computing it now if nobody has (blocking).
+    fn water_distances(&self) -> &WaterDistances {
+        self.water
+            .get_or_init(|| WaterDistances::compute(&self.data, &self.cache, self.water_level, &self.rules.climate))
+    }
+
+    fn slot(&self, cx: i32, cz: i32) -> (RasterSlot, bool) {
+        let mut rasters = self.rasters.lock().expect("biome raster mutex poisoned");
+        match rasters.get(&(cx, cz)) {
+            Some(slot) => (slot.clone(), false),
+            None => {
+                let slot = RasterSlot::default();
+                rasters.insert((cx, cz), slot.clone());
+                (slot, true)
+            }
+        }
+    }
+
+    /// The chunk's raster; `None` until it has been classified. Classification starts on
+    /// the compute task pool once the distance to water and the chunk's tile are ready
+    /// (the tile is requested from the IO task pool meanwhile), so nothing here blocks.
+    pub fn request_raster(&self, cx: i32, cz: i32) -> Option<Arc<BiomeRaster>> {
+        self.water.get()?;
+        if let Some(raster) = self.rasters.lock().expect("biome raster mutex poisoned").get(&(cx, cz)) {
+            return raster.get().cloned();
+        }
+        let tile = match self.cache.request_tile(cx, cz) {
+            TileStatus::Loading => return None,
+            TileStatus::Ready(tile) => Ok(tile),
+            TileStatus::Unavailable(e) => Err(e),
+        };
+        let (slot, new) = self.slot(cx, cz);
+        if new {
+            let layer = self.clone();
+            AsyncComputeTaskPool::get()
+                .spawn(async move {
+                    slot.get_or_init(|| Arc::new(layer.classify_chunk(cx, cz, &tile)));
+                })
+                .detach();
+        }
+        None
+    }
+
+    /// Drop a chunk's raster (its terrain changed, or it left the streamed set).
+    pub fn forget(&self, cx: i32, cz: i32) {
+        self.rasters.lock().expect("biome raster mutex poisoned").remove(&(cx, cz));
+    }
+
+    /// What the rules see at world position `at`, given the ground there.
+    pub fn sample(&self, at: Vec2, altitude: f32, ground: &TerrainAttributes, water_distance: f32) -> BiomeSample {
+        let c = &self.rules.climate;
+
+        let p = at / c.noise_scale.max(f32::EPSILON);
+        let facing = (ground.aspect_deg - c.sun_bearing).to_radians().cos();
+        let sun = c.aspect_warming * facing * ground.slope_deg.to_radians().sin() / std::f32::consts::FRAC_1_SQRT_2;
+        let temperature = c.sea_level_temperature - c.lapse_rate * (altitude - self.water_level).max(0.0) / 1000.0
+            + sun
+            + c.temperature_noise * gradient_noise(mix64(self.seed ^ 0x7e3a_0001), p);
+
+        let shore = (-water_distance / c.water_falloff.max(f32::EPSILON)).exp();
+        let moisture = c.base_moisture
+            + c.water_moisture * shore
+            + c.moisture_noise * gradient_noise(mix64(self.seed ^ 0x7e3a_0002), p);
+
+        BiomeSample {
+            altitude,
+            slope_deg: ground.slope_deg,
+            water_distance,
+            temperature,
+            moisture: moisture.clamp(0.0, 1.0),
+        }
+    }
+
+    /// Classify chunk (cx, cz) from its tile as fetched: a missing tile is flat ground at
+    /// the void height, an unreadable one gets no biome.
+    fn classify_chunk(&self, cx: i32, cz: i32, tile: &Result<Tile16, HeightTileError>) -> BiomeRaster {
+        let res = UVec2::splat(self.rules.resolution.max(2));
+        let cells_n = (res.x * res.y) as usize;
+        let tile = match tile {
+            Ok(t) => Some(t),
+            Err(e) if e.is_missing() => None,
+            Err(_) => return BiomeRaster { res, cells: vec![NO_BIOME; cells_n] },
+        };
+        let analysis = tile.and_then(|t| self.cache.analysis_for(cx, cz, t));
+        let min = self.data.origin + Vec2::new(cx as f32, cz as f32) * self.data.chunk_size;
+        let water = self.water_distances();
+
+        let mut cells = Vec::with_capacity(cells_n);
+        for j in 0..res.y {
+            for i in 0..res.x {
+                let uv = Vec2::new(i as f32, j as f32) / (res - UVec2::ONE).as_vec2();
+                let p = min + uv * self.data.chunk_size;
+                let (altitude, ground) = match tile {
+                    Some(t) => (
+                        height_in_tile(t, uv, &self.data),
+                        analysis.as_ref().map_or(TerrainAttributes::FLAT, |a| a.sample(uv)),
+                    ),
+                    None => (self.data.void_height, TerrainAttributes::FLAT),
+                };
+                let sample = self.sample(p, altitude, &ground, water.at(p));
+                cells.push(self.rules.classify(&sample).map_or(NO_BIOME, |b| b as u8));
+            }
+        }
+        BiomeRaster { res, cells }
+    }
+}
+
+impl BiomeSampler for BiomeLayer {
+    fn biome_mask(&self, x: f32, z: f32) -> Option<BiomeMask> {
+        // Placement runs on worker threads: never classify here, a chunk that isn't yet
+        // knows nothing (placement waits for its own chunk's raster)
+        let ((cx, cz), uv) = locate_in_tile(x, z, &self.data)?;
+        let biome = self.request_raster(cx, cz)?.at(uv);
+        Some(self.rules.biomes.get(biome as usize).map_or(BiomeMask::NONE, BiomeDef::mask))
+    }
+}
+
+// ---------- Systems ----------
+
+#[derive(Resource, Default)]
+pub struct BiomeRulesHandle(pub Option<Handle<BiomeRules>>);
+
+/// Load the biome rules the active manifest names (at startup, on edits and map switches).
+pub fn load_biome_rules(
+    mut commands: Commands,
+    manifest: Res<TerrainManifest>,
+    mut handle: ResMut<BiomeRulesHandle>,
+    assets: Res<AssetServer>,
+) {
+    if !manifest.is_changed() {
+        return;
+    }
+    handle.0 = manifest.biomes.as_ref().map(|path| assets.load(path.as_str()));
+    if handle.0.is_none() {
+        commands.remove_resource::<BiomeLayer>();
+    }
+}
+
+/// The map a `BiomeLayer` classifies.
+#[derive(SystemParam)]
+pub struct BiomeTerrain<'w> {
+    data: Res<'w, HeightmapData>,
+    cache: Res<'w, HeightTileCache>,
+    water: Res<'w, SettledWaterLevel>,
+    seed: Option<Res<'w, WorldSeed>>,
+}
+
+/// (Re)build `BiomeLayer` when its rules load or change, or the map's terrain or settled
+/// water level does.
+pub fn apply_biome_rules(
+    mut commands: Commands,
+    mut events: EventReader<AssetEvent<BiomeRules>>,
+    handle: Res<BiomeRulesHandle>,
+    rules: Res<Assets<BiomeRules>>,
+    terrain: BiomeTerrain,
+) {
+    let BiomeTerrain { data, cache, water, seed } = terrain;
+    let Some(handle) = &handle.0 else {
+        events.clear();
+        return;
+    };
+    let mut dirty = cache.is_changed() || water.is_changed();
+    for ev in events.read() {
+        match ev {
+            AssetEvent::LoadedWithDependencies { id } | AssetEvent::Modified { id } => {
+                dirty |= *id == handle.id();
+            }
+            _ => {}
+        }
+    }
+    if !dirty {
+        return;
+    }
+    // Still loading: its LoadedWithDependencies event brings us back
+    let Some(rules) = rules.get(handle) else { return };
+
+    info!("Terrain: {} biomes", rules.biomes.len());
+    let seed = seed.map_or(DEFAULT_WORLD_SEED, |s| s.0);
+    let layer = BiomeLayer::new(rules.clone(), data.clone(), cache.clone(), water.0, seed);
+    layer.prepare();
+    commands.insert_resource(layer);
+}
+
+/// Reclassify edited chunks when next asked, and forget chunks that left.
+pub fn forget_stale_biomes(
+    layer: Res<BiomeLayer>,
+    mut deformed: EventReader<TerrainDeformed>,
+    mut unloaded: EventReader<TerrainChunkUnloaded>,
+) {
+    for ev in deformed.read() {
+        for &(cx, cz) in &ev.chunks {
+            layer.forget(cx, cz);
+        }
+    }
+    for TerrainChunkUnloaded(coord) in unloaded.read() {
+        layer.forget(coord.x, coord.z);
+    }
+}
+
+// ---------- Debug overlay ----------
+
+/// Tint chunks with their biome colors (toggled with B).
+#[derive(Resource, Default)]
+pub struct BiomeOverlay {
+    pub enabled: bool,
+}
+
+/// The overlay mesh, a child of its chunk.
+#[derive(Component)]
+pub struct BiomeOverlayMesh;
+
+/// On chunks that have their overlay.
+#[derive(Component)]
+pub struct BiomeOverlayShown;
+
+pub fn toggle_biome_overlay(keys: Res<ButtonInput<KeyCode>>, mut overlay: ResMut<BiomeOverlay>) {
+    if keys.just_pressed(KeyCode::KeyB) {
+        overlay.enabled = !overlay.enabled;
+        info!("Terrain: biome overlay {}", if overlay.enabled { "on" } else { "off" });
+    }
+}
+
+/// Where overlay textures and materials go.
+#[derive(SystemParam)]
+pub struct OverlayAssets<'w> {
+    images: ResMut<'w, Assets<Image>>,
+    materials: ResMut<'w, Assets<StandardMaterial>>,
+}
+
+/// Give each loaded chunk a translucent copy of its mesh textured with its biome raster
+/// (once the raster is ready), or take them all away.
+pub fn update_biome_overlay(
+    mut commands: Commands,
+    overlay: Res<BiomeOverlay>,
+    layer: Option<Res<BiomeLayer>>,
+    chunks: Query<(Entity, &ChunkKey, &Mesh3d), Without<BiomeOverlayShown>>,
+    shown: Query<Entity, With<BiomeOverlayShown>>,
+    overlays: Query<Entity, With<BiomeOverlayMesh>>,
+    mut assets: OverlayAssets,
+) {
+    let stale = !overlay.enabled || layer.as_ref().is_none_or(|l| l.is_changed());
+    if stale {
+        for e in &overlays {
+            commands.entity(e).despawn();
+        }
+        for e in &shown {
+            commands.entity(e).remove::<BiomeOverlayShown>();
+        }
+    }
+    let Some(layer) = layer.filter(|_| overlay.enabled) else { return };
+
+    for (chunk, key, mesh) in &chunks {
+        let Some(raster) = layer.request_raster(key.cx, key.cz) else { continue };
+        let image = assets.images.add(overlay_image(&raster, &layer.rules));
+        let material = assets.materials.add(StandardMaterial {
+            base_color_texture: Some(image),
+            alpha_mode: AlphaMode::Blend,
+            unlit: true,
+            depth_bias: 100.0,
+            ..default()
+        });
+        commands.entity(chunk).insert(BiomeOverlayShown).with_child((
+            BiomeOverlayMesh,
+            Mesh3d(mesh.0.clone()),
+            MeshMaterial3d(material),
+            Transform::from_xyz(0.0, 0.05, 0.0),
+        ));
+    }
+}
+
+fn overlay_image(raster: &BiomeRaster, rules: &BiomeRules) -> Image {
+    let mut texels = Vec::with_capacity(raster.cells.len() * 4);
+    for &cell in &raster.cells {
+        match rules.biomes.get(cell as usize) {
+            Some(b) => {
+                let [r, g, b] = b.color.map(|c| (c.clamp(0.0, 1.0) * 255.0).round() as u8);
+                texels.extend_from_slice(&[r, g, b, 150]);
+            }
+            None => texels.extend_from_slice(&[0, 0, 0, 0]),
+        }
+    }
+    let mut image = Image::new(
+        Extent3d { width: raster.res.x, height: raster.res.y, depth_or_array_layers: 1 },
+        TextureDimension::D2,
+        texels,
+        TextureFormat::Rgba8UnormSrgb,
+        RenderAssetUsages::RENDER_WORLD,
+    );
+    image.sampler = ImageSampler::nearest();
+    image
+}
+
+// ---------- Asset loader for `.biomes.ron` ----------
+
+#[derive(Default)]
+pub struct BiomeRulesLoader;
+
+impl AssetLoader for BiomeRulesLoader {
+    type Asset = BiomeRules;
+    type Settings = ();
+    type Error = BiomeRulesLoadError;
+
+    fn extensions(&self) -> &[&str] {
+        &["biomes.ron"]
+    }
+
+    async fn load(
+        &self,
+        reader: &mut dyn Reader,
+        _settings: &Self::Settings,
+        _load_context: &mut LoadContext<'_>,
+    ) -> Result<Self::Asset, Self::Error> {
+        let mut bytes = Vec::new();
+        reader.read_to_end(&mut bytes).await?;
+        let rules: BiomeRules =
+            ron::de::from_bytes(&bytes).map_err(|e| BiomeRulesLoadError::Ron(e.to_string()))?;
+        rules.validate()?;
+        Ok(rules)
+    }
+}
+
+// ---------- Loader errors ----------
+
+#[derive(thiserror::Error, Debug)]
+pub enum BiomeRulesLoadError {
+    #[error("I/O while reading biome rules: {0}")]
+    Io(#[from] std::io::Error),
+    #[error("RON parse error: {0}")]
+    Ron(String),
+    #[error("Invalid biome rules: {0}")]
+    Invalid(String),
+}
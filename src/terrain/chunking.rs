@@ -4,7 +4,7 @@ use bevy::prelude::*;
 use std::collections::{HashMap, HashSet};
 
 use crate::heightmap_data::HeightmapData;
-use crate::terrain::lod::LodLevel;
+use crate::terrain::lod::{EdgeLods, LodLevel};
 
 /// How many chunks around the focus center to keep
 pub const CHUNK_RADIUS: i32 = 2;
@@ -16,6 +16,8 @@ pub struct ChunkManager {
     pub loaded: HashMap<(i32, i32), (Entity, LodLevel)>,
     /// Desired set with LoD chosen for each key, cleared each frame by scheduler
     pub desired: HashMap<(i32, i32), LodLevel>,
+    /// Border resolutions the loaded mesh of each chunk was stitched with
+    pub edges: HashMap<(i32, i32), EdgeLods>,
 }
 
 impl ChunkManager {
@@ -23,6 +25,7 @@ impl ChunkManager {
         Self {
             loaded: HashMap::new(),
             desired: HashMap::new(),
+            edges: HashMap::new(),
         }
     }
 }
@@ -98,6 +101,8 @@ pub fn clamp_world_to_terrain(mut world_xz: Vec2, data: &HeightmapData) -> Vec2
 /// Iterate all chunk keys that should be present around a center point (camera/hero).
 /// - radius: how many rings around the center (use CHUNK_RADIUS)
 /// - The set is clipped to terrain bounds (no negative or overflow indices).
+/// - Keys without a height tile (sparse maps) are still yielded; they stream in
+///   as flat chunks at `HeightmapData::void_height`.
 pub fn needed_chunks_around(
     center_world: Vec3,
     data: &HeightmapData,
@@ -109,10 +114,12 @@ pub fn needed_chunks_around(
     let mut center = Vec2::new(center_world.x, center_world.z);
     center = clamp_world_to_terrain(center, data);
 
-    let ((ccx, ccz), _) = world_to_chunk_local(center, data)
-        .expect("clamped center must lie inside terrain");
-
+    // Only fails for an empty map (zero-sized terrain)
     let mut keys: Vec<(i32, i32)> = Vec::new();
+    let Some(((ccx, ccz), _)) = world_to_chunk_local(center, data) else {
+        return keys.into_iter();
+    };
+
     for dz in -radius..=radius {
         for dx in -radius..=radius {
             let cx = ccx + dx;
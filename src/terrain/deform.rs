@@ -0,0 +1,330 @@
+// src/terrain/deform.rs
+//! Runtime height edits: craters, build-site flattening, editor sculpting.
+//!
+//! Brushes write straight into the shared tile store; edited tiles stay pinned there
+//! (the files on disk are untouched). Chunks whose mesh reads an edited tile are queued in
+//! `DirtyChunks` and rebuilt by `async_schedule_chunks` at their current LoD, and
+//! `TerrainDeformed` tells props to re-place. Units re-snap on their own through
+//! `grounding_system`. When the cache has an edit layer, every stamp is also recorded
+//! there and saved as a delta next to the tile (see `edit_layer`).
+
+use std::collections::{HashMap, HashSet};
+use std::sync::Arc;
+
+use bevy::prelude::*;
+
+use crate::heightmap_data::{HeightTileCache, HeightmapData, Tile16};
+use crate::terrain::chunking::{chunk_counts, ChunkCounts};
+
+/// Footprint of a brush stamp (world units).
+#[derive(Clone, Copy, Debug)]
+pub enum BrushShape {
+    Circle { radius: f32 },
+    /// Rotated rectangle; `rotation` in radians around +Y.
+    Rect { half_extents: Vec2, rotation: f32 },
+}
+
+/// What a stamp does to the heights under it (meters).
+#[derive(Clone, Copy, Debug)]
+pub enum BrushOp {
+    Set(f32),
+    Raise(f32),
+    Lower(f32),
+    /// Blend toward the 3x3 texel average; strength 0..1 per stamp.
+    Smooth(f32),
+    /// Level to a height, or to the mean height under the footprint when `None`.
+    Flatten(Option<f32>),
+}
+
+#[derive(Clone, Copy, Debug)]
+pub struct TerrainBrush {
+    /// World XZ center of the stamp.
+    pub center: Vec2,
+    pub shape: BrushShape,
+    /// Fraction (0..1) of the footprint, from the rim inward, over which the effect fades in.
+    pub falloff: f32,
+    pub op: BrushOp,
+}
+
+impl TerrainBrush {
+    pub fn new(center: Vec2, shape: BrushShape, op: BrushOp) -> Self {
+        Self { center, shape, falloff: 0.25, op }
+    }
+
+    pub fn with_falloff(mut self, falloff: f32) -> Self {
+        self.falloff = falloff;
+        self
+    }
+
+    /// 0 outside the footprint, 1 in its core, smoothstep across the falloff band.
+    pub fn weight(&self, p: Vec2) -> f32 {
+        let d = p - self.center;
+        // Normalized distance: 0 at the center, 1 on the rim
+        let r = match self.shape {
+            BrushShape::Circle { radius } => d.length() / radius.max(f32::EPSILON),
+            BrushShape::Rect { half_extents, rotation } => {
+                let local = Vec2::from_angle(-rotation).rotate(d);
+                (local.abs() / half_extents.max(Vec2::splat(f32::EPSILON))).max_element()
+            }
+        };
+        if r >= 1.0 {
+            return 0.0;
+        }
+        let band = self.falloff.clamp(0.0, 1.0);
+        if band <= 0.0 || r <= 1.0 - band {
+            return 1.0;
+        }
+        let t = (1.0 - r) / band;
+        t * t * (3.0 - 2.0 * t)
+    }
+
+    /// World XZ bounds of the footprint.
+    pub fn bounds(&self) -> (Vec2, Vec2) {
+        let ext = match self.shape {
+            BrushShape::Circle { radius } => Vec2::splat(radius),
+            BrushShape::Rect { half_extents, rotation } => {
+                let (s, c) = rotation.sin_cos();
+                Vec2::new(
+                    half_extents.x * c.abs() + half_extents.y * s.abs(),
+                    half_extents.x * s.abs() + half_extents.y * c.abs(),
+                )
+            }
+        };
+        (self.center - ext, self.center + ext)
+    }
+}
+
+/// Chunks whose meshes are stale after an edit; drained by `async_schedule_chunks`.
+#[derive(Resource, Default, Debug)]
+pub struct DirtyChunks(pub HashSet<(i32, i32)>);
+
+/// Request a stamp; applied by `apply_terrain_deformations` before chunk scheduling.
+#[derive(Event, Clone, Copy, Debug)]
+pub struct DeformTerrain(pub TerrainBrush);
+
+/// Sent after a stamp landed.
+#[derive(Event, Clone, Debug)]
+pub struct TerrainDeformed {
+    /// Chunks queued for a rebuild (the edited tiles plus their neighbours).
+    pub chunks: Vec<(i32, i32)>,
+    pub min_xz: Vec2,
+    pub max_xz: Vec2,
+}
+
+/// Stamps wait (in order) until every tile under them is resident, so the frame never
+/// blocks on a tile read.
+pub fn apply_terrain_deformations(
+    mut requests: EventReader<DeformTerrain>,
+    mut waiting: Local<Vec<TerrainBrush>>,
+    data: Res<HeightmapData>,
+    cache: Res<HeightTileCache>,
+    mut dirty: ResMut<DirtyChunks>,
+    mut deformed: EventWriter<TerrainDeformed>,
+) {
+    let queued: Vec<TerrainBrush> = waiting.drain(..).chain(requests.read().map(|r| r.0)).collect();
+    for brush in queued {
+        // Later stamps queue behind an earlier one that is waiting, to keep their order
+        if !waiting.is_empty() || !brush_tiles_ready(&brush, &data, &cache) {
+            waiting.push(brush);
+            continue;
+        }
+        let tiles = apply_brush(&brush, &data, &cache);
+        if tiles.is_empty() {
+            continue;
+        }
+
+        // A chunk mesh borrows edge samples from its right/up/up-right tiles and its
+        // normals look one step further, so the whole 3x3 ring around an edit is stale.
+        let mut chunks = HashSet::new();
+        for &(tx, tz) in &tiles {
+            for dz in -1..=1 {
+                for dx in -1..=1 {
+                    chunks.insert((tx + dx, tz + dz));
+                }
+            }
+        }
+        dirty.0.extend(chunks.iter().copied());
+
+        let (min_xz, max_xz) = brush.bounds();
+        deformed.write(TerrainDeformed { chunks: chunks.into_iter().collect(), min_xz, max_xz });
+    }
+}
+
+/// Global texel range under a brush.
+struct BrushTexels {
+    g0: IVec2,
+    g1: IVec2,
+    cells: IVec2,
+    counts: ChunkCounts,
+}
+
+impl BrushTexels {
+    /// Footprint (+1 ring for Smooth) clipped to the map; `None` if it misses the map.
+    fn new(brush: &TerrainBrush, data: &HeightmapData, cache: &HeightTileCache) -> Option<Self> {
+        let res = cache.tile_resolution;
+        let cells = IVec2::new(res.x as i32 - 1, res.y as i32 - 1);
+        if cells.x < 1 || cells.y < 1 || data.height_scale <= 0.0 {
+            return None;
+        }
+        let counts = chunk_counts(data);
+        let spacing = data.chunk_size / cells.as_vec2();
+
+        let (min_w, max_w) = brush.bounds();
+        let g_max = IVec2::new(counts.x * cells.x, counts.z * cells.y);
+        let g0 = (((min_w - data.origin) / spacing).floor().as_ivec2() - IVec2::ONE).max(IVec2::ZERO);
+        let g1 = (((max_w - data.origin) / spacing).ceil().as_ivec2() + IVec2::ONE).min(g_max);
+        (g0.x <= g1.x && g0.y <= g1.y).then_some(Self { g0, g1, cells, counts })
+    }
+
+    fn tiles(&self) -> impl Iterator<Item = (i32, i32)> {
+        let (t0, t1) = tiles_overlapping(self.g0, self.g1, self.cells, self.counts);
+        (t0.y..=t1.y).flat_map(move |tz| (t0.x..=t1.x).map(move |tx| (tx, tz)))
+    }
+}
+
+/// Request every tile under `brush`; true once none of them is still loading.
+fn brush_tiles_ready(brush: &TerrainBrush, data: &HeightmapData, cache: &HeightTileCache) -> bool {
+    let Some(texels) = BrushTexels::new(brush, data, cache) else { return true };
+    // Counted rather than `all`, so every read starts at once
+    texels.tiles().filter(|&(tx, tz)| cache.request_tile(tx, tz).is_loading()).count() == 0
+}
+
+/// Apply one stamp to the tiles under it and return the keys of the tiles that changed.
+///
+/// Edits are computed per global texel from a snapshot of the original data and written
+/// to every tile that stores that texel, so shared tile edges stay identical.
+/// Missing (void) and unreadable tiles are left alone.
+pub fn apply_brush(brush: &TerrainBrush, data: &HeightmapData, cache: &HeightTileCache) -> Vec<(i32, i32)> {
+    let Some(texels) = BrushTexels::new(brush, data, cache) else { return Vec::new() };
+    let BrushTexels { g0, g1, cells, counts } = texels;
+    let res = cache.tile_resolution;
+    let spacing = data.chunk_size / cells.as_vec2();
+
+    // Snapshot of the original tiles
+    let mut snapshot: HashMap<(i32, i32), Tile16> = HashMap::new();
+    for (tx, tz) in texels.tiles() {
+        if let Ok(tile) = cache.try_fetch_tile(tx, tz) {
+            if tile.res == res {
+                snapshot.insert((tx, tz), tile);
+            }
+        }
+    }
+    if snapshot.is_empty() {
+        return Vec::new();
+    }
+
+    let (rmin, rmax) = data.raw_minmax;
+    let span = if rmax > rmin { rmax - rmin } else { 1.0 };
+    let to_h = |raw: f32| ((raw - rmin) / span).clamp(0.0, 1.0) * data.height_scale;
+    let to_raw = |h: f32| (rmin + h / data.height_scale * span).round().clamp(0.0, u16::MAX as f32) as u16;
+    let read = |g: IVec2| -> Option<f32> {
+        owners(g, cells, counts)
+            .find_map(|(key, local)| snapshot.get(&key).map(|t| t.get_clamped(local.x, local.y)))
+            .map(|raw| to_h(raw as f32))
+    };
+    let texel_pos = |g: IVec2| data.origin + g.as_vec2() * spacing;
+
+    let flatten_to = match brush.op {
+        BrushOp::Flatten(Some(h)) => h,
+        BrushOp::Flatten(None) => {
+            let (mut sum, mut wsum) = (0.0, 0.0);
+            for gz in g0.y..=g1.y {
+                for gx in g0.x..=g1.x {
+                    let g = IVec2::new(gx, gz);
+                    let w = brush.weight(texel_pos(g));
+                    if w <= 0.0 {
+                        continue;
+                    }
+                    if let Some(h) = read(g) {
+                        sum += h * w;
+                        wsum += w;
+                    }
+                }
+            }
+            if wsum <= 0.0 {
+                return Vec::new();
+            }
+            sum / wsum
+        }
+        _ => 0.0,
+    };
+
+    // New value per texel, then scatter into copies of every tile that stores it
+    let mut working: HashMap<(i32, i32), Vec<u16>> = HashMap::new();
+    for gz in g0.y..=g1.y {
+        for gx in g0.x..=g1.x {
+            let g = IVec2::new(gx, gz);
+            let w = brush.weight(texel_pos(g));
+            if w <= 0.0 {
+                continue;
+            }
+            let Some(h) = read(g) else { continue };
+            let target = match brush.op {
+                BrushOp::Set(v) => v,
+                BrushOp::Raise(m) => h + m,
+                BrushOp::Lower(m) => h - m,
+                BrushOp::Smooth(strength) => {
+                    let (mut sum, mut n) = (0.0, 0.0);
+                    for dz in -1..=1 {
+                        for dx in -1..=1 {
+                            if let Some(v) = read(g + IVec2::new(dx, dz)) {
+                                sum += v;
+                                n += 1.0;
+                            }
+                        }
+                    }
+                    h + (sum / n - h) * strength.clamp(0.0, 1.0)
+                }
+                BrushOp::Flatten(_) => flatten_to,
+            };
+            let v = to_raw(h + (target - h) * w);
+
+            for (key, local) in owners(g, cells, counts) {
+                let Some(tile) = snapshot.get(&key) else { continue };
+                let buf = working.entry(key).or_insert_with(|| tile.data.to_vec());
+                buf[(local.y * res.x as i32 + local.x) as usize] = v;
+            }
+        }
+    }
+
+    let mut changed: Vec<(i32, i32)> = Vec::with_capacity(working.len());
+    for (key, buf) in working {
+        if buf.as_slice() == snapshot[&key].data.as_slice() {
+            continue;
+        }
+        let edited = Tile16 { res, data: Arc::new(buf) };
+        if let Some(layer) = &cache.edits {
+            layer.accumulate(key.0, key.1, &snapshot[&key], &edited);
+        }
+        cache.commit_edited_tile(key.0, key.1, edited);
+        changed.push(key);
+    }
+    changed.sort_unstable();
+    changed
+}
+
+/// Tiles storing global texel `g` with its tile-local coordinate. Texels on a tile border
+/// belong to both neighbours (a tile's last column is the next tile's column 0).
+fn owners(g: IVec2, cells: IVec2, counts: ChunkCounts) -> impl Iterator<Item = ((i32, i32), IVec2)> {
+    let axis = |g: i32, c: i32, n: i32| -> [Option<(i32, i32)>; 2] {
+        let t = g.div_euclid(c);
+        let own = (t < n).then_some((t, g - t * c));
+        let prev = (g % c == 0 && t > 0 && t - 1 < n).then_some((t - 1, c));
+        [own, prev]
+    };
+    let xs = axis(g.x, cells.x, counts.x);
+    let zs = axis(g.y, cells.y, counts.z);
+    zs.into_iter().flatten().flat_map(move |(tz, lz)| {
+        xs.into_iter()
+            .flatten()
+            .map(move |(tx, lx)| ((tx, tz), IVec2::new(lx, lz)))
+    })
+}
+
+/// Keys of the tiles overlapping the global texel range `[g0, g1]` (tile `t` spans `t*c..=(t+1)*c`).
+fn tiles_overlapping(g0: IVec2, g1: IVec2, cells: IVec2, counts: ChunkCounts) -> (IVec2, IVec2) {
+    let first = |g: i32, c: i32| ((g + c - 1).div_euclid(c) - 1).max(0);
+    let lo = IVec2::new(first(g0.x, cells.x), first(g0.y, cells.y));
+    let hi = IVec2::new((g1.x / cells.x).min(counts.x - 1), (g1.y / cells.y).min(counts.z - 1));
+    (lo, hi)
+}
@@ -0,0 +1,351 @@
+// src/terrain/edit_layer.rs
+//! Persistent runtime edits. Deformations are stored as per-tile signed deltas against the
+//! artist-exported tiles and saved as `{prefix}_y{cz}_x{cx}.delta` next to them, so the
+//! source `.r16` files are never rewritten. `HeightTileCache` merges a tile's delta when
+//! the tile loads, so `sample_height`, the chunk mesher and the raycast all see edits.
+//!
+//! On-disk format (little-endian), only non-zero runs are stored:
+//!
+//! ```text
+//! "CHD1"  res_x: u32  res_y: u32  run_count: u32
+//! run_count x { start: u32  len: u32  len x delta: i32 }
+//! ```
+
+use std::collections::{HashMap, HashSet};
+use std::path::Path;
+use std::sync::{Arc, Mutex, MutexGuard};
+
+use bevy::app::AppExit;
+use bevy::prelude::*;
+use bevy::tasks::{futures::check_ready, IoTaskPool, Task};
+use futures_lite::future::block_on;
+
+use crate::heightmap_data::{HeightTileCache, HeightTileError, Tile16};
+use crate::terrain::deform::TerrainDeformed;
+
+const DELTA_MAGIC: &[u8; 4] = b"CHD1";
+
+/// Seconds without new edits before dirty deltas are written out.
+const AUTOSAVE_IDLE_SECS: f32 = 2.0;
+
+/// Signed raw-unit offsets for one tile (dense in memory, run-length on disk).
+#[derive(Clone, Debug, PartialEq)]
+pub struct TileDelta {
+    pub res: UVec2,
+    pub values: Vec<i32>,
+}
+
+impl TileDelta {
+    pub fn zero(res: UVec2) -> Self {
+        Self { res, values: vec![0; res.x as usize * res.y as usize] }
+    }
+
+    pub fn is_zero(&self) -> bool {
+        self.values.iter().all(|&d| d == 0)
+    }
+
+    /// `tile + delta`, clamped to the u16 range.
+    pub fn apply(&self, tile: &Tile16) -> Tile16 {
+        let data = tile
+            .data
+            .iter()
+            .zip(&self.values)
+            .map(|(&v, &d)| (v as i32 + d).clamp(0, u16::MAX as i32) as u16)
+            .collect::<Vec<_>>();
+        Tile16 { res: tile.res, data: Arc::new(data) }
+    }
+
+    pub fn encode(&self) -> Vec<u8> {
+        let mut runs: Vec<(usize, usize)> = Vec::new();
+        let mut i = 0;
+        while i < self.values.len() {
+            if self.values[i] == 0 {
+                i += 1;
+                continue;
+            }
+            let start = i;
+            while i < self.values.len() && self.values[i] != 0 {
+                i += 1;
+            }
+            runs.push((start, i - start));
+        }
+
+        let mut out = Vec::with_capacity(16 + runs.iter().map(|r| 8 + r.1 * 4).sum::<usize>());
+        out.extend_from_slice(DELTA_MAGIC);
+        out.extend_from_slice(&self.res.x.to_le_bytes());
+        out.extend_from_slice(&self.res.y.to_le_bytes());
+        out.extend_from_slice(&(runs.len() as u32).to_le_bytes());
+        for (start, len) in runs {
+            out.extend_from_slice(&(start as u32).to_le_bytes());
+            out.extend_from_slice(&(len as u32).to_le_bytes());
+            for d in &self.values[start..start + len] {
+                out.extend_from_slice(&d.to_le_bytes());
+            }
+        }
+        out
+    }
+
+    /// Parse a `.delta` file for a tile of resolution `expected` (any other header
+    /// resolution is rejected before anything is allocated).
+    pub fn decode(path: &Path, bytes: &[u8], expected: UVec2) -> Result<Self, HeightTileError> {
+        let bad = |reason: &str| HeightTileError::Decode { path: path.to_path_buf(), reason: reason.to_string() };
+        // Header fields after the magic
+        let mut at = 4usize;
+        let mut u32_at = |bytes: &[u8]| -> Result<u32, HeightTileError> {
+            let b = bytes.get(at..at + 4).ok_or_else(|| bad("unexpected end of delta file"))?;
+            at += 4;
+            Ok(u32::from_le_bytes([b[0], b[1], b[2], b[3]]))
+        };
+
+        if bytes.get(..4) != Some(DELTA_MAGIC.as_slice()) {
+            return Err(bad("not a terrain delta file"));
+        }
+        let res = UVec2::new(u32_at(bytes)?, u32_at(bytes)?);
+        if res != expected {
+            return Err(HeightTileError::Decode {
+                path: path.to_path_buf(),
+                reason: format!(
+                    "delta is {}x{}, tiles are {}x{}",
+                    res.x, res.y, expected.x, expected.y
+                ),
+            });
+        }
+        let mut delta = TileDelta::zero(res);
+        let runs = u32_at(bytes)?;
+        for _ in 0..runs {
+            let start = u32_at(bytes)? as usize;
+            let len = u32_at(bytes)? as usize;
+            let run = start
+                .checked_add(len)
+                .and_then(|end| delta.values.get_mut(start..end))
+                .ok_or_else(|| bad("delta run outside the tile"))?;
+            for d in run {
+                *d = u32_at(bytes)? as i32;
+            }
+        }
+        Ok(delta)
+    }
+}
+
+#[derive(Default)]
+struct EditLayerState {
+    deltas: HashMap<(i32, i32), TileDelta>,
+    /// Keys whose `.delta` file has been looked for (present or not).
+    probed: HashSet<(i32, i32)>,
+    /// Changed since the last save.
+    dirty: HashSet<(i32, i32)>,
+}
+
+/// Sparse delta layer keyed like `HeightTileCache`. Cheap to clone; clones share state.
+#[derive(Clone, Default)]
+pub struct TileEditLayer {
+    state: Arc<Mutex<EditLayerState>>,
+}
+
+impl TileEditLayer {
+    fn lock(&self) -> MutexGuard<'_, EditLayerState> {
+        self.state.lock().expect("terrain edit layer mutex poisoned")
+    }
+
+    pub(crate) fn needs_probe(&self, cx: i32, cz: i32) -> bool {
+        !self.lock().probed.contains(&(cx, cz))
+    }
+
+    /// Record the result of looking for a tile's delta file.
+    pub(crate) fn insert_probed(&self, cx: i32, cz: i32, delta: Option<TileDelta>) {
+        let mut st = self.lock();
+        st.probed.insert((cx, cz));
+        if let Some(d) = delta {
+            st.deltas.entry((cx, cz)).or_insert(d);
+        }
+    }
+
+    /// Source tile + stored delta (unchanged if there is none or the resolution differs).
+    pub fn apply(&self, cx: i32, cz: i32, tile: Tile16) -> Tile16 {
+        let st = self.lock();
+        match st.deltas.get(&(cx, cz)) {
+            Some(d) if d.res == tile.res => d.apply(&tile),
+            Some(d) => {
+                warn!(
+                    "Terrain: edit delta for tile ({}, {}) is {}x{}, tile is {}x{}; ignoring it",
+                    cx, cz, d.res.x, d.res.y, tile.res.x, tile.res.y
+                );
+                tile
+            }
+            None => tile,
+        }
+    }
+
+    /// Fold an in-memory edit (`before` -> `after`, both already merged) into the delta.
+    pub fn accumulate(&self, cx: i32, cz: i32, before: &Tile16, after: &Tile16) {
+        let mut st = self.lock();
+        st.probed.insert((cx, cz));
+        let delta = st
+            .deltas
+            .entry((cx, cz))
+            .or_insert_with(|| TileDelta::zero(after.res));
+        if delta.res != after.res || before.res != after.res {
+            return;
+        }
+        for ((d, &b), &a) in delta.values.iter_mut().zip(before.data.iter()).zip(after.data.iter()) {
+            *d += a as i32 - b as i32;
+        }
+        st.dirty.insert((cx, cz));
+    }
+
+    pub fn get(&self, cx: i32, cz: i32) -> Option<TileDelta> {
+        self.lock().deltas.get(&(cx, cz)).cloned()
+    }
+
+    /// True when the tile has a delta (loaded from disk or accumulated this session).
+    pub fn has_delta(&self, cx: i32, cz: i32) -> bool {
+        self.lock().deltas.contains_key(&(cx, cz))
+    }
+
+    pub fn has_unsaved(&self) -> bool {
+        !self.lock().dirty.is_empty()
+    }
+
+    /// Deltas changed since the last save (clears the dirty set).
+    pub(crate) fn take_unsaved(&self) -> Vec<((i32, i32), TileDelta)> {
+        let mut st = self.lock();
+        let keys: Vec<_> = st.dirty.drain().collect();
+        keys.into_iter()
+            .filter_map(|k| st.deltas.get(&k).map(|d| (k, d.clone())))
+            .collect()
+    }
+
+    pub(crate) fn mark_unsaved(&self, keys: impl IntoIterator<Item = (i32, i32)>) {
+        self.lock().dirty.extend(keys);
+    }
+}
+
+/// Ask for unsaved edits to be written now (instead of after the idle delay).
+#[derive(Event, Clone, Copy, Debug, Default)]
+pub struct SaveTerrainEdits;
+
+/// Write dirty deltas once edits have been idle for a moment, on request, or on exit.
+/// Saves run on the IO task pool, one at a time (so an older delta never lands after a
+/// newer one); the save on exit writes before returning, since no later frame will poll it.
+pub fn save_terrain_edits(
+    time: Res<Time>,
+    mut idle: Local<f32>,
+    mut saving: Local<Option<Task<Result<usize, HeightTileError>>>>,
+    mut deformed: EventReader<TerrainDeformed>,
+    mut requests: EventReader<SaveTerrainEdits>,
+    mut exit: EventReader<AppExit>,
+    cache: Res<HeightTileCache>,
+) {
+    let edited = deformed.read().count() > 0;
+    let exiting = exit.read().count() > 0;
+    let forced = requests.read().count() > 0 || exiting;
+    if edited {
+        *idle = 0.0;
+    } else {
+        *idle += time.delta_secs();
+    }
+
+    if let Some(task) = saving.as_mut() {
+        let result = if exiting { Some(block_on(task)) } else { check_ready(task) };
+        let Some(result) = result else { return };
+        *saving = None;
+        log_saved(result);
+    }
+
+    let Some(layer) = &cache.edits else { return };
+    if !layer.has_unsaved() || !(forced || *idle >= AUTOSAVE_IDLE_SECS) {
+        return;
+    }
+    if exiting {
+        log_saved(cache.save_edits());
+        return;
+    }
+    let cache = cache.clone();
+    *saving = Some(IoTaskPool::get().spawn(async move { cache.save_edits() }));
+}
+
+fn log_saved(result: Result<usize, HeightTileError>) {
+    match result {
+        Ok(n) => info!("Terrain: saved edit deltas for {} tiles", n),
+        Err(e) => error!("Terrain: could not save terrain edits: {}", e),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::path::PathBuf;
+
+    use super::*;
+
+    const RES: UVec2 = UVec2::new(8, 4);
+
+    fn path() -> PathBuf {
+        PathBuf::from("test_y0_x0.delta")
+    }
+
+    fn sample_delta() -> TileDelta {
+        let mut delta = TileDelta::zero(RES);
+        delta.values[0] = 5;
+        delta.values[1] = -7;
+        for d in &mut delta.values[10..14] {
+            *d = 300;
+        }
+        delta.values[31] = i32::MIN;
+        delta
+    }
+
+    fn header(res: UVec2, runs: u32) -> Vec<u8> {
+        let mut out = DELTA_MAGIC.to_vec();
+        for v in [res.x, res.y, runs] {
+            out.extend_from_slice(&v.to_le_bytes());
+        }
+        out
+    }
+
+    #[test]
+    fn round_trips() {
+        let delta = sample_delta();
+        assert_eq!(TileDelta::decode(&path(), &delta.encode(), RES).unwrap(), delta);
+
+        let zero = TileDelta::zero(RES);
+        assert_eq!(zero.encode().len(), 16);
+        assert_eq!(TileDelta::decode(&path(), &zero.encode(), RES).unwrap(), zero);
+    }
+
+    #[test]
+    fn rejects_bad_magic() {
+        let mut bytes = sample_delta().encode();
+        bytes[0] = b'X';
+        assert!(TileDelta::decode(&path(), &bytes, RES).is_err());
+        assert!(TileDelta::decode(&path(), &[], RES).is_err());
+    }
+
+    #[test]
+    fn rejects_truncated_input() {
+        let bytes = sample_delta().encode();
+        for len in 4..bytes.len() {
+            assert!(TileDelta::decode(&path(), &bytes[..len], RES).is_err(), "accepted {len} bytes");
+        }
+    }
+
+    #[test]
+    fn rejects_unexpected_resolution() {
+        let bytes = sample_delta().encode();
+        assert!(TileDelta::decode(&path(), &bytes, UVec2::new(4, 8)).is_err());
+
+        // A hostile header must not size the allocation
+        let bytes = header(UVec2::new(u32::MAX, u32::MAX), 0);
+        assert!(TileDelta::decode(&path(), &bytes, RES).is_err());
+    }
+
+    #[test]
+    fn rejects_runs_outside_the_tile() {
+        for (start, len) in [(30, 3), (u32::MAX, 2), (1, u32::MAX)] {
+            let mut bytes = header(RES, 1);
+            bytes.extend_from_slice(&start.to_le_bytes());
+            bytes.extend_from_slice(&len.to_le_bytes());
+            bytes.extend(std::iter::repeat_n(0u8, 12));
+            assert!(TileDelta::decode(&path(), &bytes, RES).is_err(), "accepted run {start}+{len}");
+        }
+    }
+}
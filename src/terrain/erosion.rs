@@ -0,0 +1,485 @@
+// src/terrain/erosion.rs
+//! Offline erosion for height tiles: particle-based hydraulic erosion (droplets that pick
+//! up sediment going downhill and drop it where they slow down) and grid-based thermal
+//! erosion (material slides off slopes steeper than the talus angle).
+//!
+//! `erode_region` stitches a rectangle of tiles into one `Heightfield`, so shared tile edges
+//! are single texels and come out identical in both tiles. The region's outer edge and void
+//! tiles are held fixed, with the change fading in over `ErosionDef::border`, so the result
+//! still meets the tiles around it. Everything runs on one thread from a seeded generator:
+//! the same tiles, settings and seed give bit-identical output. `chasma-tiles erode` writes
+//! the result as new tiles.
+
+use std::sync::Arc;
+
+use bevy::prelude::*;
+use serde::{Deserialize, Serialize};
+
+use crate::heightmap_data::{HeightTileCache, HeightTileError, HeightmapData, Tile16};
+use crate::props::core::DEFAULT_WORLD_SEED;
+use crate::terrain::grid::{nearest_source, D8, FAR};
+use crate::terrain::procedural::mix64;
+
+/// What to run over a region, in order: hydraulic, then thermal.
+#[derive(Clone, Copy, Debug, Serialize, Deserialize)]
+#[serde(default)]
+pub struct ErosionDef {
+    pub seed: u64,
+    pub hydraulic: Option<HydraulicErosion>,
+    pub thermal: Option<ThermalErosion>,
+    /// Width (meters) over which erosion fades out towards fixed texels.
+    pub border: f32,
+}
+
+impl Default for ErosionDef {
+    fn default() -> Self {
+        Self {
+            seed: DEFAULT_WORLD_SEED,
+            hydraulic: Some(HydraulicErosion::default()),
+            thermal: None,
+            border: 32.0,
+        }
+    }
+}
+
+/// Droplet erosion. Heights are in meters and droplets move one texel per step.
+#[derive(Clone, Copy, Debug, Serialize, Deserialize)]
+#[serde(default)]
+pub struct HydraulicErosion {
+    /// Droplets per editable texel.
+    pub droplets_per_texel: f32,
+    /// Steps before a droplet is dropped.
+    pub max_steps: u32,
+    /// How much of its direction a droplet keeps each step (0 = always straight downhill).
+    pub inertia: f32,
+    /// Sediment a droplet can carry, per meter dropped, unit speed and unit water.
+    pub capacity: f32,
+    /// Drop (meters) assumed on flat ground, so slow droplets still carry some sediment.
+    pub min_drop: f32,
+    /// Share of the free capacity eroded per step.
+    pub erode_rate: f32,
+    /// Share of the excess sediment deposited per step.
+    pub deposit_rate: f32,
+    /// Share of the water evaporating per step.
+    pub evaporate_rate: f32,
+    pub gravity: f32,
+    /// Radius (texels) eroded around a droplet.
+    pub radius: f32,
+}
+
+impl Default for HydraulicErosion {
+    fn default() -> Self {
+        Self {
+            droplets_per_texel: 0.25,
+            max_steps: 64,
+            inertia: 0.05,
+            capacity: 4.0,
+            min_drop: 0.01,
+            erode_rate: 0.3,
+            deposit_rate: 0.3,
+            evaporate_rate: 0.01,
+            gravity: 4.0,
+            radius: 3.0,
+        }
+    }
+}
+
+/// Talus erosion: slopes steeper than `talus_deg` shed material to their lower neighbours.
+#[derive(Clone, Copy, Debug, Serialize, Deserialize)]
+#[serde(default)]
+pub struct ThermalErosion {
+    pub iterations: u32,
+    /// Steepest stable slope (degrees).
+    pub talus_deg: f32,
+    /// Share (0..1) of the excess moved per iteration.
+    pub rate: f32,
+}
+
+impl Default for ThermalErosion {
+    fn default() -> Self {
+        Self { iterations: 50, talus_deg: 35.0, rate: 0.5 }
+    }
+}
+
+/// A rectangle of tile keys, both corners included.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct TileRegion {
+    pub min: IVec2,
+    pub max: IVec2,
+}
+
+impl TileRegion {
+    pub fn tiles(&self) -> IVec2 {
+        (self.max - self.min + IVec2::ONE).max(IVec2::ZERO)
+    }
+
+    /// Keys in row-major order.
+    pub fn keys(&self) -> impl Iterator<Item = (i32, i32)> {
+        let (min, max) = (self.min, self.max);
+        (min.y..=max.y).flat_map(move |cz| (min.x..=max.x).map(move |cx| (cx, cz)))
+    }
+}
+
+/// Heights (meters) on a regular grid, row-major.
+#[derive(Clone, Debug)]
+pub struct Heightfield {
+    pub size: UVec2,
+    /// Meters between texels (X, Z).
+    pub spacing: Vec2,
+    pub heights: Vec<f32>,
+    /// Texels erosion may change.
+    pub editable: Vec<bool>,
+}
+
+impl Heightfield {
+    /// A field with every texel editable.
+    pub fn new(size: UVec2, spacing: Vec2, heights: Vec<f32>) -> Self {
+        let editable = vec![true; heights.len()];
+        Self { size, spacing, heights, editable }
+    }
+
+    pub fn erode_hydraulic(&mut self, params: &HydraulicErosion, seed: u64) {
+        if self.size.x < 2 || self.size.y < 2 {
+            return;
+        }
+        let editable = self.editable.iter().filter(|&&e| e).count();
+        let droplets = (params.droplets_per_texel.max(0.0) * editable as f32).round() as u64;
+        let max = (self.size - UVec2::ONE).as_vec2();
+        let mut rng = SplitMix64(seed);
+
+        for _ in 0..droplets {
+            let mut pos = Vec2::new(rng.next_f32(), rng.next_f32()) * max;
+            let mut dir = Vec2::ZERO;
+            let (mut speed, mut water, mut sediment) = (1.0, 1.0, 0.0);
+
+            for _ in 0..params.max_steps {
+                let (height, gradient) = self.height_and_gradient(pos);
+                dir = (dir * params.inertia - gradient * (1.0 - params.inertia)).normalize_or_zero();
+                if dir == Vec2::ZERO {
+                    break;
+                }
+                let next = pos + dir;
+                if next.cmplt(Vec2::ZERO).any() || next.cmpgt(max).any() {
+                    break;
+                }
+
+                let drop = height - self.height_and_gradient(next).0;
+                let capacity = drop.max(params.min_drop) * speed * water * params.capacity;
+                if drop < 0.0 || sediment > capacity {
+                    // Fill the pit it climbs out of, or shed what it can't carry
+                    let amount = if drop < 0.0 {
+                        (-drop).min(sediment)
+                    } else {
+                        (sediment - capacity) * params.deposit_rate
+                    };
+                    sediment -= self.deposit(pos, amount);
+                } else {
+                    // Never dig deeper than the drop, or it cuts a hole behind itself
+                    let amount = ((capacity - sediment) * params.erode_rate).min(drop);
+                    sediment += self.erode(pos, amount, params.radius);
+                }
+
+                speed = (speed * speed + drop * params.gravity).max(0.0).sqrt();
+                water *= 1.0 - params.evaporate_rate;
+                pos = next;
+            }
+        }
+    }
+
+    pub fn erode_thermal(&mut self, params: &ThermalErosion) {
+        let (w, h) = (self.size.x as i32, self.size.y as i32);
+        let tan_talus = params.talus_deg.to_radians().tan();
+        let limits = D8.map(|d| tan_talus * (d.as_vec2() * self.spacing).length());
+        let rate = params.rate.clamp(0.0, 1.0);
+
+        let mut delta = vec![0.0; self.heights.len()];
+        for _ in 0..params.iterations {
+            delta.fill(0.0);
+            for z in 0..h {
+                for x in 0..w {
+                    let c = (z * w + x) as usize;
+                    if !self.editable[c] {
+                        continue;
+                    }
+                    let mut excess = [0.0; 8];
+                    let (mut total, mut largest) = (0.0, 0.0f32);
+                    for (k, d) in D8.iter().enumerate() {
+                        let p = IVec2::new(x, z) + *d;
+                        if p.x < 0 || p.y < 0 || p.x >= w || p.y >= h {
+                            continue;
+                        }
+                        let nb = (p.y * w + p.x) as usize;
+                        let e = self.heights[c] - self.heights[nb] - limits[k];
+                        if self.editable[nb] && e > 0.0 {
+                            excess[k] = e;
+                            total += e;
+                            largest = largest.max(e);
+                        }
+                    }
+                    if total <= 0.0 {
+                        continue;
+                    }
+                    // Half the largest excess levels that pair; spread by excess to the rest
+                    let moved = rate * largest * 0.5;
+                    delta[c] -= moved;
+                    for (k, d) in D8.iter().enumerate() {
+                        if excess[k] > 0.0 {
+                            let p = IVec2::new(x, z) + *d;
+                            delta[(p.y * w + p.x) as usize] += moved * excess[k] / total;
+                        }
+                    }
+                }
+            }
+            for (height, d) in self.heights.iter_mut().zip(&delta) {
+                *height += d;
+            }
+        }
+    }
+
+    /// Blend back towards `original` near fixed texels: no change on them, the full change
+    /// `width` meters away.
+    pub fn fade_towards_fixed(&mut self, original: &[f32], width: f32) {
+        let fixed: Vec<bool> = self.editable.iter().map(|&e| !e).collect();
+        let nearest = nearest_source(&fixed, self.size, self.spacing);
+        for (i, &(d, _)) in nearest.iter().enumerate() {
+            if d >= FAR {
+                continue;
+            }
+            let t = if width > 0.0 { (d.sqrt() as f32 / width).min(1.0) } else { 1.0 };
+            self.heights[i] = original[i] + (self.heights[i] - original[i]) * t;
+        }
+    }
+
+    /// Bilinear height and its gradient (meters per texel) at texel-space `p`.
+    fn height_and_gradient(&self, p: Vec2) -> (f32, Vec2) {
+        let max = self.size - UVec2::ONE;
+        let i0 = p.floor().as_uvec2().min(max - UVec2::ONE);
+        let f = p - i0.as_vec2();
+        let at = |x: u32, z: u32| self.heights[(z * self.size.x + x) as usize];
+        let (h00, h10) = (at(i0.x, i0.y), at(i0.x + 1, i0.y));
+        let (h01, h11) = (at(i0.x, i0.y + 1), at(i0.x + 1, i0.y + 1));
+        let gradient = Vec2::new(
+            (h10 - h00) * (1.0 - f.y) + (h11 - h01) * f.y,
+            (h01 - h00) * (1.0 - f.x) + (h11 - h10) * f.x,
+        );
+        let height = h00 * (1.0 - f.x) * (1.0 - f.y) + h10 * f.x * (1.0 - f.y) + h01 * (1.0 - f.x) * f.y + h11 * f.x * f.y;
+        (height, gradient)
+    }
+
+    /// Add `amount` meters at `p`, split bilinearly over the surrounding editable texels.
+    /// Returns what was placed.
+    fn deposit(&mut self, p: Vec2, amount: f32) -> f32 {
+        let max = self.size - UVec2::ONE;
+        let i0 = p.floor().as_uvec2().min(max - UVec2::ONE);
+        let f = p - i0.as_vec2();
+        let mut placed = 0.0;
+        for (dx, dz, w) in [
+            (0, 0, (1.0 - f.x) * (1.0 - f.y)),
+            (1, 0, f.x * (1.0 - f.y)),
+            (0, 1, (1.0 - f.x) * f.y),
+            (1, 1, f.x * f.y),
+        ] {
+            let i = ((i0.y + dz) * self.size.x + i0.x + dx) as usize;
+            if self.editable[i] {
+                self.heights[i] += amount * w;
+                placed += amount * w;
+            }
+        }
+        placed
+    }
+
+    /// Remove up to `amount` meters within `radius` texels of `p`, weighted towards the
+    /// center. Returns what was removed.
+    fn erode(&mut self, p: Vec2, amount: f32, radius: f32) -> f32 {
+        let radius = radius.max(1.0);
+        let lo = (p - Vec2::splat(radius)).ceil().max(Vec2::ZERO).as_uvec2();
+        let hi = (p + Vec2::splat(radius)).floor().as_uvec2().min(self.size - UVec2::ONE);
+
+        let mut weights = Vec::new();
+        let mut sum = 0.0;
+        for z in lo.y..=hi.y {
+            for x in lo.x..=hi.x {
+                let i = (z * self.size.x + x) as usize;
+                let w = radius - p.distance(Vec2::new(x as f32, z as f32));
+                if w > 0.0 && self.editable[i] {
+                    weights.push((i, w));
+                    sum += w;
+                }
+            }
+        }
+        let mut removed = 0.0;
+        for (i, w) in weights {
+            let take = amount * w / sum;
+            self.heights[i] -= take;
+            removed += take;
+        }
+        removed
+    }
+}
+
+/// SplitMix64 stream.
+struct SplitMix64(u64);
+
+impl SplitMix64 {
+    fn next_f32(&mut self) -> f32 {
+        self.0 = self.0.wrapping_add(0x9E37_79B9_7F4A_7C15);
+        (mix64(self.0) >> 40) as f32 / (1u64 << 24) as f32
+    }
+}
+
+/// Tiles out of `erode_region`, keyed (cx, cz).
+pub type ErodedTiles = Vec<((i32, i32), Tile16)>;
+
+/// Erode `region` and return its new tiles (row-major, void tiles left out). Tiles are read
+/// through `cache`; nothing is written. Memory: about 9 bytes per texel of the region.
+pub fn erode_region(
+    region: TileRegion,
+    def: &ErosionDef,
+    data: &HeightmapData,
+    cache: &HeightTileCache,
+) -> Result<ErodedTiles, HeightTileError> {
+    let res = cache.tile_resolution;
+    let cells = res - UVec2::ONE;
+    let size = region.tiles().as_uvec2() * cells + UVec2::ONE;
+    let spacing = data.chunk_size / cells.as_vec2();
+
+    let (rmin, rmax) = data.raw_minmax;
+    let span = if rmax > rmin { rmax - rmin } else { 1.0 };
+    let to_h = |raw: u16| ((raw as f32 - rmin) / span).clamp(0.0, 1.0) * data.height_scale;
+    let to_raw = |h: f32| (rmin + h / data.height_scale * span).round().clamp(0.0, u16::MAX as f32) as u16;
+
+    // One field for the region; shared tile edges land on the same texels
+    let n = (size.x * size.y) as usize;
+    let mut field = Heightfield {
+        size,
+        spacing,
+        heights: vec![data.void_height; n],
+        editable: vec![false; n],
+    };
+    let mut present = Vec::new();
+    for (cx, cz) in region.keys() {
+        let tile = match cache.try_fetch_tile(cx, cz) {
+            Ok(t) => t,
+            Err(e) if e.is_missing() => continue,
+            Err(e) => return Err(e),
+        };
+        if tile.res != res {
+            return Err(HeightTileError::Decode {
+                path: cache.tile_path(cx, cz),
+                reason: format!("{}x{} texels, expected {}x{}", tile.res.x, tile.res.y, res.x, res.y),
+            });
+        }
+        let at = (IVec2::new(cx, cz) - region.min).as_uvec2() * cells;
+        for j in 0..res.y {
+            for i in 0..res.x {
+                let g = ((at.y + j) * size.x + at.x + i) as usize;
+                field.heights[g] = to_h(tile.data[(j * res.x + i) as usize]);
+                field.editable[g] = true;
+            }
+        }
+        present.push((cx, cz, at));
+    }
+
+    // The region's outline is shared with the tiles around it
+    for j in 0..size.y {
+        for i in 0..size.x {
+            if i == 0 || j == 0 || i == size.x - 1 || j == size.y - 1 {
+                field.editable[(j * size.x + i) as usize] = false;
+            }
+        }
+    }
+
+    let original = field.heights.clone();
+    if let Some(hydraulic) = &def.hydraulic {
+        field.erode_hydraulic(hydraulic, def.seed);
+    }
+    if let Some(thermal) = &def.thermal {
+        field.erode_thermal(thermal);
+    }
+    field.fade_towards_fixed(&original, def.border);
+
+    Ok(present
+        .into_iter()
+        .map(|(cx, cz, at)| {
+            let mut texels = Vec::with_capacity((res.x * res.y) as usize);
+            for j in 0..res.y {
+                for i in 0..res.x {
+                    texels.push(to_raw(field.heights[((at.y + j) * size.x + at.x + i) as usize]));
+                }
+            }
+            ((cx, cz), Tile16 { res, data: Arc::new(texels) })
+        })
+        .collect())
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::HashMap;
+
+    use super::*;
+    use crate::terrain::{GeneratedTiles, NoiseDef, NoiseHeightSource, ProceduralMode};
+
+    /// A bumpy 33x33 field: a slope with ridges across it.
+    fn field() -> Heightfield {
+        let size = UVec2::splat(33);
+        let heights = (0..size.y)
+            .flat_map(|j| (0..size.x).map(move |i| (i as f32, j as f32)))
+            .map(|(x, z)| 0.5 * x + 3.0 * (z * 0.7).sin() * (x * 0.3).cos())
+            .collect();
+        Heightfield::new(size, Vec2::ONE, heights)
+    }
+
+    fn bits(field: &Heightfield) -> Vec<u32> {
+        field.heights.iter().map(|h| h.to_bits()).collect()
+    }
+
+    #[test]
+    fn same_seed_erodes_bit_identically() {
+        let hydraulic = HydraulicErosion::default();
+        let thermal = ThermalErosion::default();
+        let run = |seed| {
+            let mut f = field();
+            f.erode_hydraulic(&hydraulic, seed);
+            f.erode_thermal(&thermal);
+            f
+        };
+
+        let (a, b) = (run(7), run(7));
+        assert_eq!(bits(&a), bits(&b));
+        assert_ne!(bits(&a), bits(&field()), "erosion changed nothing");
+        assert_ne!(bits(&a), bits(&run(8)), "the seed made no difference");
+    }
+
+    #[test]
+    fn shared_tile_edges_match() {
+        let res = UVec2::splat(33);
+        let chunk_size = Vec2::splat(64.0);
+        let mut cache = HeightTileCache::new("no-such-folder", res);
+        cache.generated = Some(GeneratedTiles {
+            mode: ProceduralMode::Replace,
+            source: Arc::new(NoiseHeightSource::new(1, NoiseDef { feature_size: 48.0, ..default() }, chunk_size)),
+        });
+        let data = HeightmapData {
+            size: chunk_size * 2.0,
+            chunk_size,
+            height_scale: 100.0,
+            ..default()
+        };
+        let region = TileRegion { min: IVec2::ZERO, max: IVec2::ONE };
+        let def = ErosionDef { thermal: Some(ThermalErosion::default()), border: 8.0, ..default() };
+
+        let tiles: HashMap<(i32, i32), Tile16> = erode_region(region, &def, &data, &cache).unwrap().into_iter().collect();
+        assert_eq!(tiles.len(), 4);
+        let last = res.x - 1;
+        let at = |key, i: u32, j: u32| tiles[&key].data[(j * res.x + i) as usize];
+        for k in 0..res.x {
+            assert_eq!(at((0, 0), last, k), at((1, 0), 0, k), "x edge, texel {}", k);
+            assert_eq!(at((0, 1), last, k), at((1, 1), 0, k), "x edge, texel {}", k);
+            assert_eq!(at((0, 0), k, last), at((0, 1), k, 0), "z edge, texel {}", k);
+            assert_eq!(at((1, 0), k, last), at((1, 1), k, 0), "z edge, texel {}", k);
+        }
+        // The edge between the tiles is inside the region, so it was eroded too
+        let original = cache.try_fetch_tile(0, 0).unwrap();
+        assert!((0..res.y).any(|k| at((0, 0), last, k) != original.data[(k * res.x + last) as usize]));
+    }
+}
@@ -0,0 +1,46 @@
+// src/terrain/events.rs
+//! Chunk lifecycle, in the order terrain emits it for one chunk:
+//!
+//! 1. `TerrainChunkRequested`: a mesh build task was started.
+//! 2. `TerrainChunkMeshBuilt`: the task finished; the mesh waits for its integration slot.
+//! 3. `TerrainChunkLoaded`: the chunk entity is in the world. Sent once per appearance, and
+//!    again when a chunk is rebuilt after `TerrainDeformed` (whose listeners dropped
+//!    anything placed on the old surface).
+//! 4. `TerrainChunkLodChanged`: a loaded chunk now shows a mesh at another LoD.
+//!    Not followed by another `TerrainChunkLoaded`.
+//! 5. `TerrainChunkUnloaded`: the chunk left the streamed set (or the map changed).
+//!
+//! 1 and 2 repeat for every rebuild (LoD changes, edge re-stitching after a neighbour
+//! changed LoD, edits); 3 and 5 always come in pairs.
+//!
+//! In quadtree mode (`terrain::quadtree`) only leaf nodes are chunks: they send 3 and 5.
+
+use bevy::prelude::*;
+
+use crate::props::core::{ChunkArea, ChunkCoord};
+use crate::terrain::lod::LodLevel;
+
+#[derive(Event, Clone, Copy, Debug)]
+pub struct TerrainChunkRequested {
+    pub coord: ChunkCoord,
+    pub lod: LodLevel,
+}
+
+#[derive(Event, Clone, Copy, Debug)]
+pub struct TerrainChunkMeshBuilt {
+    pub coord: ChunkCoord,
+    pub lod: LodLevel,
+}
+
+#[derive(Event, Clone, Copy, Debug)]
+pub struct TerrainChunkLoaded(pub ChunkArea);
+
+#[derive(Event, Clone, Copy, Debug)]
+pub struct TerrainChunkLodChanged {
+    pub coord: ChunkCoord,
+    pub from: LodLevel,
+    pub to: LodLevel,
+}
+
+#[derive(Event, Clone, Copy, Debug)]
+pub struct TerrainChunkUnloaded(pub ChunkCoord);
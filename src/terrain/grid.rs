@@ -0,0 +1,132 @@
+// src/terrain/grid.rs
+//! Helpers for the whole-map grids of the terrain analyses (drainage, distance to water,
+//! erosion): the D8 neighbourhood, an exact Euclidean distance transform, and sampling the
+//! heightmap on a regular grid from the mip level matching its spacing.
+
+use std::collections::HashMap;
+
+use bevy::prelude::*;
+
+use crate::heightmap_data::{height_in_tile, locate_in_tile, HeightTileCache, HeightmapData};
+
+/// D8 neighbour offsets (x, z), counter-clockwise from +x (`DrainageMap::flow_dir` indexes them).
+pub const D8: [IVec2; 8] = [
+    IVec2::new(1, 0),
+    IVec2::new(1, 1),
+    IVec2::new(0, 1),
+    IVec2::new(-1, 1),
+    IVec2::new(-1, 0),
+    IVec2::new(-1, -1),
+    IVec2::new(0, -1),
+    IVec2::new(1, -1),
+];
+
+/// Distance transform value (squared meters) of cells with no source in reach.
+pub(crate) const FAR: f64 = 1e20;
+
+/// Exact Euclidean distance transform (Felzenszwalb & Huttenlocher): for each cell of a
+/// `size` grid, the squared distance (meters) to the nearest `sources` cell and that cell's
+/// index. Without any source the distance is `FAR` or more.
+pub(crate) fn nearest_source(sources: &[bool], size: UVec2, spacing: Vec2) -> Vec<(f64, usize)> {
+    let (w, h) = (size.x as usize, size.y as usize);
+    // Columns: the nearest source in the same column
+    let mut columns = vec![(FAR, 0); w * h];
+    let mut f = vec![0.0; h];
+    for x in 0..w {
+        for (y, v) in f.iter_mut().enumerate() {
+            *v = if sources[y * w + x] { 0.0 } else { FAR };
+        }
+        for (y, (d, row)) in lower_envelope(&f, spacing.y as f64).into_iter().enumerate() {
+            columns[y * w + x] = (d, row * w + x);
+        }
+    }
+    // Rows: the nearest of those
+    let mut out = vec![(FAR, 0); w * h];
+    let mut f = vec![0.0; w];
+    for y in 0..h {
+        for (x, v) in f.iter_mut().enumerate() {
+            *v = columns[y * w + x].0;
+        }
+        for (x, (d, column)) in lower_envelope(&f, spacing.x as f64).into_iter().enumerate() {
+            out[y * w + x] = (d, columns[y * w + column].1);
+        }
+    }
+    out
+}
+
+/// 1D pass: `min over p of (step * (q - p))^2 + f[p]` for every q, and the p reaching it,
+/// via the lower envelope of the parabolas rooted at each sample.
+fn lower_envelope(f: &[f64], step: f64) -> Vec<(f64, usize)> {
+    let n = f.len();
+    let x = |i: usize| i as f64 * step;
+    let meet = |q: usize, p: usize| ((f[q] + x(q) * x(q)) - (f[p] + x(p) * x(p))) / (2.0 * (x(q) - x(p)));
+
+    // Parabola roots of the envelope and where each one takes over
+    let mut roots = vec![0usize; n];
+    let mut from = vec![0.0; n + 1];
+    let mut k = 0;
+    from[0] = f64::NEG_INFINITY;
+    from[1] = f64::INFINITY;
+    for q in 1..n {
+        let mut s = meet(q, roots[k]);
+        while s <= from[k] {
+            k -= 1;
+            s = meet(q, roots[k]);
+        }
+        k += 1;
+        roots[k] = q;
+        from[k] = s;
+        from[k + 1] = f64::INFINITY;
+    }
+
+    let mut out = Vec::with_capacity(n);
+    k = 0;
+    for q in 0..n {
+        while from[k + 1] < x(q) {
+            k += 1;
+        }
+        let d = x(q) - x(roots[k]);
+        out.push((d * d + f[roots[k]], roots[k]));
+    }
+    out
+}
+
+/// Heights (meters) on a `size` grid `cell` meters apart from `origin`, each cell read from
+/// the coarsest mip whose texels are at most `cell` apart; `None` off the map or where the
+/// tile is unreadable (void tiles give the void height). Points past the map's far edge are
+/// pulled just inside it. Blocking: reads each tile once, a row of tiles at a time, so run
+/// it on a task pool.
+pub(crate) fn sample_grid(
+    origin: Vec2,
+    cell: Vec2,
+    size: UVec2,
+    data: &HeightmapData,
+    cache: &HeightTileCache,
+) -> Vec<Option<f32>> {
+    let cells = (data.chunk_size / cell.max(Vec2::splat(f32::EPSILON))).ceil().min_element() as u32;
+    let level = cache.level_for_cells(cells);
+    let last = data.origin + data.size - Vec2::splat(1e-3);
+
+    let mut tiles = HashMap::new();
+    let mut out = Vec::with_capacity((size.x * size.y) as usize);
+    for j in 0..size.y {
+        for i in 0..size.x {
+            let p = (origin + Vec2::new(i as f32, j as f32) * cell).min(last);
+            let Some((key, uv)) = locate_in_tile(p.x, p.y, data) else {
+                out.push(None);
+                continue;
+            };
+            // Only the current row of tiles is kept
+            if tiles.keys().next().is_some_and(|k: &(i32, i32)| k.1 != key.1) {
+                tiles.clear();
+            }
+            let tile = tiles.entry(key).or_insert_with(|| cache.try_fetch_tile_level(key.0, key.1, level));
+            out.push(match tile {
+                Ok(t) => Some(height_in_tile(t, uv, data)),
+                Err(e) if e.is_missing() => Some(data.void_height),
+                Err(_) => None,
+            });
+        }
+    }
+    out
+}
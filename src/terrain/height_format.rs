@@ -0,0 +1,146 @@
+// src/terrain/height_format.rs
+//! Height tile encodings. Every format normalizes into `Tile16` (u16 samples),
+//! which is what `sample_height` and the chunk mesher read.
+
+use std::io::Cursor;
+use std::path::Path;
+use std::sync::Arc;
+
+use bevy::math::UVec2;
+use image::{DynamicImage, ImageBuffer, ImageFormat, ImageOutputFormat, Luma};
+use serde::{Deserialize, Serialize};
+
+use crate::heightmap_data::{HeightTileError, Tile16};
+
+/// On-disk encoding of a map's height tiles. Chosen per map in configuration.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Serialize, Deserialize)]
+pub enum HeightFormat {
+    /// Little-endian u16 RAW (Gaea / World Machine "r16").
+    #[default]
+    Raw16Le,
+    /// Big-endian u16 RAW (some Photoshop/Mac exports).
+    Raw16Be,
+    /// Little-endian f32 RAW. Values in `[min, max]` are remapped onto the full u16 range,
+    /// so pair this with `raw_minmax = (0, 65535)` and `height_scale = max - min`.
+    RawF32 { min: f32, max: f32 },
+    /// 16-bit grayscale PNG (8-bit PNGs are widened).
+    Png16,
+    /// Single-channel 8/16-bit TIFF (GeoTIFF tags are ignored).
+    Tiff,
+}
+
+impl HeightFormat {
+    /// Bytes per sample for RAW formats; `None` for self-describing image formats.
+    pub fn raw_sample_bytes(self) -> Option<usize> {
+        match self {
+            HeightFormat::Raw16Le | HeightFormat::Raw16Be => Some(2),
+            HeightFormat::RawF32 { .. } => Some(4),
+            HeightFormat::Png16 | HeightFormat::Tiff => None,
+        }
+    }
+
+    /// Decode one tile. `res` is required to be correct for RAW formats
+    /// (`None` = infer a square tile from the byte count); image formats carry their own size.
+    pub fn decode(self, path: &Path, bytes: &[u8], res: Option<UVec2>) -> Result<Tile16, HeightTileError> {
+        match self {
+            HeightFormat::Raw16Le => decode_raw(path, bytes, res, 2, |b| u16::from_le_bytes([b[0], b[1]])),
+            HeightFormat::Raw16Be => decode_raw(path, bytes, res, 2, |b| u16::from_be_bytes([b[0], b[1]])),
+            HeightFormat::RawF32 { min, max } => {
+                let inv_span = if max > min { 1.0 / (max - min) } else { 0.0 };
+                decode_raw(path, bytes, res, 4, |b| {
+                    let v = f32::from_le_bytes([b[0], b[1], b[2], b[3]]);
+                    (((v - min) * inv_span).clamp(0.0, 1.0) * u16::MAX as f32).round() as u16
+                })
+            }
+            HeightFormat::Png16 => decode_image(path, bytes, ImageFormat::Png),
+            HeightFormat::Tiff => decode_image(path, bytes, ImageFormat::Tiff),
+        }
+    }
+
+    /// Inverse of `decode` (used by the offline tools that rewrite tiles).
+    pub fn encode(self, path: &Path, tile: &Tile16) -> Result<Vec<u8>, HeightTileError> {
+        let samples = tile.data.iter().copied();
+        match self {
+            HeightFormat::Raw16Le => Ok(samples.flat_map(u16::to_le_bytes).collect()),
+            HeightFormat::Raw16Be => Ok(samples.flat_map(u16::to_be_bytes).collect()),
+            HeightFormat::RawF32 { min, max } => Ok(samples
+                .flat_map(|v| (min + v as f32 / u16::MAX as f32 * (max - min)).to_le_bytes())
+                .collect()),
+            HeightFormat::Png16 => encode_image(path, tile, ImageOutputFormat::Png),
+            HeightFormat::Tiff => encode_image(path, tile, ImageOutputFormat::Tiff),
+        }
+    }
+}
+
+/// Shared RAW path: validate size, then convert `stride`-byte samples with `conv`.
+fn decode_raw(
+    path: &Path,
+    bytes: &[u8],
+    res: Option<UVec2>,
+    stride: usize,
+    conv: impl Fn(&[u8]) -> u16,
+) -> Result<Tile16, HeightTileError> {
+    let res = match res {
+        Some(r) => r,
+        None => {
+            let side = ((bytes.len() / stride) as f64).sqrt() as u32;
+            if side < 2 || side as usize * side as usize * stride != bytes.len() {
+                return Err(HeightTileError::UnknownResolution {
+                    path: path.to_path_buf(),
+                    bytes: bytes.len(),
+                });
+            }
+            UVec2::splat(side)
+        }
+    };
+
+    // Trailing bytes past `res` are ignored (some exporters pad files).
+    let expected = (res.x * res.y) as usize * stride;
+    if bytes.len() < expected {
+        return Err(HeightTileError::Truncated {
+            path: path.to_path_buf(),
+            expected,
+            actual: bytes.len(),
+        });
+    }
+
+    let data = bytes[..expected].chunks_exact(stride).map(conv).collect::<Vec<_>>();
+    Ok(Tile16 { res, data: Arc::new(data) })
+}
+
+fn decode_image(path: &Path, bytes: &[u8], format: ImageFormat) -> Result<Tile16, HeightTileError> {
+    let decode_err = |reason: String| HeightTileError::Decode { path: path.to_path_buf(), reason };
+
+    let img = image::load(Cursor::new(bytes), format).map_err(|e| decode_err(e.to_string()))?;
+    let gray = match img {
+        DynamicImage::ImageLuma16(g) => g,
+        DynamicImage::ImageLuma8(_) => img.to_luma16(),
+        other => {
+            return Err(decode_err(format!(
+                "expected a single-channel image, got {:?}",
+                other.color()
+            )))
+        }
+    };
+
+    let (w, h) = gray.dimensions();
+    if w < 2 || h < 2 {
+        return Err(decode_err(format!("image is only {}x{}", w, h)));
+    }
+    Ok(Tile16 {
+        res: UVec2::new(w, h),
+        data: Arc::new(gray.into_raw()),
+    })
+}
+
+fn encode_image(path: &Path, tile: &Tile16, format: ImageOutputFormat) -> Result<Vec<u8>, HeightTileError> {
+    let write_err = |reason: String| HeightTileError::Write { path: path.to_path_buf(), reason };
+
+    let gray = ImageBuffer::<Luma<u16>, _>::from_raw(tile.res.x, tile.res.y, tile.data.to_vec())
+        .ok_or_else(|| write_err("sample count does not match the tile resolution".into()))?;
+    let mut out = Cursor::new(Vec::new());
+    DynamicImage::ImageLuma16(gray)
+        .write_to(&mut out, format)
+        .map_err(|e| write_err(e.to_string()))?;
+    Ok(out.into_inner())
+}
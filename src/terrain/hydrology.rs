@@ -0,0 +1,576 @@
+// src/terrain/hydrology.rs
+//! Surface drainage over the whole map, on a grid coarser than the tiles
+//! (`HydrologyDef::cell_size`): depressions are filled up to their spill point
+//! (Priority-Flood), each cell drains to its steepest D8 neighbour, and flow accumulation
+//! gives the area draining through every cell. Cells draining enough area are rivers,
+//! traced into `River` reaches that join at confluences and end in the water or at the
+//! map's edge. Every land cell also gets the watershed of the outlet it drains to.
+//!
+//! `Hydrology` is computed on the IO task pool when a map loads or its water level settles
+//! (brush edits don't reroute rivers). It drives the river water meshes, the placement
+//! filters `CommonFilters::river_distance_min/max` (reeds along banks) and unit movement
+//! through channels (`DrainageMap::wading_cost`).
+
+use std::cmp::Ordering;
+use std::collections::{BinaryHeap, HashMap, VecDeque};
+use std::sync::Arc;
+
+use bevy::prelude::*;
+use bevy::render::mesh::{Indices, Mesh, PrimitiveTopology};
+use bevy::tasks::{futures::check_ready, IoTaskPool, Task};
+use serde::{Deserialize, Serialize};
+
+use crate::heightmap_data::{HeightTileCache, HeightmapData};
+use crate::props::core::RiverSampler;
+use crate::terrain::grid::{nearest_source, sample_grid, D8, FAR};
+use crate::terrain::manifest::TerrainManifest;
+use crate::terrain::water::SettledWaterLevel;
+
+/// `flow_dir` of water cells and of edge cells draining off the map.
+pub const NO_FLOW: u8 = 8;
+
+/// Rise (meters) per cell across filled flats, so they still drain.
+const FILL_EPSILON: f32 = 1e-3;
+
+/// River meshes sit this far (meters) above the centerline, clear of the ground.
+const RIVER_SURFACE_OFFSET: f32 = 0.3;
+
+/// Manifest section for rivers (`hydrology: Some((river_area_km2: 0.5))`).
+#[derive(Clone, Copy, Debug, Serialize, Deserialize)]
+#[serde(default)]
+pub struct HydrologyDef {
+    /// Grid spacing (meters) of the drainage analysis.
+    pub cell_size: f32,
+    /// Area (km²) a stream must drain to count as a river.
+    pub river_area_km2: f32,
+    /// Channel width (meters) per square root of the km² drained.
+    pub river_width: f32,
+    /// Width (meters) of the smallest rivers.
+    pub min_river_width: f32,
+    /// Movement cost multiplier inside a channel.
+    pub wading_cost: f32,
+}
+
+impl Default for HydrologyDef {
+    fn default() -> Self {
+        Self {
+            cell_size: 8.0,
+            river_area_km2: 0.25,
+            river_width: 3.0,
+            min_river_width: 1.5,
+            wading_cost: 3.0,
+        }
+    }
+}
+
+impl HydrologyDef {
+    /// Mip levels a map with `chunk_size` tiles needs so the analysis reads a level with
+    /// about one texel per cell (`tile_step`: texel steps per full tile).
+    pub fn mip_levels(&self, tile_step: u32, chunk_size: Vec2) -> u32 {
+        let cells = (chunk_size.min_element() / self.cell_size.max(0.5)).ceil().max(1.0) as u32;
+        let mut levels = 0;
+        while tile_step >> (levels + 1) >= cells {
+            levels += 1;
+        }
+        levels
+    }
+
+    /// Channel width (meters) of a river draining `area` m².
+    pub fn width(&self, area: f32) -> f32 {
+        (self.river_width * (area / 1e6).sqrt()).max(self.min_river_width)
+    }
+}
+
+/// One reach of a river network: from a source or a confluence down to the next
+/// confluence, the water, or the map's edge.
+#[derive(Clone, Debug)]
+pub struct River {
+    /// World-space centerline, downstream; `y` is the water surface.
+    pub points: Vec<Vec3>,
+    /// Arclength (meters) at each point.
+    pub distances: Vec<f32>,
+    /// Area (m²) draining through each point.
+    pub areas: Vec<f32>,
+    /// Channel width (meters) at each point.
+    pub widths: Vec<f32>,
+    /// Reach this one joins at its last point; `None` when it ends in the water or leaves the map.
+    pub downstream: Option<usize>,
+    pub watershed: u32,
+}
+
+impl River {
+    fn new(points: Vec<Vec3>, areas: Vec<f32>, def: &HydrologyDef, downstream: Option<usize>, watershed: u32) -> Self {
+        let mut distances = Vec::with_capacity(points.len());
+        let mut s = 0.0;
+        for (i, p) in points.iter().enumerate() {
+            if i > 0 {
+                s += p.distance(points[i - 1]);
+            }
+            distances.push(s);
+        }
+        let widths = areas.iter().map(|&a| def.width(a)).collect();
+        Self { points, distances, areas, widths, downstream, watershed }
+    }
+
+    pub fn length(&self) -> f32 {
+        self.distances.last().copied().unwrap_or(0.0)
+    }
+
+    /// Point `s` meters down the reach, on a Catmull-Rom spline through `points`.
+    pub fn point_at(&self, s: f32) -> Vec3 {
+        let (i, t) = self.segment_at(s);
+        let last = self.points.len() as isize - 1;
+        let p = |k: isize| self.points[k.clamp(0, last) as usize];
+        let i = i as isize;
+        let (p0, p1, p2, p3) = (p(i - 1), p(i), p(i + 1), p(i + 2));
+        0.5 * (2.0 * p1
+            + (p2 - p0) * t
+            + (2.0 * p0 - 5.0 * p1 + 4.0 * p2 - p3) * t * t
+            + (3.0 * p1 - p0 - 3.0 * p2 + p3) * t * t * t)
+    }
+
+    /// Channel width (meters) `s` meters down the reach.
+    pub fn width_at(&self, s: f32) -> f32 {
+        let (i, t) = self.segment_at(s);
+        let next = (i + 1).min(self.widths.len() - 1);
+        self.widths[i] + (self.widths[next] - self.widths[i]) * t
+    }
+
+    /// Flat ribbon over the channel, with vertex rows about `spacing` meters apart (world space).
+    pub fn ribbon_mesh(&self, spacing: f32) -> Option<Mesh> {
+        let length = self.length();
+        if length <= f32::EPSILON {
+            return None;
+        }
+        let spacing = spacing.max(0.1);
+        let steps = (length / spacing).ceil().max(1.0) as usize;
+
+        let mut positions = Vec::with_capacity((steps + 1) * 2);
+        let mut uvs = Vec::with_capacity((steps + 1) * 2);
+        for k in 0..=steps {
+            let s = length * k as f32 / steps as f32;
+            let p = self.point_at(s);
+            let ahead = self.point_at((s + spacing * 0.5).min(length)) - self.point_at((s - spacing * 0.5).max(0.0));
+            let side = Vec3::new(-ahead.z, 0.0, ahead.x).normalize_or_zero() * self.width_at(s) * 0.5;
+            positions.push((p - side).to_array());
+            positions.push((p + side).to_array());
+            uvs.push([0.0, s]);
+            uvs.push([1.0, s]);
+        }
+        let normals = vec![[0.0, 1.0, 0.0]; positions.len()];
+
+        let mut indices = Vec::with_capacity(steps * 6);
+        for k in 0..steps as u32 {
+            let a = k * 2;
+            indices.extend_from_slice(&[a, a + 1, a + 2, a + 1, a + 3, a + 2]);
+        }
+
+        let mut mesh = Mesh::new(PrimitiveTopology::TriangleList, Default::default());
+        mesh.insert_attribute(Mesh::ATTRIBUTE_POSITION, positions);
+        mesh.insert_attribute(Mesh::ATTRIBUTE_NORMAL, normals);
+        mesh.insert_attribute(Mesh::ATTRIBUTE_UV_0, uvs);
+        mesh.insert_indices(Indices::U32(indices));
+        Some(mesh)
+    }
+
+    /// Segment index and position along it (0..1) at arclength `s`.
+    fn segment_at(&self, s: f32) -> (usize, f32) {
+        let last = self.points.len().saturating_sub(1);
+        if last == 0 {
+            return (0, 0.0);
+        }
+        let i = self.distances.partition_point(|&d| d <= s).clamp(1, last) - 1;
+        let len = self.distances[i + 1] - self.distances[i];
+        let t = if len > 0.0 { ((s - self.distances[i]) / len).clamp(0.0, 1.0) } else { 0.0 };
+        (i, t)
+    }
+}
+
+/// Drainage of a whole map. Grids are row-major over `size` cells.
+#[derive(Clone, Debug)]
+pub struct DrainageMap {
+    pub def: HydrologyDef,
+    /// World XZ of cell (0, 0)'s center.
+    pub origin: Vec2,
+    pub size: UVec2,
+    pub water_level: f32,
+    /// Ground height (meters) with depressions filled to their spill point.
+    pub filled: Vec<f32>,
+    /// Index into `D8` of the cell each cell drains to, or `NO_FLOW`.
+    pub flow_dir: Vec<u8>,
+    /// Area (m²) draining through each cell, its own included; 0 on water.
+    pub accumulation: Vec<f32>,
+    /// Watershed per cell, `1..=watershed_count`; 0 on water.
+    pub watershed: Vec<u32>,
+    pub watershed_count: u32,
+    pub rivers: Vec<River>,
+    /// Meters from each cell to the nearest river bank, negative inside a channel.
+    bank_distance: Vec<f32>,
+}
+
+impl DrainageMap {
+    /// Analyse the map's drainage. Blocking: reads every tile (at the mip matching
+    /// `def.cell_size`), so run it on the IO task pool.
+    pub fn compute(def: HydrologyDef, data: &HeightmapData, cache: &HeightTileCache, water_level: f32) -> Self {
+        let mut def = def;
+        def.cell_size = def.cell_size.max(0.5);
+        let cell = def.cell_size;
+        let size = (data.size / cell).ceil().as_uvec2().max(UVec2::ONE);
+        let origin = data.origin + Vec2::splat(cell * 0.5);
+        let n = (size.x * size.y) as usize;
+
+        // Ground under the water level (or unreadable) is water: it only takes flow in
+        let heights = sample_grid(origin, Vec2::splat(cell), size, data, cache);
+        let wet: Vec<bool> = heights.iter().map(|h| h.is_none_or(|h| h <= water_level)).collect();
+        let ground: Vec<f32> = heights.iter().map(|h| h.unwrap_or(water_level)).collect();
+
+        let filled = fill_depressions(&ground, &wet, size);
+
+        // D8: steepest descent on the filled surface
+        let mut flow_dir = vec![NO_FLOW; n];
+        for c in (0..n).filter(|&c| !wet[c]) {
+            let mut steepest = 0.0;
+            for (k, d) in D8.iter().enumerate() {
+                let Some(nb) = neighbour(c, k, size) else { continue };
+                let drop = (filled[c] - filled[nb]) / d.as_vec2().length();
+                if drop > steepest {
+                    steepest = drop;
+                    flow_dir[c] = k as u8;
+                }
+            }
+        }
+        let downstream = |c: usize| match flow_dir[c] {
+            NO_FLOW => None,
+            k => neighbour(c, k as usize, size),
+        };
+
+        // Accumulation, highest cells first so each one is complete before it is passed on
+        let mut order: Vec<usize> = (0..n).collect();
+        order.sort_by(|&a, &b| filled[b].total_cmp(&filled[a]));
+        let mut accumulation: Vec<f32> = wet.iter().map(|&w| if w { 0.0 } else { cell * cell }).collect();
+        for &c in &order {
+            if let Some(d) = downstream(c).filter(|&d| !wet[d]) {
+                accumulation[d] += accumulation[c];
+            }
+        }
+
+        // Watersheds, lowest cells first: one per body of water, one per edge outlet
+        let lakes = label_water(&wet, size);
+        let mut outlets = HashMap::new();
+        let mut watershed = vec![0u32; n];
+        let mut watershed_count = 0;
+        for &c in order.iter().rev().filter(|&&c| !wet[c]) {
+            watershed[c] = match downstream(c) {
+                Some(d) if wet[d] => *outlets.entry(lakes[d]).or_insert_with(|| {
+                    watershed_count += 1;
+                    watershed_count
+                }),
+                Some(d) => watershed[d],
+                None => {
+                    watershed_count += 1;
+                    watershed_count
+                }
+            };
+        }
+
+        // Rivers: reaches start at sources (no river flowing in) and at confluences
+        let threshold = def.river_area_km2 * 1e6;
+        let is_river: Vec<bool> = (0..n).map(|c| !wet[c] && accumulation[c] >= threshold).collect();
+        let mut inflows = vec![0u8; n];
+        for c in (0..n).filter(|&c| is_river[c]) {
+            if let Some(d) = downstream(c).filter(|&d| is_river[d]) {
+                inflows[d] = inflows[d].saturating_add(1);
+            }
+        }
+        let starts: Vec<usize> = (0..n).filter(|&c| is_river[c] && inflows[c] != 1).collect();
+        let reach_at: HashMap<usize, usize> = starts.iter().enumerate().map(|(r, &c)| (c, r)).collect();
+
+        let center = |c: usize| origin + Vec2::new((c as u32 % size.x) as f32, (c as u32 / size.x) as f32) * cell;
+        let mut rivers = Vec::with_capacity(starts.len());
+        for &start in &starts {
+            let mut cells = vec![start];
+            let mut next = None;
+            let mut mouth = None;
+            let mut c = start;
+            while let Some(d) = downstream(c) {
+                if wet[d] {
+                    mouth = Some(d);
+                    break;
+                }
+                cells.push(d);
+                if let Some(&r) = reach_at.get(&d) {
+                    next = Some(r);
+                    break;
+                }
+                c = d;
+            }
+
+            let mut points: Vec<Vec3> = cells.iter().map(|&c| center(c).extend(filled[c]).xzy()).collect();
+            let mut areas: Vec<f32> = cells.iter().map(|&c| accumulation[c]).collect();
+            if let Some(m) = mouth {
+                points.push(center(m).extend(water_level).xzy());
+                areas.push(accumulation[c]);
+            }
+            smooth(&mut points);
+            rivers.push(River::new(points, areas, &def, next, watershed[start]));
+        }
+
+        // Distance to the banks: to the nearest river cell, less its half-width
+        let nearest = nearest_source(&is_river, size, Vec2::splat(cell));
+        let bank_distance = nearest
+            .iter()
+            .map(|&(d, src)| {
+                if d >= FAR {
+                    f32::MAX
+                } else {
+                    d.sqrt() as f32 - def.width(accumulation[src]) * 0.5
+                }
+            })
+            .collect();
+
+        Self {
+            def,
+            origin,
+            size,
+            water_level,
+            filled,
+            flow_dir,
+            accumulation,
+            watershed,
+            watershed_count,
+            rivers,
+            bank_distance,
+        }
+    }
+
+    /// Meters from (x, z) to the nearest river bank, negative inside a channel; `None` off
+    /// the map or when the map has no rivers.
+    pub fn bank_distance(&self, x: f32, z: f32) -> Option<f32> {
+        if self.rivers.is_empty() {
+            return None;
+        }
+        let g = (Vec2::new(x, z) - self.origin) / self.def.cell_size;
+        let max = (self.size - UVec2::ONE).as_vec2();
+        if g.cmplt(Vec2::splat(-0.5)).any() || g.cmpgt(max + 0.5).any() {
+            return None;
+        }
+        let g = g.clamp(Vec2::ZERO, max);
+        let i0 = g.floor().as_uvec2();
+        let i1 = (i0 + UVec2::ONE).min(self.size - UVec2::ONE);
+        let f = g - i0.as_vec2();
+        let at = |i: u32, j: u32| self.bank_distance[(j * self.size.x + i) as usize];
+        let a = at(i0.x, i0.y) + (at(i1.x, i0.y) - at(i0.x, i0.y)) * f.x;
+        let b = at(i0.x, i1.y) + (at(i1.x, i1.y) - at(i0.x, i1.y)) * f.x;
+        Some(a + (b - a) * f.y)
+    }
+
+    /// Movement cost multiplier at (x, z): `def.wading_cost` inside a channel, else 1.
+    pub fn wading_cost(&self, x: f32, z: f32) -> f32 {
+        if self.bank_distance(x, z).is_some_and(|d| d < 0.0) {
+            self.def.wading_cost
+        } else {
+            1.0
+        }
+    }
+
+    /// Watershed at (x, z) (nearest cell); `None` off the map or on water.
+    pub fn watershed_at(&self, x: f32, z: f32) -> Option<u32> {
+        let g = ((Vec2::new(x, z) - self.origin) / self.def.cell_size).round();
+        if g.cmplt(Vec2::ZERO).any() || g.cmpge(self.size.as_vec2()).any() {
+            return None;
+        }
+        let g = g.as_uvec2();
+        Some(self.watershed[(g.y * self.size.x + g.x) as usize]).filter(|&w| w != 0)
+    }
+}
+
+impl RiverSampler for DrainageMap {
+    fn river_distance(&self, x: f32, z: f32) -> Option<f32> {
+        self.bank_distance(x, z)
+    }
+}
+
+/// Cell `c`'s neighbour in direction `D8[k]`, if inside the grid.
+fn neighbour(c: usize, k: usize, size: UVec2) -> Option<usize> {
+    let p = IVec2::new((c as u32 % size.x) as i32, (c as u32 / size.x) as i32) + D8[k];
+    if p.x < 0 || p.y < 0 || p.x >= size.x as i32 || p.y >= size.y as i32 {
+        return None;
+    }
+    Some((p.y as u32 * size.x + p.x as u32) as usize)
+}
+
+/// Priority-Flood (Barnes et al. 2014) with an epsilon gradient: flooding inward from the
+/// map's edge and the water, every cell ends up above the one that reached it, so all
+/// land drains. Depressions fill to their spill point, flats slope gently to their outlet.
+fn fill_depressions(ground: &[f32], wet: &[bool], size: UVec2) -> Vec<f32> {
+    let mut filled = ground.to_vec();
+    let mut done = vec![false; ground.len()];
+    let mut open = BinaryHeap::new();
+    for c in 0..ground.len() {
+        let (i, j) = (c as u32 % size.x, c as u32 / size.x);
+        let edge = i == 0 || j == 0 || i == size.x - 1 || j == size.y - 1;
+        if wet[c] || edge {
+            done[c] = true;
+            open.push(Lowest(filled[c], c));
+        }
+    }
+    while let Some(Lowest(h, c)) = open.pop() {
+        for k in 0..D8.len() {
+            let Some(nb) = neighbour(c, k, size) else { continue };
+            if done[nb] {
+                continue;
+            }
+            done[nb] = true;
+            filled[nb] = filled[nb].max(h + FILL_EPSILON);
+            open.push(Lowest(filled[nb], nb));
+        }
+    }
+    filled
+}
+
+/// Min-heap entry for `fill_depressions`.
+struct Lowest(f32, usize);
+
+impl PartialEq for Lowest {
+    fn eq(&self, other: &Self) -> bool {
+        self.cmp(other) == Ordering::Equal
+    }
+}
+
+impl Eq for Lowest {}
+
+impl PartialOrd for Lowest {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Ord for Lowest {
+    fn cmp(&self, other: &Self) -> Ordering {
+        other.0.total_cmp(&self.0).then(other.1.cmp(&self.1))
+    }
+}
+
+/// Connected bodies of water (8-neighbourhood): an id per wet cell.
+fn label_water(wet: &[bool], size: UVec2) -> Vec<u32> {
+    let mut label = vec![u32::MAX; wet.len()];
+    let mut next = 0;
+    let mut queue = VecDeque::new();
+    for seed in 0..wet.len() {
+        if !wet[seed] || label[seed] != u32::MAX {
+            continue;
+        }
+        label[seed] = next;
+        queue.push_back(seed);
+        while let Some(c) = queue.pop_front() {
+            for k in 0..D8.len() {
+                if let Some(nb) = neighbour(c, k, size).filter(|&nb| wet[nb] && label[nb] == u32::MAX) {
+                    label[nb] = next;
+                    queue.push_back(nb);
+                }
+            }
+        }
+        next += 1;
+    }
+    label
+}
+
+/// Two passes of a [1 2 1] / 4 filter, ends kept: takes the stair steps out of a D8 path.
+fn smooth(points: &mut [Vec3]) {
+    for _ in 0..2 {
+        let prev = points.to_vec();
+        for i in 1..points.len().saturating_sub(1) {
+            points[i] = (prev[i - 1] + 2.0 * prev[i] + prev[i + 1]) * 0.25;
+        }
+    }
+}
+
+// ---------- Systems ----------
+
+/// Drainage of the current map (manifests with a `hydrology` section). Cheap to clone.
+#[derive(Resource, Clone, Deref)]
+pub struct Hydrology(pub Arc<DrainageMap>);
+
+/// Drainage being computed for the current map.
+#[derive(Resource)]
+pub struct HydrologyTask(Task<DrainageMap>);
+
+/// A river's water mesh.
+#[derive(Component)]
+pub struct RiverWater;
+
+/// Recompute drainage when the map's tiles or its settled water level change. Runs on
+/// the IO task pool, since it reads every tile.
+pub fn start_hydrology(
+    mut commands: Commands,
+    manifest: Res<TerrainManifest>,
+    data: Res<HeightmapData>,
+    cache: Res<HeightTileCache>,
+    water: Res<SettledWaterLevel>,
+    rivers: Query<Entity, With<RiverWater>>,
+) {
+    if !cache.is_changed() && !water.is_changed() {
+        return;
+    }
+    for e in &rivers {
+        commands.entity(e).despawn();
+    }
+    commands.remove_resource::<Hydrology>();
+    let Some(def) = manifest.hydrology else {
+        commands.remove_resource::<HydrologyTask>();
+        return;
+    };
+
+    let data = data.clone();
+    let cache = cache.clone(); // shares the tile store
+    let water_level = water.0;
+    let task = IoTaskPool::get().spawn(async move { DrainageMap::compute(def, &data, &cache, water_level) });
+    commands.insert_resource(HydrologyTask(task));
+}
+
+pub fn finish_hydrology(mut commands: Commands, mut task: ResMut<HydrologyTask>) {
+    let Some(map) = check_ready(&mut task.0) else { return };
+    info!(
+        "Terrain: {} river reaches, {} watersheds ({}x{} drainage cells)",
+        map.rivers.len(),
+        map.watershed_count,
+        map.size.x,
+        map.size.y
+    );
+    commands.remove_resource::<HydrologyTask>();
+    commands.insert_resource(Hydrology(Arc::new(map)));
+}
+
+/// Give every river reach a water ribbon.
+pub fn spawn_river_water(
+    mut commands: Commands,
+    hydrology: Res<Hydrology>,
+    old: Query<Entity, With<RiverWater>>,
+    mut meshes: ResMut<Assets<Mesh>>,
+    mut materials: ResMut<Assets<StandardMaterial>>,
+) {
+    for e in &old {
+        commands.entity(e).despawn();
+    }
+
+    // Same look as the water slab (see `spawn_water`)
+    let material = materials.add(StandardMaterial {
+        base_color: Color::linear_rgba(0.0, 0.35, 0.55, 0.6),
+        alpha_mode: AlphaMode::Blend,
+        double_sided: true,
+        perceptual_roughness: 0.15,
+        reflectance: 0.6,
+        ..Default::default()
+    });
+
+    for (i, river) in hydrology.rivers.iter().enumerate() {
+        let Some(mesh) = river.ribbon_mesh(hydrology.def.cell_size * 0.5) else { continue };
+        commands.spawn((
+            Mesh3d(meshes.add(mesh)),
+            MeshMaterial3d(material.clone()),
+            Transform::from_xyz(0.0, RIVER_SURFACE_OFFSET, 0.0),
+            RiverWater,
+            Name::new(format!("River {}", i)),
+        ));
+    }
+}
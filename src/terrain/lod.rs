@@ -20,10 +20,74 @@ impl LodLevel {
 
     /// Distance thresholds → LoD (tweak to taste)
     pub fn pick(distance: f32) -> Self {
-        if distance < 600.0 { LodLevel::Near }
-        else if distance < 1200.0 { LodLevel::Mid }
+        if distance < NEAR_MAX { LodLevel::Near }
+        else if distance < MID_MAX { LodLevel::Mid }
         else { LodLevel::Far }
     }
+
+    /// Like `pick`, but a chunk keeps its `current` LoD until it is `LOD_HYSTERESIS`
+    /// past the threshold, so chunks sitting on a boundary don't rebuild every frame.
+    pub fn pick_sticky(distance: f32, current: Option<Self>) -> Self {
+        let fresh = Self::pick(distance);
+        match current {
+            Some(cur) if cur != fresh && cur.band_contains(distance, LOD_HYSTERESIS) => cur,
+            _ => fresh,
+        }
+    }
+
+    /// The tier with fewer vertices of the two.
+    pub fn coarser(self, other: Self) -> Self {
+        if other.grid_res().x < self.grid_res().x { other } else { self }
+    }
+
+    /// Distance band `[lo, hi)` of this tier, widened by `margin` on both sides.
+    fn band_contains(self, distance: f32, margin: f32) -> bool {
+        let (lo, hi) = match self {
+            LodLevel::Near => (0.0, NEAR_MAX),
+            LodLevel::Mid  => (NEAR_MAX, MID_MAX),
+            LodLevel::Far  => (MID_MAX, f32::INFINITY),
+        };
+        distance >= lo - margin && distance < hi + margin
+    }
+}
+
+const NEAR_MAX: f32 = 600.0;
+const MID_MAX: f32 = 1200.0;
+
+/// Extra distance (world units) a chunk must move past a threshold before its LoD flips.
+pub const LOD_HYSTERESIS: f32 = 64.0;
+
+/// Resolution each border of a chunk mesh is built at: its own LoD, or the coarser
+/// neighbour's on that side. Grids are nested (129 / 65 / 33), so snapping the extra
+/// vertices of the finer side onto the coarse edge closes T-junction cracks.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub struct EdgeLods {
+    /// -X side (cx - 1)
+    pub left: LodLevel,
+    /// +X side (cx + 1)
+    pub right: LodLevel,
+    /// -Z side (cz - 1)
+    pub down: LodLevel,
+    /// +Z side (cz + 1)
+    pub up: LodLevel,
+}
+
+impl EdgeLods {
+    /// Stitch `lod` against the neighbours `neighbour(dx, dz)` reports (None = no mesh there).
+    pub fn for_chunk(lod: LodLevel, neighbour: impl Fn(i32, i32) -> Option<LodLevel>) -> Self {
+        let side = |dx, dz| neighbour(dx, dz).map_or(lod, |n| lod.coarser(n));
+        Self {
+            left: side(-1, 0),
+            right: side(1, 0),
+            down: side(0, -1),
+            up: side(0, 1),
+        }
+    }
+
+    /// No border is stitched.
+    pub fn uniform(lod: LodLevel) -> Self {
+        Self { left: lod, right: lod, down: lod, up: lod }
+    }
 }
 
 /// Tag the chunk entity with its LoD
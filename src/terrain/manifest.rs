@@ -0,0 +1,324 @@
+// src/terrain/manifest.rs
+//! Data-driven map description (`*.terrain.ron`) + loader.
+
+use std::sync::Arc;
+
+use bevy::asset::{io::Reader, AssetLoader, LoadContext};
+use bevy::prelude::*;
+use serde::{Deserialize, Serialize};
+
+use crate::heightmap_data::{HeightTileCache, HeightTileError, HeightmapData, TileSet, TileSource};
+use crate::terrain::analysis::AnalysisScale;
+use crate::terrain::edit_layer::TileEditLayer;
+use crate::terrain::height_format::HeightFormat;
+use crate::terrain::hydrology::HydrologyDef;
+use crate::terrain::mesh_cache::ChunkMeshCache;
+use crate::terrain::procedural::{GeneratedTiles, NoiseHeightSource, ProceduralDef};
+use crate::terrain::quadtree::QuadtreeDef;
+use crate::terrain::rtin::ChunkMesher;
+use crate::terrain::systems::CHUNK_SIZE;
+
+// ---------- Public plugin to register asset+loader ----------
+
+pub struct TerrainManifestAssetPlugin;
+
+impl Plugin for TerrainManifestAssetPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_asset::<TerrainManifest>()
+            .register_asset_loader(TerrainManifestLoader);
+    }
+}
+
+// ---------- Manifest (data form) ----------
+
+/// Where and how the height tiles of a map are stored.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct HeightTilesDef {
+    /// Asset path of the tile folder (e.g. "Heightmaps").
+    pub folder: String,
+    /// -> {prefix}_y{cz}_x{cx}{ext}
+    #[serde(default = "default_tile_prefix")]
+    pub prefix: String,
+    #[serde(default = "default_tile_ext")]
+    pub ext: String,
+    #[serde(default)]
+    pub format: HeightFormat,
+    /// Texels per tile (X, Z).
+    pub resolution: UVec2,
+    /// Half-resolution levels below the tiles, in `{folder}/mip{k}` (`chasma-tiles slice
+    /// --mips`). Levels without files are generated from the full tiles when first needed.
+    #[serde(default)]
+    pub mips: u32,
+    /// Write mip tiles generated at runtime into their folders so the next run reads them.
+    #[serde(default = "default_mip_cache")]
+    pub mip_cache: bool,
+}
+
+fn default_tile_prefix() -> String {
+    "Heightmap".to_string()
+}
+fn default_tile_ext() -> String {
+    ".r16".to_string()
+}
+fn default_mip_cache() -> bool {
+    true
+}
+
+/// Which tiles a map has.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub enum TileLayout {
+    /// Dense `x` by `z` rectangle (a missing file still samples as void).
+    Grid(UVec2),
+    /// Exactly these (cx, cz) keys: islands, L-shapes, non-square maps.
+    List(Vec<IVec2>),
+    /// Scan `heightmaps.folder` for `{prefix}_y{cz}_x{cx}{ext}` when the map loads.
+    Discover,
+}
+
+/// Everything needed to stream one map. The active manifest is also inserted as a resource.
+#[derive(Asset, Resource, TypePath, Clone, Debug, Serialize, Deserialize)]
+pub struct TerrainManifest {
+    /// Display name (logs only).
+    #[serde(default)]
+    pub name: String,
+
+    pub heightmaps: HeightTilesDef,
+
+    /// Which tiles exist. One tile = one chunk.
+    pub tiles: TileLayout,
+
+    /// Height (meters) used where no tile exists (ocean floor / void).
+    #[serde(default)]
+    pub void_height: f32,
+
+    /// World-space size of one chunk/tile (X, Z) in meters.
+    #[serde(default = "default_chunk_size")]
+    pub chunk_size: Vec2,
+
+    /// World XZ of the (0,0) tile's min corner.
+    #[serde(default)]
+    pub origin: Vec2,
+
+    /// Meters between raw_min and raw_max.
+    pub height_scale: f32,
+    #[serde(default)]
+    pub raw_min: f32,
+    #[serde(default = "default_raw_max")]
+    pub raw_max: f32,
+
+    #[serde(default)]
+    pub water_level: f32,
+
+    /// Color texture per tile; `{cx}` / `{cz}` are substituted (e.g. "Textures/Texture_y{cz}_x{cx}.png").
+    pub color_tiles: String,
+
+    /// Memory for resident tiles and their analysis rasters.
+    #[serde(default = "default_tile_cache_budget_mb")]
+    pub tile_cache_budget_mb: usize,
+
+    /// Save runtime height edits as `.delta` files next to the tiles and merge them on load.
+    #[serde(default = "default_edit_layer")]
+    pub edit_layer: bool,
+
+    /// Triangulation of LoD chunk meshes (`Adaptive(max_error: 0.5)` for RTIN meshes).
+    #[serde(default)]
+    pub mesher: ChunkMesher,
+
+    /// Stream the map as a screen-space-error quadtree instead of fixed-radius LoD chunks.
+    #[serde(default)]
+    pub quadtree: Option<QuadtreeDef>,
+
+    /// Asset folder for built chunk meshes (see `ChunkMeshCache`). `None` = always build.
+    #[serde(default)]
+    pub mesh_cache: Option<String>,
+
+    /// Seeded noise terrain for tiles without files, or for the whole map.
+    #[serde(default)]
+    pub procedural: Option<ProceduralDef>,
+
+    /// Asset path of the map's biome rules (`*.biomes.ron`, see `BiomeRules`). `None` = no biomes.
+    #[serde(default)]
+    pub biomes: Option<String>,
+
+    /// Drainage analysis and rivers (see `terrain::hydrology`). `None` = no rivers.
+    #[serde(default)]
+    pub hydrology: Option<HydrologyDef>,
+
+    /// Texel size (meters) of the slope / curvature / roughness rasters computed per tile
+    /// (see `TileAnalysis`). 0 = the height tiles' own resolution.
+    #[serde(default = "default_analysis_spacing")]
+    pub analysis_spacing: f32,
+}
+
+fn default_chunk_size() -> Vec2 {
+    CHUNK_SIZE
+}
+fn default_raw_max() -> f32 {
+    65535.0
+}
+fn default_tile_cache_budget_mb() -> usize {
+    128
+}
+fn default_edit_layer() -> bool {
+    true
+}
+fn default_analysis_spacing() -> f32 {
+    1.0
+}
+
+impl TerrainManifest {
+    /// Global heightmap metadata for this map; the world rectangle covers `tiles.extent`.
+    pub fn heightmap_data(&self, tiles: &TileSet) -> HeightmapData {
+        HeightmapData {
+            size: Vec2::new(
+                self.chunk_size.x * tiles.extent.x as f32,
+                self.chunk_size.y * tiles.extent.y as f32,
+            ),
+            origin: self.origin,
+            height_scale: self.height_scale,
+            chunk_size: self.chunk_size,
+            raw_minmax: (self.raw_min, self.raw_max),
+            void_height: self.void_height,
+        }
+    }
+
+    /// Resolve `tiles` into a concrete set (scans the tile folder for `Discover`).
+    pub fn tile_set(&self, cache: &HeightTileCache) -> Result<TileSet, HeightTileError> {
+        match &self.tiles {
+            TileLayout::Grid(n) => Ok(TileSet::full(n.as_ivec2())),
+            TileLayout::List(keys) => Ok(TileSet::sparse(keys.iter().map(|k| (k.x, k.y)))),
+            TileLayout::Discover => cache.discover_tiles(),
+        }
+    }
+
+    /// The procedural tile source, seeded by `procedural.seed` or else `world_seed`.
+    pub fn generated_tiles(&self, world_seed: u64) -> Option<GeneratedTiles> {
+        self.procedural.as_ref().map(|def| GeneratedTiles {
+            mode: def.mode,
+            source: Arc::new(NoiseHeightSource::new(def.seed.unwrap_or(world_seed), def.noise, self.chunk_size)),
+        })
+    }
+
+    /// A fresh tile cache for this map, reading through `source`; procedural tiles (if any)
+    /// use `world_seed`. The tile set is resolved here too, so unknown keys never touch the disk.
+    pub fn tile_cache(&self, source: TileSource, world_seed: u64) -> Result<HeightTileCache, HeightTileError> {
+        let hm = &self.heightmaps;
+        let mut cache = HeightTileCache::new(&hm.folder, hm.resolution);
+        cache.source = source;
+        cache.format = hm.format;
+        cache.filename_prefix = hm.prefix.clone();
+        cache.filename_ext = hm.ext.clone();
+        cache.set_budget_bytes(self.tile_cache_budget_mb * 1024 * 1024);
+        cache.edits = self.edit_layer.then(TileEditLayer::default);
+        cache.generated = self.generated_tiles(world_seed);
+        cache.analysis = Some(AnalysisScale {
+            chunk_size: self.chunk_size,
+            height_scale: self.height_scale,
+            raw_minmax: (self.raw_min, self.raw_max),
+            spacing: self.analysis_spacing,
+        });
+        cache.tile_set = Some(self.tile_set(&cache)?);
+        cache.mip_disk_cache = hm.mip_cache;
+        let mut levels = hm.mips;
+        if let (Some(q), Some(set)) = (&self.quadtree, &cache.tile_set) {
+            // Quadtree nodes read the level matching their vertex spacing, down to the root's
+            levels = levels.max(q.mip_levels(hm.resolution.min_element().saturating_sub(1), set.extent));
+        }
+        if let Some(h) = &self.hydrology {
+            // The drainage analysis reads the level matching its cell size
+            levels = levels.max(h.mip_levels(hm.resolution.min_element().saturating_sub(1), self.chunk_size));
+        }
+        cache.set_mip_levels(levels);
+        if (cache.mips.len() as u32) < levels {
+            warn!(
+                "Terrain: {} of {} mip levels used; tiles of {} texels can't be halved further",
+                cache.mips.len(),
+                levels,
+                hm.resolution
+            );
+        }
+        Ok(cache)
+    }
+
+    /// Disk cache for this map's chunk meshes, if it has one.
+    pub fn chunk_mesh_cache(&self, source: TileSource) -> Option<ChunkMeshCache> {
+        self.mesh_cache.as_ref().map(|folder| ChunkMeshCache::new(folder, source))
+    }
+
+    /// Asset path of the color texture for a tile.
+    pub fn color_tile_path(&self, cx: i32, cz: i32) -> String {
+        self.color_tiles
+            .replace("{cx}", &cx.to_string())
+            .replace("{cz}", &cz.to_string())
+    }
+
+    /// Read a manifest straight from disk (CLI tools; the game goes through the asset server).
+    pub fn from_file(path: impl AsRef<std::path::Path>) -> Result<Self, TerrainManifestLoadError> {
+        let bytes = std::fs::read(path)?;
+        let manifest: TerrainManifest =
+            ron::de::from_bytes(&bytes).map_err(|e| TerrainManifestLoadError::Ron(e.to_string()))?;
+        manifest.validate()?;
+        Ok(manifest)
+    }
+
+    pub fn validate(&self) -> Result<(), TerrainManifestLoadError> {
+        match &self.tiles {
+            TileLayout::Grid(n) if n.x == 0 || n.y == 0 => {
+                return Err(TerrainManifestLoadError::Invalid("tiles: Grid must be at least (1, 1)".into()));
+            }
+            TileLayout::List(keys) if keys.is_empty() || keys.iter().any(|k| k.x < 0 || k.y < 0) => {
+                return Err(TerrainManifestLoadError::Invalid(
+                    "tiles: List must be non-empty with non-negative keys".into(),
+                ));
+            }
+            _ => {}
+        }
+        if self.heightmaps.resolution.x < 2 || self.heightmaps.resolution.y < 2 {
+            return Err(TerrainManifestLoadError::Invalid(
+                "heightmaps.resolution must be at least (2, 2)".into(),
+            ));
+        }
+        Ok(())
+    }
+}
+
+// ---------- Asset loader for `.terrain.ron` ----------
+
+#[derive(Default)]
+pub struct TerrainManifestLoader;
+
+impl AssetLoader for TerrainManifestLoader {
+    type Asset = TerrainManifest;
+    type Settings = ();
+    type Error = TerrainManifestLoadError;
+
+    fn extensions(&self) -> &[&str] {
+        &["terrain.ron"]
+    }
+
+    async fn load(
+        &self,
+        reader: &mut dyn Reader,
+        _settings: &Self::Settings,
+        _load_context: &mut LoadContext<'_>,
+    ) -> Result<Self::Asset, Self::Error> {
+        let mut bytes = Vec::new();
+        reader.read_to_end(&mut bytes).await?;
+        let manifest: TerrainManifest =
+            ron::de::from_bytes(&bytes).map_err(|e| TerrainManifestLoadError::Ron(e.to_string()))?;
+        manifest.validate()?;
+        Ok(manifest)
+    }
+}
+
+// ---------- Loader errors ----------
+
+#[derive(thiserror::Error, Debug)]
+pub enum TerrainManifestLoadError {
+    #[error("I/O while reading terrain manifest: {0}")]
+    Io(#[from] std::io::Error),
+    #[error("RON parse error: {0}")]
+    Ron(String),
+    #[error("Invalid terrain manifest: {0}")]
+    Invalid(String),
+}
@@ -0,0 +1,360 @@
+// src/terrain/mesh_cache.rs
+//! Disk cache of built chunk meshes, so a map pays for meshing once. Entries are
+//! `chunk_y{cz}_x{cx}_{lod}.mesh` files in the manifest's `mesh_cache` folder. Each one
+//! stores a key hashing everything its mesh was built from (the height tiles read, the
+//! heightmap's scaling and the mesher settings); an entry whose key doesn't match is a miss.
+//!
+//! Only unstitched meshes are cached: stitched borders depend on the neighbours' LoD and
+//! only occur along LoD boundaries. `chasma-tiles bake-meshes` fills the cache offline.
+//!
+//! On-disk format (little-endian):
+//!
+//! ```text
+//! "CHM1"  key: u64  vertex_count: u32  index_count: u32
+//! vertex_count x { position: 3 x f32  normal: 3 x f32  uv: 2 x f32 }
+//! index_count x index: u32
+//! ```
+
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
+
+use bevy::prelude::*;
+use bevy::render::mesh::{Indices, PrimitiveTopology, VertexAttributeValues};
+
+use crate::heightmap_data::{HeightTileCache, HeightTileError, HeightmapData, Tile16, TileSource};
+use crate::terrain::async_chunk_loader::{build_chunk_mesh_from_tiles, chunk_tile_level};
+use crate::terrain::lod::{EdgeLods, LodLevel};
+use crate::terrain::rtin::ChunkMesher;
+
+const MESH_MAGIC: &[u8; 4] = b"CHM1";
+const HEADER_BYTES: usize = 20;
+const VERTEX_BYTES: usize = 32;
+
+/// Bump when mesh building changes in a way the key can't see, to invalidate old entries.
+const MESH_BUILD_VERSION: u32 = 1;
+
+/// Where built chunk meshes are kept. Cheap to clone.
+#[derive(Resource, Clone)]
+pub struct ChunkMeshCache {
+    pub folder: PathBuf,
+    pub source: TileSource,
+    /// Set after the first failed write; read-only sources then stop trying (and warning).
+    write_failed: Arc<AtomicBool>,
+}
+
+impl ChunkMeshCache {
+    pub fn new(folder: impl AsRef<Path>, source: TileSource) -> Self {
+        Self {
+            folder: folder.as_ref().to_path_buf(),
+            source,
+            write_failed: Arc::new(AtomicBool::new(false)),
+        }
+    }
+
+    pub fn entry_path(&self, cx: i32, cz: i32, lod: LodLevel) -> PathBuf {
+        let lod = match lod {
+            LodLevel::Near => "near",
+            LodLevel::Mid => "mid",
+            LodLevel::Far => "far",
+        };
+        self.folder.join(format!("chunk_y{}_x{}_{}.mesh", cz, cx, lod))
+    }
+
+    /// The cached mesh if its entry was built with `key`; `None` otherwise
+    /// (unreadable entries are logged and treated as misses).
+    pub fn load(&self, cx: i32, cz: i32, lod: LodLevel, key: u64) -> Option<Mesh> {
+        let path = self.entry_path(cx, cz, lod);
+        let result = self
+            .source
+            .read_bytes(&path)
+            .and_then(|bytes| decode_mesh(&path, &bytes, key));
+        match result {
+            Ok(mesh) => mesh,
+            Err(e) if e.is_missing() => None,
+            Err(e) => {
+                warn!("Terrain: ignoring cached chunk mesh: {}", e);
+                None
+            }
+        }
+    }
+
+    /// Write `mesh` as the entry for `key`, replacing whatever was there.
+    pub fn store(&self, cx: i32, cz: i32, lod: LodLevel, key: u64, mesh: &Mesh) -> Result<(), HeightTileError> {
+        let path = self.entry_path(cx, cz, lod);
+        let bytes = encode_mesh(&path, mesh, key)?;
+        self.source.write_bytes(&path, &bytes)
+    }
+
+    /// `store` for the streamer: the first failure is logged and disables further writes.
+    pub(crate) fn store_quietly(&self, cx: i32, cz: i32, lod: LodLevel, key: u64, mesh: &Mesh) {
+        if self.write_failed.load(Ordering::Relaxed) {
+            return;
+        }
+        if let Err(e) = self.store(cx, cz, lod, key, mesh) {
+            warn!("Terrain: not caching chunk meshes any more: {}", e);
+            self.write_failed.store(true, Ordering::Relaxed);
+        }
+    }
+
+    /// Make sure the unstitched `lod` mesh of chunk (cx, cz) is cached, reading its tiles
+    /// at the same mip level the streamer does. Blocking (offline baking).
+    /// Returns whether an entry was written: `false` when it was current or the chunk is void.
+    pub fn bake(
+        &self,
+        cx: i32,
+        cz: i32,
+        lod: LodLevel,
+        mesher: ChunkMesher,
+        data: &HeightmapData,
+        tiles: &HeightTileCache,
+    ) -> Result<bool, HeightTileError> {
+        let level = chunk_tile_level(tiles, lod);
+        let fetch = |dx: i32, dz: i32| match tiles.try_fetch_tile_level(cx + dx, cz + dz, level) {
+            Ok(t) => Ok(Some(t)),
+            Err(e) if e.is_missing() => Ok(None),
+            Err(e) => Err(e),
+        };
+        let Some(cur) = fetch(0, 0)? else { return Ok(false) };
+        let (right, up, up_right) = (fetch(1, 0)?, fetch(0, 1)?, fetch(1, 1)?);
+
+        let store = tiles.level(level);
+        let hash = |dx: i32, dz: i32, t: Option<&Tile16>| t.map(|t| store.tile_hash(cx + dx, cz + dz, t));
+        let key = chunk_mesh_key(
+            lod,
+            mesher,
+            data,
+            [hash(0, 0, Some(&cur)), hash(1, 0, right.as_ref()), hash(0, 1, up.as_ref()), hash(1, 1, up_right.as_ref())],
+        );
+        if self.load(cx, cz, lod, key).is_some() {
+            return Ok(false);
+        }
+        let edges = EdgeLods::uniform(lod);
+        let Some(mesh) = build_chunk_mesh_from_tiles(cx, cz, lod.grid_res(), edges, mesher, data, cur, right, up, up_right)
+        else {
+            return Ok(false);
+        };
+        self.store(cx, cz, lod, key, &mesh)?;
+        Ok(true)
+    }
+}
+
+/// Hash of everything an unstitched chunk mesh is built from: its tiles (self, right, up,
+/// up-right, at whichever mip level; given as their `HeightTileCache::tile_hash`, `None`
+/// for a missing tile), the heightmap's placement and scaling, the vertex grid and the
+/// mesher. FNV-1a, so keys stay stable across builds and platforms.
+pub fn chunk_mesh_key(lod: LodLevel, mesher: ChunkMesher, data: &HeightmapData, tile_hashes: [Option<u64>; 4]) -> u64 {
+    let mut h = Fnv1a::default();
+    h.u32(MESH_BUILD_VERSION);
+    let grid = lod.grid_res();
+    h.u32(grid.x);
+    h.u32(grid.y);
+    match mesher {
+        ChunkMesher::Uniform => h.u32(0),
+        ChunkMesher::Adaptive { max_error } => {
+            h.u32(1);
+            h.f32(max_error);
+        }
+    }
+    for v in [
+        data.origin.x,
+        data.origin.y,
+        data.chunk_size.x,
+        data.chunk_size.y,
+        data.height_scale,
+        data.raw_minmax.0,
+        data.raw_minmax.1,
+    ] {
+        h.f32(v);
+    }
+    for tile in tile_hashes {
+        match tile {
+            Some(hash) => {
+                h.u32(1);
+                h.bytes(&hash.to_le_bytes());
+            }
+            None => h.u32(u32::MAX),
+        }
+    }
+    h.0
+}
+
+struct Fnv1a(u64);
+
+impl Default for Fnv1a {
+    fn default() -> Self {
+        Self(0xcbf2_9ce4_8422_2325)
+    }
+}
+
+impl Fnv1a {
+    fn bytes(&mut self, bytes: &[u8]) {
+        for &b in bytes {
+            self.0 ^= b as u64;
+            self.0 = self.0.wrapping_mul(0x0100_0000_01b3);
+        }
+    }
+
+    fn u32(&mut self, v: u32) {
+        self.bytes(&v.to_le_bytes());
+    }
+
+    fn f32(&mut self, v: f32) {
+        self.u32(v.to_bits());
+    }
+}
+
+fn encode_mesh(path: &Path, mesh: &Mesh, key: u64) -> Result<Vec<u8>, HeightTileError> {
+    let bad = |reason: &str| HeightTileError::Write { path: path.to_path_buf(), reason: reason.to_string() };
+    let (
+        Some(VertexAttributeValues::Float32x3(positions)),
+        Some(VertexAttributeValues::Float32x3(normals)),
+        Some(VertexAttributeValues::Float32x2(uvs)),
+        Some(Indices::U32(indices)),
+    ) = (
+        mesh.attribute(Mesh::ATTRIBUTE_POSITION),
+        mesh.attribute(Mesh::ATTRIBUTE_NORMAL),
+        mesh.attribute(Mesh::ATTRIBUTE_UV_0),
+        mesh.indices(),
+    )
+    else {
+        return Err(bad("chunk mesh needs positions, normals, UVs and u32 indices"));
+    };
+    if normals.len() != positions.len() || uvs.len() != positions.len() {
+        return Err(bad("chunk mesh attributes differ in length"));
+    }
+
+    let mut out = Vec::with_capacity(HEADER_BYTES + positions.len() * VERTEX_BYTES + indices.len() * 4);
+    out.extend_from_slice(MESH_MAGIC);
+    out.extend_from_slice(&key.to_le_bytes());
+    out.extend_from_slice(&(positions.len() as u32).to_le_bytes());
+    out.extend_from_slice(&(indices.len() as u32).to_le_bytes());
+    for ((p, n), t) in positions.iter().zip(normals).zip(uvs) {
+        for v in p.iter().chain(n).chain(t) {
+            out.extend_from_slice(&v.to_le_bytes());
+        }
+    }
+    for i in indices {
+        out.extend_from_slice(&i.to_le_bytes());
+    }
+    Ok(out)
+}
+
+/// `Ok(None)` when the entry was built with a different key.
+fn decode_mesh(path: &Path, bytes: &[u8], key: u64) -> Result<Option<Mesh>, HeightTileError> {
+    let bad = |reason: &str| HeightTileError::Decode { path: path.to_path_buf(), reason: reason.to_string() };
+    if bytes.len() < HEADER_BYTES || bytes.get(..4) != Some(MESH_MAGIC.as_slice()) {
+        return Err(bad("not a chunk mesh cache entry"));
+    }
+    let u32_at = |at: usize| u32::from_le_bytes([bytes[at], bytes[at + 1], bytes[at + 2], bytes[at + 3]]);
+    let f32_at = |at: usize| f32::from_bits(u32_at(at));
+
+    let stored_key = u64::from(u32_at(4)) | (u64::from(u32_at(8)) << 32);
+    if stored_key != key {
+        return Ok(None);
+    }
+    let vertex_count = u32_at(12) as usize;
+    let index_count = u32_at(16) as usize;
+    if bytes.len() != HEADER_BYTES + vertex_count * VERTEX_BYTES + index_count * 4 {
+        return Err(bad("size doesn't match its vertex and index counts"));
+    }
+
+    let mut positions = Vec::with_capacity(vertex_count);
+    let mut normals = Vec::with_capacity(vertex_count);
+    let mut uvs = Vec::with_capacity(vertex_count);
+    for v in 0..vertex_count {
+        let at = HEADER_BYTES + v * VERTEX_BYTES;
+        positions.push([f32_at(at), f32_at(at + 4), f32_at(at + 8)]);
+        normals.push([f32_at(at + 12), f32_at(at + 16), f32_at(at + 20)]);
+        uvs.push([f32_at(at + 24), f32_at(at + 28)]);
+    }
+    let first_index = HEADER_BYTES + vertex_count * VERTEX_BYTES;
+    let indices: Vec<u32> = (0..index_count).map(|i| u32_at(first_index + i * 4)).collect();
+    if indices.iter().any(|&i| i as usize >= vertex_count) {
+        return Err(bad("index past the last vertex"));
+    }
+
+    let mut mesh = Mesh::new(PrimitiveTopology::TriangleList, Default::default());
+    mesh.insert_attribute(Mesh::ATTRIBUTE_POSITION, positions);
+    mesh.insert_attribute(Mesh::ATTRIBUTE_NORMAL, normals);
+    mesh.insert_attribute(Mesh::ATTRIBUTE_UV_0, uvs);
+    mesh.insert_indices(Indices::U32(indices));
+    Ok(Some(mesh))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const KEY: u64 = 0x0123_4567_89ab_cdef;
+
+    fn path() -> PathBuf {
+        PathBuf::from("chunk_y0_x0_near.mesh")
+    }
+
+    /// A unit quad.
+    fn quad() -> Mesh {
+        let mut mesh = Mesh::new(PrimitiveTopology::TriangleList, Default::default());
+        mesh.insert_attribute(
+            Mesh::ATTRIBUTE_POSITION,
+            vec![[0.0, 1.0, 0.0], [1.0, 1.5, 0.0], [0.0, -2.0, 1.0], [1.0, 0.25, 1.0]],
+        );
+        mesh.insert_attribute(Mesh::ATTRIBUTE_NORMAL, vec![[0.0, 1.0, 0.0]; 4]);
+        mesh.insert_attribute(Mesh::ATTRIBUTE_UV_0, vec![[0.0, 0.0], [1.0, 0.0], [0.0, 1.0], [1.0, 1.0]]);
+        mesh.insert_indices(Indices::U32(vec![0, 2, 1, 1, 2, 3]));
+        mesh
+    }
+
+    fn float3(mesh: &Mesh, id: impl Into<bevy::render::mesh::MeshVertexAttributeId>) -> Vec<[f32; 3]> {
+        match mesh.attribute(id) {
+            Some(VertexAttributeValues::Float32x3(v)) => v.clone(),
+            other => panic!("unexpected attribute {other:?}"),
+        }
+    }
+
+    fn uvs(mesh: &Mesh) -> Vec<[f32; 2]> {
+        match mesh.attribute(Mesh::ATTRIBUTE_UV_0) {
+            Some(VertexAttributeValues::Float32x2(v)) => v.clone(),
+            other => panic!("unexpected attribute {other:?}"),
+        }
+    }
+
+    #[test]
+    fn round_trips() {
+        let mesh = quad();
+        let bytes = encode_mesh(&path(), &mesh, KEY).unwrap();
+        assert_eq!(bytes.len(), HEADER_BYTES + 4 * VERTEX_BYTES + 6 * 4);
+        let decoded = decode_mesh(&path(), &bytes, KEY).unwrap().expect("key matches");
+
+        assert_eq!(float3(&decoded, Mesh::ATTRIBUTE_POSITION), float3(&mesh, Mesh::ATTRIBUTE_POSITION));
+        assert_eq!(float3(&decoded, Mesh::ATTRIBUTE_NORMAL), float3(&mesh, Mesh::ATTRIBUTE_NORMAL));
+        assert_eq!(uvs(&decoded), uvs(&mesh));
+        let indices = |m: &Mesh| m.indices().map(|i| i.iter().collect::<Vec<_>>());
+        assert_eq!(indices(&decoded), indices(&mesh));
+    }
+
+    #[test]
+    fn other_key_is_a_miss() {
+        let bytes = encode_mesh(&path(), &quad(), KEY).unwrap();
+        assert!(decode_mesh(&path(), &bytes, KEY ^ 1).unwrap().is_none());
+    }
+
+    #[test]
+    fn rejects_truncated_input() {
+        let bytes = encode_mesh(&path(), &quad(), KEY).unwrap();
+        for len in 0..bytes.len() {
+            assert!(decode_mesh(&path(), &bytes[..len], KEY).is_err(), "accepted {len} bytes");
+        }
+        let mut longer = bytes.clone();
+        longer.push(0);
+        assert!(decode_mesh(&path(), &longer, KEY).is_err());
+    }
+
+    #[test]
+    fn rejects_out_of_range_indices() {
+        let mut bytes = encode_mesh(&path(), &quad(), KEY).unwrap();
+        let last = bytes.len() - 4;
+        bytes[last..].copy_from_slice(&4u32.to_le_bytes());
+        assert!(decode_mesh(&path(), &bytes, KEY).is_err());
+    }
+}
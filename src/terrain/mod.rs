@@ -6,6 +6,51 @@ mod async_chunk_loader;
 mod water;
 mod compat;
 mod lod;
+mod tile_asset;
+mod height_format;
+mod manifest;
+mod seams;
+mod raycast;
+mod deform;
+mod edit_layer;
+mod events;
+mod quadtree;
+mod rtin;
+mod mesh_cache;
+mod procedural;
+mod analysis;
+mod grid;
+mod biome;
+mod hydrology;
+mod erosion;
 
 pub use plugin::TerrainPlugin;
-pub use compat::{ChunkCoords, LocalOffset, world_to_chunk_and_local};
\ No newline at end of file
+pub use compat::{ChunkCoords, LocalOffset, world_to_chunk_and_local};
+pub use tile_asset::{HeightTile, HeightTileAssetPlugin, HeightTileLoaderSettings};
+pub use height_format::HeightFormat;
+pub use manifest::{HeightTilesDef, TerrainManifest, TileLayout};
+pub use systems::TerrainSettings;
+pub use deform::{apply_brush, BrushOp, BrushShape, DeformTerrain, DirtyChunks, TerrainBrush, TerrainDeformed};
+pub use edit_layer::{SaveTerrainEdits, TileDelta, TileEditLayer};
+pub use events::{
+    TerrainChunkLoaded, TerrainChunkLodChanged, TerrainChunkMeshBuilt, TerrainChunkRequested,
+    TerrainChunkUnloaded,
+};
+pub use lod::{ChunkLod, EdgeLods, LodLevel};
+pub use quadtree::{NodeBounds, QuadNode, QuadtreeDef, QuadtreeTerrain};
+pub use rtin::{ChunkMesher, Rtin};
+pub use mesh_cache::{chunk_mesh_key, ChunkMeshCache};
+pub use procedural::{GeneratedTiles, HeightSource, NoiseDef, NoiseHeightSource, ProceduralDef, ProceduralMode};
+pub use analysis::{
+    best_site, sample_terrain, sample_terrain_ready, site_score, AnalysisScale, SiteRequirements, TerrainAttributes,
+    TileAnalysis,
+};
+pub use biome::{
+    BiomeDef, BiomeLayer, BiomeOverlay, BiomeRaster, BiomeRules, BiomeRulesAssetPlugin, BiomeSample, Bounds, ClimateDef,
+    NO_BIOME,
+};
+pub use erosion::{erode_region, ErodedTiles, ErosionDef, Heightfield, HydraulicErosion, ThermalErosion, TileRegion};
+pub use grid::D8;
+pub use hydrology::{DrainageMap, Hydrology, HydrologyDef, River, RiverWater, NO_FLOW};
+pub use raycast::{raycast, TerrainHit};
+pub use seams::{fix_tile_seams, validate_tile_seams, SeamAxis, SeamReport, SeamValidation};
@@ -1,69 +1,119 @@
 use bevy::prelude::*;
+use bevy::ecs::schedule::common_conditions::{resource_exists, resource_exists_and_changed};
 
-use crate::heightmap_data::{HeightTileCache, HeightmapData};
+use crate::heightmap_data::HeightmapData;
 use crate::terrain::async_chunk_loader::{
-    async_receive_chunks, async_schedule_chunks, AsyncChunkLoader, IntegrationBudget, MeshBuildBudget, // ← added IntegrationBudget
+    async_receive_chunks, async_schedule_chunks, IntegrationBudget, MeshBuildBudget,
 };
-use crate::terrain::systems::{init_terrain_params, CHUNK_SIZE};
-use crate::terrain::water::{spawn_water, WaterLevel};
-
-// ---- Configure these to match your Gaea export ----
-const RAW_FOLDER: &str = "assets/heightmaps";   // where your *.raw16 tiles live
-const FILENAME_PREFIX: &str = "Heightmap";      // -> {prefix}_y{cz}_x{cx}.raw16
-const FILENAME_EXT: &str = ".r16";              // UshortRaw16
-pub const COLOR_FOLDER: &str = "textures";      // folder containing color tiles
-pub const COLOR_PREFIX: &str = "Texture";       // -> {prefix}_y{cz}_x{cx}{ext}
-pub const COLOR_EXT: &str = ".png";             // your exported color tile ext
-const TILE_RES_X: u32 = 1024;
-const TILE_RES_Z: u32 = 1024;
-const TILES_X: i32 = 16;
-const TILES_Z: i32 = 16;
-
-// RAW normalization
-const RAW_MIN: f32 = 0.0;
-const RAW_MAX: f32 = 65535.0;
+use crate::terrain::biome::{
+    apply_biome_rules, forget_stale_biomes, load_biome_rules, toggle_biome_overlay, update_biome_overlay, BiomeLayer,
+    BiomeOverlay, BiomeRulesAssetPlugin, BiomeRulesHandle,
+};
+use crate::terrain::deform::{apply_terrain_deformations, DeformTerrain, DirtyChunks, TerrainDeformed};
+use crate::terrain::edit_layer::{save_terrain_edits, SaveTerrainEdits};
+use crate::terrain::events::{
+    TerrainChunkLoaded, TerrainChunkLodChanged, TerrainChunkMeshBuilt, TerrainChunkRequested,
+    TerrainChunkUnloaded,
+};
+use crate::terrain::hydrology::{finish_hydrology, spawn_river_water, start_hydrology, Hydrology, HydrologyTask};
+use crate::terrain::manifest::{TerrainManifest, TerrainManifestAssetPlugin};
+use crate::terrain::quadtree::{quadtree_mode, receive_quadtree_nodes, update_quadtree_terrain};
+use crate::terrain::systems::{
+    apply_terrain_manifest, load_terrain_manifest, TerrainManifestHandle, TerrainSettings,
+};
+use crate::terrain::tile_asset::HeightTileAssetPlugin;
+use crate::terrain::water::{settle_water_level, spawn_water, SettledWaterLevel, WaterLevel};
 
-// World mapping
-const TERRAIN_ORIGIN_X: f32 = 0.0;
-const TERRAIN_ORIGIN_Z: f32 = 0.0;
-const HEIGHT_SCALE_METERS: f32 = 600.0;
+// Map layout, tile format, height scale, water level and color tiles all come from
+// the `*.terrain.ron` manifest named by `TerrainSettings::manifest_path`.
 
 pub struct TerrainPlugin;
 
 impl Plugin for TerrainPlugin {
     fn build(&self, app: &mut App) {
-        // Compute total world size from chunk size and tile counts
-        let world_size = Vec2::new(
-            CHUNK_SIZE.x * TILES_X as f32,
-            CHUNK_SIZE.y * TILES_Z as f32,
-        );
-
-        // Global heightmap metadata
-        let hmd = HeightmapData {
-            size: world_size,
-            origin: Vec2::new(TERRAIN_ORIGIN_X, TERRAIN_ORIGIN_Z),
-            height_scale: HEIGHT_SCALE_METERS,
-            chunk_size: CHUNK_SIZE, // reaffirmed in init_terrain_params
-            raw_minmax: (RAW_MIN, RAW_MAX),
-        };
-
-        // RAW16 tile cache
-        let mut cache = HeightTileCache::new(RAW_FOLDER, UVec2::new(TILE_RES_X, TILE_RES_Z));
-        cache.filename_prefix = FILENAME_PREFIX.to_string();
-        cache.filename_ext = FILENAME_EXT.to_string();
-
         app
-            // Core resources
-            .insert_resource(hmd)
-            .insert_resource(cache)
-            .insert_resource(WaterLevel(40.0))
-            .insert_resource(AsyncChunkLoader::default())
+            // `.r16` + `.terrain.ron` + `.biomes.ron` asset loaders
+            .add_plugins(HeightTileAssetPlugin)
+            .add_plugins(TerrainManifestAssetPlugin)
+            .add_plugins(BiomeRulesAssetPlugin)
+            // Core resources (HeightmapData / HeightTileCache / WaterLevel arrive with the manifest)
+            .init_resource::<TerrainSettings>()
+            .init_resource::<TerrainManifestHandle>()
             .insert_resource(MeshBuildBudget::default())
             .insert_resource(IntegrationBudget::default()) // ← add the budget resource
-            // Initialize chunk manager + push CHUNK_SIZE into HeightmapData
-            .add_systems(Startup, init_terrain_params)
-            .add_systems(Startup, spawn_water)
-            // Streaming pipeline (unchanged order; budget is enforced in async_receive_chunks)
-            .add_systems(Update, (async_schedule_chunks, async_receive_chunks).chain());
+            .init_resource::<BiomeRulesHandle>()
+            .init_resource::<BiomeOverlay>()
+            // Runtime deformation (brush requests in, remesh + notification out)
+            .init_resource::<DirtyChunks>()
+            .add_event::<DeformTerrain>()
+            .add_event::<TerrainDeformed>()
+            .add_event::<SaveTerrainEdits>()
+            // Chunk lifecycle (see `terrain::events`)
+            .add_event::<TerrainChunkRequested>()
+            .add_event::<TerrainChunkMeshBuilt>()
+            .add_event::<TerrainChunkLoaded>()
+            .add_event::<TerrainChunkLodChanged>()
+            .add_event::<TerrainChunkUnloaded>()
+            // Manifest → resources (also handles hot reload and map switches)
+            .add_systems(Update, (load_terrain_manifest, apply_terrain_manifest).chain())
+            .add_systems(
+                Update,
+                spawn_water
+                    .after(apply_terrain_manifest)
+                    .run_if(resource_exists_and_changed::<WaterLevel>),
+            )
+            .add_systems(
+                Update,
+                settle_water_level
+                    .after(apply_terrain_manifest)
+                    .run_if(resource_exists::<SettledWaterLevel>),
+            )
+            // Streaming pipeline: LoD chunks, or the quadtree when the manifest asks for it
+            // (budgets are enforced in the receive systems)
+            .add_systems(
+                Update,
+                (
+                    apply_terrain_deformations,
+                    (async_schedule_chunks, async_receive_chunks)
+                        .chain()
+                        .run_if(not(quadtree_mode)),
+                    (update_quadtree_terrain, receive_quadtree_nodes)
+                        .chain()
+                        .run_if(quadtree_mode),
+                )
+                    .chain()
+                    .after(apply_terrain_manifest)
+                    .run_if(resource_exists::<HeightmapData>),
+            )
+            // Biomes: rules named by the manifest → `BiomeLayer`, plus the debug overlay
+            .add_systems(
+                Update,
+                (
+                    load_biome_rules.run_if(resource_exists::<TerrainManifest>),
+                    apply_biome_rules.run_if(resource_exists::<HeightmapData>),
+                    forget_stale_biomes.run_if(resource_exists::<BiomeLayer>),
+                    (toggle_biome_overlay, update_biome_overlay).chain(),
+                )
+                    .chain()
+                    .after(apply_terrain_deformations)
+                    .after(settle_water_level),
+            )
+            // Drainage and rivers, computed on a task pool per map / water level
+            .add_systems(
+                Update,
+                (
+                    start_hydrology.run_if(resource_exists::<HeightmapData>),
+                    finish_hydrology.run_if(resource_exists::<HydrologyTask>),
+                    spawn_river_water.run_if(resource_exists_and_changed::<Hydrology>),
+                )
+                    .chain()
+                    .after(settle_water_level),
+            )
+            .add_systems(
+                Update,
+                save_terrain_edits
+                    .after(apply_terrain_deformations)
+                    .run_if(resource_exists::<HeightmapData>),
+            );
     }
 }
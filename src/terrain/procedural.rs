@@ -0,0 +1,201 @@
+// src/terrain/procedural.rs
+//! Procedural height tiles. A `HeightSource` produces tiles on demand; `HeightTileCache`
+//! uses one (see `GeneratedTiles`) to fill tiles missing from disk, or instead of the files
+//! altogether, so a map can be prototyped, or tests run, with no exported assets.
+//!
+//! `NoiseHeightSource` is the seeded generator behind the manifest's `procedural` section:
+//! fBm blended with ridged multifractal noise, both sampled through a domain warp. Noise is
+//! evaluated per world-space texel, so neighbouring tiles share their edge texels exactly.
+
+use std::f32::consts::FRAC_1_SQRT_2;
+use std::sync::Arc;
+
+use bevy::prelude::*;
+use serde::{Deserialize, Serialize};
+
+use crate::heightmap_data::Tile16;
+
+/// Produces height tiles without files.
+pub trait HeightSource: Send + Sync + 'static {
+    /// Tile (cx, cz) with `res` texels. Texel `(i, j)` sits at chunk-space position
+    /// `(cx + i / (res.x - 1), cz + j / (res.y - 1))`, so shared edges must come out equal.
+    fn tile(&self, cx: i32, cz: i32, res: UVec2) -> Tile16;
+}
+
+/// What a `HeightSource` is used for.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub enum ProceduralMode {
+    /// Generate tiles whose files don't exist, and tiles a sparse layout leaves out.
+    #[default]
+    FillGaps,
+    /// Never read tile files; every tile of the layout is generated.
+    Replace,
+}
+
+/// A `HeightSource` plugged into a `HeightTileCache`.
+#[derive(Clone)]
+pub struct GeneratedTiles {
+    pub mode: ProceduralMode,
+    pub source: Arc<dyn HeightSource>,
+}
+
+/// Manifest section for procedural terrain (`procedural: Some((mode: Replace))`).
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct ProceduralDef {
+    #[serde(default)]
+    pub mode: ProceduralMode,
+    /// `None` = the `WorldSeed`.
+    #[serde(default)]
+    pub seed: Option<u64>,
+    #[serde(default)]
+    pub noise: NoiseDef,
+}
+
+/// Shape of `NoiseHeightSource` terrain. Heights come out in `0..=1` of the raw range.
+#[derive(Clone, Copy, Debug, Serialize, Deserialize)]
+#[serde(default)]
+pub struct NoiseDef {
+    /// Wavelength of the first octave, in meters.
+    pub feature_size: f32,
+    pub octaves: u32,
+    /// Frequency multiplier per octave.
+    pub lacunarity: f32,
+    /// Amplitude multiplier per octave.
+    pub gain: f32,
+    /// Blend from rolling fBm (0) to ridged mountains (1).
+    pub ridged: f32,
+    /// Domain warp displacement, in meters (0 = no warp).
+    pub warp: f32,
+}
+
+impl Default for NoiseDef {
+    fn default() -> Self {
+        Self {
+            feature_size: 2048.0,
+            octaves: 6,
+            lacunarity: 2.0,
+            gain: 0.5,
+            ridged: 0.5,
+            warp: 300.0,
+        }
+    }
+}
+
+/// Seeded fBm + ridged noise through a domain warp.
+pub struct NoiseHeightSource {
+    pub seed: u64,
+    pub noise: NoiseDef,
+    /// World size of one tile (the manifest's `chunk_size`).
+    pub chunk_size: Vec2,
+}
+
+impl NoiseHeightSource {
+    pub fn new(seed: u64, noise: NoiseDef, chunk_size: Vec2) -> Self {
+        Self { seed, noise, chunk_size }
+    }
+
+    /// Height in `0..=1` at a world-space position relative to the map origin.
+    pub fn height(&self, p: Vec2) -> f32 {
+        let n = &self.noise;
+        let p = p / n.feature_size.max(f32::EPSILON);
+        let warp = n.warp / n.feature_size.max(f32::EPSILON);
+        let q = if warp != 0.0 {
+            let w = Vec2::new(
+                self.fbm(p + Vec2::new(5.2, 1.3), 3, 1),
+                self.fbm(p + Vec2::new(1.7, 9.2), 3, 2),
+            );
+            p + w * warp
+        } else {
+            p
+        };
+
+        let smooth = self.fbm(q, n.octaves, 3) * 0.5 + 0.5;
+        let ridges = self.ridged(q, n.octaves, 4);
+        (smooth + (ridges - smooth) * n.ridged.clamp(0.0, 1.0)).clamp(0.0, 1.0)
+    }
+
+    /// Fractal Brownian motion in about `-1..=1`.
+    fn fbm(&self, p: Vec2, octaves: u32, stream: u64) -> f32 {
+        let n = &self.noise;
+        let (mut sum, mut norm, mut amp, mut freq) = (0.0, 0.0, 1.0, 1.0);
+        for o in 0..octaves.max(1) {
+            sum += amp * gradient_noise(self.octave_seed(stream, o), p * freq);
+            norm += amp;
+            amp *= n.gain;
+            freq *= n.lacunarity;
+        }
+        sum / norm
+    }
+
+    /// Ridged multifractal in `0..=1`: creases where the noise crosses zero, with each
+    /// octave weighted by the one before so detail gathers on the ridges.
+    fn ridged(&self, p: Vec2, octaves: u32, stream: u64) -> f32 {
+        let n = &self.noise;
+        let (mut sum, mut norm, mut amp, mut freq, mut weight) = (0.0, 0.0, 1.0, 1.0, 1.0);
+        for o in 0..octaves.max(1) {
+            let r = 1.0 - gradient_noise(self.octave_seed(stream, o), p * freq).abs();
+            let r = r * r * weight;
+            weight = (r * 2.0).clamp(0.0, 1.0);
+            sum += amp * r;
+            norm += amp;
+            amp *= n.gain;
+            freq *= n.lacunarity;
+        }
+        sum / norm
+    }
+
+    fn octave_seed(&self, stream: u64, octave: u32) -> u64 {
+        mix64(self.seed ^ stream.wrapping_mul(0x9E37_79B9_7F4A_7C15) ^ (octave as u64) << 40)
+    }
+}
+
+impl HeightSource for NoiseHeightSource {
+    fn tile(&self, cx: i32, cz: i32, res: UVec2) -> Tile16 {
+        let cells = (res - UVec2::ONE).max(UVec2::ONE).as_vec2();
+        let mut data = Vec::with_capacity((res.x * res.y) as usize);
+        for j in 0..res.y {
+            for i in 0..res.x {
+                let chunk_pos = Vec2::new(cx as f32, cz as f32) + Vec2::new(i as f32, j as f32) / cells;
+                let h = self.height(chunk_pos * self.chunk_size);
+                data.push((h * u16::MAX as f32).round() as u16);
+            }
+        }
+        Tile16 { res, data: Arc::new(data) }
+    }
+}
+
+/// 2D gradient noise in about `-1..=1`, with hashed lattice gradients.
+pub(crate) fn gradient_noise(seed: u64, p: Vec2) -> f32 {
+    let cell = p.floor();
+    let f = p - cell;
+    let (x0, z0) = (cell.x as i32, cell.y as i32);
+    let dot = |dx: i32, dz: i32| gradient(seed, x0 + dx, z0 + dz).dot(f - Vec2::new(dx as f32, dz as f32));
+
+    let fade = f * f * f * (f * (f * 6.0 - Vec2::splat(15.0)) + Vec2::splat(10.0));
+    let a = dot(0, 0) + (dot(1, 0) - dot(0, 0)) * fade.x;
+    let b = dot(0, 1) + (dot(1, 1) - dot(0, 1)) * fade.x;
+    // A gradient-noise value never exceeds sqrt(1/2) in 2D
+    (a + (b - a) * fade.y) / FRAC_1_SQRT_2
+}
+
+fn gradient(seed: u64, x: i32, z: i32) -> Vec2 {
+    const DIRS: [Vec2; 8] = [
+        Vec2::new(1.0, 0.0),
+        Vec2::new(-1.0, 0.0),
+        Vec2::new(0.0, 1.0),
+        Vec2::new(0.0, -1.0),
+        Vec2::new(FRAC_1_SQRT_2, FRAC_1_SQRT_2),
+        Vec2::new(-FRAC_1_SQRT_2, FRAC_1_SQRT_2),
+        Vec2::new(FRAC_1_SQRT_2, -FRAC_1_SQRT_2),
+        Vec2::new(-FRAC_1_SQRT_2, -FRAC_1_SQRT_2),
+    ];
+    let h = mix64(seed ^ (x as u32 as u64) ^ ((z as u32 as u64) << 32));
+    DIRS[(h & 7) as usize]
+}
+
+/// SplitMix64 finalizer.
+pub(crate) fn mix64(mut h: u64) -> u64 {
+    h = (h ^ (h >> 30)).wrapping_mul(0xBF58_476D_1CE4_E5B9);
+    h = (h ^ (h >> 27)).wrapping_mul(0x94D0_49BB_1331_11EB);
+    h ^ (h >> 31)
+}
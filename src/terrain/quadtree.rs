@@ -0,0 +1,635 @@
+// src/terrain/quadtree.rs
+//! Quadtree terrain (CDLOD-style), used instead of per-chunk tiers when the manifest has a
+//! `quadtree` section.
+//!
+//! The map is covered by a quadtree whose leaves are single chunks and whose root spans the
+//! whole map. Every frame the tree is refined, largest screen-space error first, until each
+//! selected node's projected geometric error is under `max_screen_error` pixels or
+//! `max_nodes` is reached. Every node is one `node_grid` x `node_grid` mesh, so the triangle
+//! count is bounded no matter how much of the map is in view.
+//!
+//! - A node reads its tiles from the mip level matching its vertex spacing (see
+//!   `node_mip_level`); only leaves may read full tiles, so the root never does.
+//! - A node's geometric error is bounded when its mesh is built, from per-tile summaries of
+//!   the level it reads (height range, largest second difference), computed once per tile
+//!   and level; until then it is estimated from the vertex spacing.
+//! - Swaps are double-buffered: a node that is no longer selected stays visible until every
+//!   selected node covering its area has been integrated, and those stay hidden until then.
+//! - Neighbours of different size are hidden behind skirts instead of stitched.
+//! - Color tiles are per chunk, so only leaves are textured; merged nodes are shaded by height.
+//! - Leaves emit `TerrainChunkLoaded` / `TerrainChunkUnloaded` (props only care about the
+//!   ground near the camera); edits rebuild every node over a dirty chunk.
+
+use std::collections::{HashMap, HashSet};
+use std::sync::{Arc, Mutex};
+
+use bevy::ecs::system::SystemParam;
+use bevy::prelude::*;
+use bevy::render::camera::Projection;
+use bevy::render::mesh::{Indices, Mesh, PrimitiveTopology};
+use bevy::tasks::{futures::check_ready, AsyncComputeTaskPool, Task};
+use serde::{Deserialize, Serialize};
+
+use crate::heightmap_data::{height_in_tile, HeightTileCache, HeightmapData, Tile16, TileStatus};
+use crate::props::core::{ChunkArea, ChunkCoord};
+use crate::setup::MainCamera;
+use crate::terrain::async_chunk_loader::{IntegrationBudget, MeshBuildBudget};
+use crate::terrain::chunking::{chunk_counts, chunk_world_aabb};
+use crate::terrain::components::{ChunkKey, ChunkReady, Terrain};
+use crate::terrain::deform::DirtyChunks;
+use crate::terrain::events::{TerrainChunkLoaded, TerrainChunkUnloaded};
+use crate::terrain::manifest::TerrainManifest;
+
+/// Slope assumed for nodes whose error hasn't been measured yet (error ≈ spacing * slope).
+const ESTIMATED_SLOPE: f32 = 0.5;
+
+/// Manifest section enabling the quadtree mode.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct QuadtreeDef {
+    /// Largest tolerated projected geometric error, in pixels.
+    #[serde(default = "default_max_screen_error")]
+    pub max_screen_error: f32,
+    /// Vertices per node side (odd, e.g. 65).
+    #[serde(default = "default_node_grid")]
+    pub node_grid: u32,
+    /// Upper bound on selected nodes (and so on triangles: `max_nodes * 2 * (node_grid - 1)^2`).
+    #[serde(default = "default_max_nodes")]
+    pub max_nodes: usize,
+}
+
+fn default_max_screen_error() -> f32 {
+    2.0
+}
+fn default_node_grid() -> u32 {
+    65
+}
+fn default_max_nodes() -> usize {
+    256
+}
+
+impl QuadtreeDef {
+    /// Mip levels a map `chunks` tiles across needs so that even the root node reads a level
+    /// with no more than about one texel per vertex (`tile_step`: texel steps per full tile).
+    pub fn mip_levels(&self, tile_step: u32, chunks: IVec2) -> u32 {
+        let root_level = (chunks.max_element().max(1) as u32).next_power_of_two().trailing_zeros();
+        let cells = ((self.node_grid.max(2) - 1) >> root_level).max(1);
+        let mut levels = 0;
+        while tile_step >> (levels + 1) >= cells {
+            levels += 1;
+        }
+        levels
+    }
+}
+
+impl Default for QuadtreeDef {
+    fn default() -> Self {
+        Self {
+            max_screen_error: default_max_screen_error(),
+            node_grid: default_node_grid(),
+            max_nodes: default_max_nodes(),
+        }
+    }
+}
+
+/// One quadtree node: covers chunks `[x, x+1) * 2^level` by `[z, z+1) * 2^level`.
+#[derive(Component, Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub struct QuadNode {
+    pub level: u32,
+    pub x: i32,
+    pub z: i32,
+}
+
+impl QuadNode {
+    /// Smallest node covering a `counts.x` by `counts.z` chunk grid.
+    pub fn root(data: &HeightmapData) -> Self {
+        let counts = chunk_counts(data);
+        let span = counts.x.max(counts.z).max(1) as u32;
+        Self { level: span.next_power_of_two().trailing_zeros(), x: 0, z: 0 }
+    }
+
+    /// Chunks per side.
+    pub fn span(self) -> i32 {
+        1 << self.level
+    }
+
+    /// Chunk range `[min, max)`.
+    pub fn chunk_range(self) -> (IVec2, IVec2) {
+        let min = IVec2::new(self.x, self.z) * self.span();
+        (min, min + IVec2::splat(self.span()))
+    }
+
+    pub fn children(self) -> Option<[QuadNode; 4]> {
+        if self.level == 0 {
+            return None;
+        }
+        let (level, x, z) = (self.level - 1, self.x * 2, self.z * 2);
+        Some([
+            QuadNode { level, x, z },
+            QuadNode { level, x: x + 1, z },
+            QuadNode { level, x, z: z + 1 },
+            QuadNode { level, x: x + 1, z: z + 1 },
+        ])
+    }
+
+    pub fn overlaps(self, other: QuadNode) -> bool {
+        let (a0, a1) = self.chunk_range();
+        let (b0, b1) = other.chunk_range();
+        a0.x < b1.x && b0.x < a1.x && a0.y < b1.y && b0.y < a1.y
+    }
+
+    pub fn contains_chunk(self, cx: i32, cz: i32) -> bool {
+        let (min, max) = self.chunk_range();
+        cx >= min.x && cx < max.x && cz >= min.y && cz < max.y
+    }
+
+    /// World XZ rectangle, clipped to the map (non power-of-two maps).
+    pub fn world_rect(self, data: &HeightmapData) -> (Vec2, Vec2) {
+        let (min, max) = self.chunk_range();
+        let lo = data.origin + min.as_vec2() * data.chunk_size;
+        let hi = (data.origin + max.as_vec2() * data.chunk_size).min(data.origin + data.size);
+        (lo, hi)
+    }
+
+    /// Whether any part of the node lies on the map.
+    fn on_map(self, data: &HeightmapData) -> bool {
+        let (lo, hi) = self.world_rect(data);
+        lo.x < hi.x && lo.y < hi.y
+    }
+}
+
+/// Error bound and height range of a built node (meters).
+#[derive(Clone, Copy, Debug)]
+pub struct NodeBounds {
+    pub error: f32,
+    pub min: f32,
+    pub max: f32,
+}
+
+/// Summary of one tile at one mip level, for node error bounds (meters).
+#[derive(Clone, Copy, Debug)]
+struct TileBounds {
+    min: f32,
+    max: f32,
+    /// Largest `|d2h/dx2| + |d2h/dz2|` as a second difference over one texel.
+    bend: f32,
+}
+
+impl TileBounds {
+    fn compute(tile: &Tile16, data: &HeightmapData) -> Self {
+        let (rmin, rmax) = data.raw_minmax;
+        let to_m = if rmax > rmin { data.height_scale / (rmax - rmin) } else { 0.0 };
+        let (w, h) = (tile.res.x as i32, tile.res.y as i32);
+        let raw = |x: i32, z: i32| tile.get_clamped(x, z) as f32;
+        let (mut min, mut max, mut bend) = (f32::INFINITY, f32::NEG_INFINITY, 0.0f32);
+        for z in 0..h {
+            for x in 0..w {
+                let v = raw(x, z);
+                min = min.min(v);
+                max = max.max(v);
+                if x > 0 && z > 0 && x < w - 1 && z < h - 1 {
+                    let d2x = raw(x - 1, z) - 2.0 * v + raw(x + 1, z);
+                    let d2z = raw(x, z - 1) - 2.0 * v + raw(x, z + 1);
+                    bend = bend.max(d2x.abs() + d2z.abs());
+                }
+            }
+        }
+        let height = |r: f32| (r - rmin).clamp(0.0, (rmax - rmin).max(0.0)) * to_m;
+        Self { min: height(min), max: height(max), bend: bend * to_m }
+    }
+}
+
+/// Tile bounds by (mip level, cx, cz), shared with the build tasks.
+type TileBoundsStore = Arc<Mutex<HashMap<(u32, i32, i32), TileBounds>>>;
+
+/// Worker building a node mesh -> mesh + its bounds.
+type NodeBuild = Task<(Mesh, NodeBounds)>;
+
+/// Node entity currently in the world.
+struct ShownNode {
+    entity: Entity,
+    visible: bool,
+}
+
+/// Quadtree streaming state; replaced on map switches like `ChunkManager`.
+#[derive(Resource, Default)]
+pub struct QuadtreeTerrain {
+    /// Nodes chosen by the last selection pass.
+    pub selected: HashSet<QuadNode>,
+    /// Error bound and height range of every node built so far.
+    pub bounds: HashMap<QuadNode, NodeBounds>,
+    /// Per-tile summaries the bounds are made from (dropped for edited tiles).
+    tile_bounds: TileBoundsStore,
+    shown: HashMap<QuadNode, ShownNode>,
+    /// In-flight builds: (node, rebuild after an edit, task)
+    tasks: Vec<(QuadNode, bool, NodeBuild)>,
+    /// Shown nodes whose mesh is stale after an edit.
+    rebuild: HashSet<QuadNode>,
+    /// Shared material for merged (untextured) nodes.
+    merged_material: Option<Handle<StandardMaterial>>,
+}
+
+impl QuadtreeTerrain {
+    /// Entities of every node mesh (for despawning on map switches).
+    pub fn entities(&self) -> impl Iterator<Item = Entity> + '_ {
+        self.shown.values().map(|n| n.entity)
+    }
+
+    /// Chunks currently covered by shown leaves.
+    pub fn shown_leaves(&self) -> impl Iterator<Item = (i32, i32)> + '_ {
+        self.shown.keys().filter(|n| n.level == 0).map(|n| (n.x, n.z))
+    }
+}
+
+/// Run condition: the active manifest asks for the quadtree mode.
+pub fn quadtree_mode(manifest: Option<Res<TerrainManifest>>) -> bool {
+    manifest.is_some_and(|m| m.quadtree.is_some())
+}
+
+/// The map the quadtree covers.
+#[derive(SystemParam)]
+pub struct QuadtreeMap<'w> {
+    data: Res<'w, HeightmapData>,
+    cache: Res<'w, HeightTileCache>,
+    manifest: Res<'w, TerrainManifest>,
+}
+
+/// Select nodes by screen-space error, retire replaced nodes and start mesh builds.
+pub fn update_quadtree_terrain(
+    mut commands: Commands,
+    mut tree: ResMut<QuadtreeTerrain>,
+    cam_q: Query<(&Transform, &Camera, &Projection), With<MainCamera>>,
+    map: QuadtreeMap,
+    mut dirty: ResMut<DirtyChunks>,
+    build_budget: Option<Res<MeshBuildBudget>>,
+    mut evw_unloaded: EventWriter<TerrainChunkUnloaded>,
+) {
+    let QuadtreeMap { data, cache, manifest } = map;
+    let Some(def) = manifest.quadtree.clone() else { return };
+    let Ok((cam_tf, camera, projection)) = cam_q.single() else { return };
+    let tree = &mut *tree;
+
+    // 1) Edits: every node over a dirty chunk is rebuilt (the old mesh stays until then)
+    if !dirty.0.is_empty() {
+        let touched = |n: &QuadNode| dirty.0.iter().any(|&(cx, cz)| n.contains_chunk(cx, cz));
+        tree.bounds.retain(|n, _| !touched(n));
+        tree.tile_bounds
+            .lock()
+            .expect("quadtree tile bounds mutex poisoned")
+            .retain(|&(_, cx, cz), _| !dirty.0.contains(&(cx, cz)));
+        tree.tasks.retain(|(n, _, _)| !touched(n));
+        let stale: Vec<QuadNode> = tree.shown.keys().filter(|n| touched(n)).copied().collect();
+        tree.rebuild.extend(stale);
+        dirty.0.clear();
+    }
+
+    // 2) Selection: split the node with the largest screen error until all are in budget
+    let fov = match projection {
+        Projection::Perspective(p) => p.fov,
+        _ => std::f32::consts::FRAC_PI_3,
+    };
+    let viewport_h = camera.logical_viewport_size().map_or(1080.0, |s| s.y);
+    let px_per_rad = viewport_h / (2.0 * (fov * 0.5).tan());
+    let eye = cam_tf.translation;
+    let grid = def.node_grid.max(2);
+    let screen_error = |n: QuadNode| -> f32 {
+        let (lo, hi) = n.world_rect(&data);
+        let b = tree.bounds.get(&n).copied().unwrap_or_else(|| NodeBounds {
+            error: (hi - lo).max_element() / (grid - 1) as f32 * ESTIMATED_SLOPE,
+            min: data.void_height.min(0.0),
+            max: data.void_height.max(data.height_scale),
+        });
+        let closest = eye.clamp(Vec3::new(lo.x, b.min, lo.y), Vec3::new(hi.x, b.max, hi.y));
+        b.error * px_per_rad / eye.distance(closest).max(1.0)
+    };
+
+    let mut nodes = vec![QuadNode::root(&data)];
+    loop {
+        let worst = nodes
+            .iter()
+            .enumerate()
+            .filter(|(_, n)| n.level > 0)
+            .map(|(i, n)| (i, screen_error(*n)))
+            .filter(|(_, e)| *e > def.max_screen_error)
+            .max_by(|a, b| a.1.total_cmp(&b.1));
+        let Some((i, _)) = worst else { break };
+        let Some(children) = nodes[i].children() else { break };
+        let children: Vec<QuadNode> = children.into_iter().filter(|c| c.on_map(&data)).collect();
+        if nodes.len() - 1 + children.len() > def.max_nodes.max(1) {
+            break;
+        }
+        nodes.swap_remove(i);
+        nodes.extend(children);
+    }
+    tree.selected = nodes.into_iter().collect();
+
+    // 3) Drop builds for nodes that are no longer wanted
+    {
+        let selected = &tree.selected;
+        tree.tasks.retain(|(n, _, _)| selected.contains(n));
+    }
+
+    // 4) Retire nodes that left the selection once their replacements are all in;
+    //    replacements stay hidden until the node they cover is gone
+    let mut blocked: HashSet<QuadNode> = HashSet::new();
+    let stale: Vec<QuadNode> = tree.shown.keys().filter(|n| !tree.selected.contains(n)).copied().collect();
+    for node in stale {
+        let cover: Vec<QuadNode> = tree.selected.iter().filter(|s| s.overlaps(node)).copied().collect();
+        if cover.iter().all(|s| tree.shown.contains_key(s)) {
+            if let Some(shown) = tree.shown.remove(&node) {
+                commands.entity(shown.entity).despawn();
+                if node.level == 0 {
+                    evw_unloaded.write(TerrainChunkUnloaded(ChunkCoord { x: node.x, z: node.z }));
+                }
+            }
+            tree.rebuild.remove(&node);
+        } else {
+            blocked.extend(cover);
+        }
+    }
+    for (node, shown) in tree.shown.iter_mut() {
+        let visible = !blocked.contains(node);
+        if visible != shown.visible {
+            shown.visible = visible;
+            commands.entity(shown.entity).insert(if visible { Visibility::Visible } else { Visibility::Hidden });
+        }
+    }
+
+    // 5) Launch builds, coarse nodes first so the horizon fills in quickly
+    let mut wanted: Vec<QuadNode> = tree
+        .selected
+        .iter()
+        .filter(|n| !tree.shown.contains_key(n) || tree.rebuild.contains(n))
+        .filter(|n| !tree.tasks.iter().any(|(t, _, _)| t == *n))
+        .copied()
+        .collect();
+    wanted.sort_by_key(|n| std::cmp::Reverse(n.level));
+
+    // A node waits (without using the budget) until its tiles are resident at its level
+    let start_budget = build_budget.map(|b| b.0).unwrap_or(usize::MAX);
+    let mut started = 0usize;
+    for node in wanted {
+        if started >= start_budget {
+            break;
+        }
+        let level = node_mip_level(node, grid as usize, &cache);
+        let Some(tiles) = node_tiles(node, level, &data, &cache) else { continue };
+        let rebuild = tree.rebuild.remove(&node);
+        let (data_c, bounds_c) = (data.clone(), tree.tile_bounds.clone());
+        let task = AsyncComputeTaskPool::get()
+            .spawn(async move { build_node_mesh(node, grid as usize, level, &tiles, &data_c, &bounds_c) });
+        tree.tasks.push((node, rebuild, task));
+        started += 1;
+    }
+}
+
+/// Where node meshes, their materials and leaf color tiles come from.
+#[derive(SystemParam)]
+pub struct NodeAssets<'w> {
+    meshes: ResMut<'w, Assets<Mesh>>,
+    materials: ResMut<'w, Assets<StandardMaterial>>,
+    asset_server: Res<'w, AssetServer>,
+}
+
+/// Integrate finished node meshes (up to `IntegrationBudget` per frame).
+pub fn receive_quadtree_nodes(
+    mut commands: Commands,
+    mut tree: ResMut<QuadtreeTerrain>,
+    assets: NodeAssets,
+    data: Res<HeightmapData>,
+    manifest: Res<TerrainManifest>,
+    integ_budget: Res<IntegrationBudget>,
+    mut evw_loaded: EventWriter<TerrainChunkLoaded>,
+) {
+    let NodeAssets { mut meshes, mut materials, asset_server } = assets;
+    let tree = &mut *tree;
+    let mut integrated = 0usize;
+    let mut i = 0usize;
+    while i < tree.tasks.len() && integrated < integ_budget.0 {
+        let Some((mesh, bounds)) = check_ready(&mut tree.tasks[i].2) else {
+            i += 1;
+            continue;
+        };
+        let (node, rebuild, _) = tree.tasks.swap_remove(i);
+        integrated += 1;
+        tree.bounds.insert(node, bounds);
+
+        let material = if node.level == 0 {
+            materials.add(StandardMaterial {
+                base_color_texture: Some(asset_server.load(manifest.color_tile_path(node.x, node.z))),
+                base_color: Color::WHITE,
+                perceptual_roughness: 1.0,
+                metallic: 0.0,
+                ..default()
+            })
+        } else {
+            tree.merged_material
+                .get_or_insert_with(|| {
+                    materials.add(StandardMaterial {
+                        base_color: Color::WHITE,
+                        perceptual_roughness: 1.0,
+                        metallic: 0.0,
+                        ..default()
+                    })
+                })
+                .clone()
+        };
+
+        // A rebuilt node takes over its predecessor's visibility
+        let previous = tree.shown.remove(&node);
+        let visible = previous.as_ref().is_some_and(|p| p.visible);
+        let mut entity = commands.spawn((
+            Terrain,
+            node,
+            ChunkReady,
+            Transform::default(),
+            if visible { Visibility::Visible } else { Visibility::Hidden },
+            bevy::render::mesh::Mesh3d(meshes.add(mesh)),
+            bevy::pbr::MeshMaterial3d(material),
+            Name::new(format!("Quad node L{} ({},{})", node.level, node.x, node.z)),
+        ));
+        if node.level == 0 {
+            entity.insert(ChunkKey::new(node.x, node.z));
+        }
+        let entity = entity.id();
+        if let Some(p) = &previous {
+            commands.entity(p.entity).despawn();
+        }
+        tree.shown.insert(node, ShownNode { entity, visible });
+
+        // Leaves are chunks for everyone downstream
+        if node.level == 0 && (previous.is_none() || rebuild) {
+            let (min_xz, max_xz) = chunk_world_aabb(node.x, node.z, &data);
+            evw_loaded.write(TerrainChunkLoaded(ChunkArea {
+                coord: ChunkCoord { x: node.x, z: node.z },
+                min_xz,
+                max_xz,
+            }));
+        }
+    }
+}
+
+/// Mip level a node reads: the coarsest with a texel per vertex. Only leaves may read full
+/// tiles (with `mip_levels` set up, coarser nodes always find a coarser level).
+fn node_mip_level(node: QuadNode, n: usize, cache: &HeightTileCache) -> u32 {
+    let level = cache.level_for_cells(((n as u32 - 1) >> node.level).max(1));
+    if node.level > 0 {
+        level.max(1)
+    } else {
+        level
+    }
+}
+
+/// Tiles under the node (clipped to the map) at mip `level`, or `None` while any is still
+/// being read. Missing or unreadable tiles are left out (the node is void there).
+fn node_tiles(
+    node: QuadNode,
+    level: u32,
+    data: &HeightmapData,
+    cache: &HeightTileCache,
+) -> Option<HashMap<(i32, i32), Tile16>> {
+    let counts = chunk_counts(data);
+    let (cmin, cmax) = node.chunk_range();
+    let cmax = cmax.min(IVec2::new(counts.x, counts.z));
+    let mut tiles = HashMap::new();
+    let mut loading = false;
+    // Ask for every tile before giving up, so they are all read in parallel
+    for cz in cmin.y..cmax.y {
+        for cx in cmin.x..cmax.x {
+            match cache.request_tile_level(cx, cz, level) {
+                TileStatus::Ready(tile) => {
+                    tiles.insert((cx, cz), tile);
+                }
+                TileStatus::Loading => loading = true,
+                TileStatus::Unavailable(_) => {}
+            }
+        }
+    }
+    (!loading).then_some(tiles)
+}
+
+/// Mesh for one node plus its bounds, from the tiles under it at the node's mip level
+/// (see `node_tiles`); the error bound is the worst interpolation error the covered tiles allow at
+/// the node's vertex spacing (`bend * (spacing / texel)^2 / 8`).
+fn build_node_mesh(
+    node: QuadNode,
+    n: usize,
+    level: u32,
+    tiles: &HashMap<(i32, i32), Tile16>,
+    data: &HeightmapData,
+    tile_bounds: &TileBoundsStore,
+) -> (Mesh, NodeBounds) {
+    let (lo, hi) = node.world_rect(data);
+    let ext = hi - lo;
+    let step = ext / (n - 1) as f32;
+
+    // Tiles under the node (clipped to the map); missing or unreadable ones are void
+    let counts = chunk_counts(data);
+    let (cmin, cmax) = node.chunk_range();
+    let cmax = cmax.min(IVec2::new(counts.x, counts.z));
+    let mut bounds = NodeBounds { error: 0.0, min: f32::INFINITY, max: f32::NEG_INFINITY };
+    for cz in cmin.y..cmax.y {
+        for cx in cmin.x..cmax.x {
+            let Some(tile) = tiles.get(&(cx, cz)) else {
+                bounds.min = bounds.min.min(data.void_height);
+                bounds.max = bounds.max.max(data.void_height);
+                continue;
+            };
+            let tb = *tile_bounds
+                .lock()
+                .expect("quadtree tile bounds mutex poisoned")
+                .entry((level, cx, cz))
+                .or_insert_with(|| TileBounds::compute(tile, data));
+            let texel = data.chunk_size / (tile.res - UVec2::ONE).max(UVec2::ONE).as_vec2();
+            let ratio = (step / texel).max_element().max(1.0);
+            bounds.error = bounds.error.max(tb.bend * ratio * ratio / 8.0);
+            bounds.min = bounds.min.min(tb.min);
+            bounds.max = bounds.max.max(tb.max);
+        }
+    }
+
+    // Vertices on a tile border read the tile inside the node, so no neighbour is needed
+    let height = |p: Vec2| -> f32 {
+        let local = (p - data.origin) / data.chunk_size;
+        let key = local.floor().as_ivec2().clamp(cmin, (cmax - IVec2::ONE).max(cmin));
+        let uv = (local - key.as_vec2()).clamp(Vec2::ZERO, Vec2::ONE);
+        tiles.get(&(key.x, key.y)).map_or(data.void_height, |t| height_in_tile(t, uv, data))
+    };
+
+    let heights: Vec<f32> = (0..n * n)
+        .map(|k| height(lo + Vec2::new((k % n) as f32, (k / n) as f32) * step))
+        .collect();
+    let h_at = |i: usize, j: usize| heights[j * n + i];
+    let error = bounds.error;
+
+    let mut positions: Vec<[f32; 3]> = Vec::with_capacity(n * n + 4 * n);
+    let mut normals: Vec<[f32; 3]> = Vec::with_capacity(n * n + 4 * n);
+    let mut uvs: Vec<[f32; 2]> = Vec::with_capacity(n * n + 4 * n);
+    for j in 0..n {
+        for i in 0..n {
+            let p = lo + Vec2::new(i as f32, j as f32) * step;
+            let (il, ir) = (i.saturating_sub(1), (i + 1).min(n - 1));
+            let (jd, ju) = (j.saturating_sub(1), (j + 1).min(n - 1));
+            let dpx = (h_at(ir, j) - h_at(il, j)) / ((ir - il) as f32 * step.x).max(f32::EPSILON);
+            let dpz = (h_at(i, ju) - h_at(i, jd)) / ((ju - jd) as f32 * step.y).max(f32::EPSILON);
+            let nrm = Vec3::new(-dpx, 1.0, -dpz).normalize_or_zero();
+
+            positions.push([p.x, h_at(i, j), p.y]);
+            normals.push(nrm.to_array());
+            uvs.push([i as f32 / (n - 1) as f32, j as f32 / (n - 1) as f32]);
+        }
+    }
+
+    let mut indices: Vec<u32> = Vec::with_capacity((n - 1) * (n - 1) * 6 + 4 * (n - 1) * 6);
+    for j in 0..n - 1 {
+        for i in 0..n - 1 {
+            let i0 = (j * n + i) as u32;
+            let i1 = (j * n + i + 1) as u32;
+            let i2 = ((j + 1) * n + i) as u32;
+            let i3 = ((j + 1) * n + i + 1) as u32;
+            indices.extend_from_slice(&[i0, i2, i1, i1, i2, i3]);
+        }
+    }
+
+    // Skirts: hang each border down far enough to cover a coarser neighbour's error
+    let skirt = error * 2.0 + step.max_element() * 0.25 + 1.0;
+    let borders: [Vec<usize>; 4] = [
+        (0..n).collect(),                                // -Z
+        (0..n).map(|j| j * n + n - 1).collect(),         // +X
+        (0..n).rev().map(|i| (n - 1) * n + i).collect(), // +Z
+        (0..n).rev().map(|j| j * n).collect(),           // -X
+    ];
+    for border in borders {
+        let base = positions.len() as u32;
+        for &k in &border {
+            let [x, y, z] = positions[k];
+            positions.push([x, y - skirt, z]);
+            normals.push(normals[k]);
+            uvs.push(uvs[k]);
+        }
+        for w in 0..border.len() - 1 {
+            let (a, b) = (border[w] as u32, border[w + 1] as u32);
+            let (a2, b2) = (base + w as u32, base + w as u32 + 1);
+            indices.extend_from_slice(&[a, b, a2, b, b2, a2]);
+        }
+    }
+
+    let mut mesh = Mesh::new(PrimitiveTopology::TriangleList, Default::default());
+    if node.level > 0 {
+        let colors: Vec<[f32; 4]> = positions.iter().map(|p| height_tint(p[1], data)).collect();
+        mesh.insert_attribute(Mesh::ATTRIBUTE_COLOR, colors);
+    }
+    mesh.insert_attribute(Mesh::ATTRIBUTE_POSITION, positions);
+    mesh.insert_attribute(Mesh::ATTRIBUTE_NORMAL, normals);
+    mesh.insert_attribute(Mesh::ATTRIBUTE_UV_0, uvs);
+    mesh.insert_indices(Indices::U32(indices));
+    (mesh, bounds)
+}
+
+/// Lowland-to-highland ramp for untextured merged nodes.
+fn height_tint(y: f32, data: &HeightmapData) -> [f32; 4] {
+    let t = (y / data.height_scale.max(f32::EPSILON)).clamp(0.0, 1.0);
+    let low = Vec3::new(0.36, 0.31, 0.24);
+    let high = Vec3::new(0.78, 0.74, 0.70);
+    let c = low.lerp(high, t);
+    [c.x, c.y, c.z, 1.0]
+}
@@ -0,0 +1,251 @@
+// src/terrain/raycast.rs
+//! Ray vs heightfield. Walks the chunk grid, then the texel grid of each tile the ray
+//! crosses (2D DDA), and tests the two triangles of every texel cell whose height range
+//! overlaps the ray. Cells are split along the same diagonal as the chunk mesher.
+
+use bevy::math::{IVec2, Ray3d, Vec2, Vec3};
+
+use crate::heightmap_data::{HeightTileCache, HeightmapData, Tile16, TileStatus};
+
+/// First intersection of a ray with the terrain.
+#[derive(Clone, Copy, Debug)]
+pub struct TerrainHit {
+    pub position: Vec3,
+    /// Face normal of the hit triangle (points up).
+    pub normal: Vec3,
+    /// Chunk / tile key (cx, cz) containing the hit.
+    pub chunk: (i32, i32),
+    /// Distance along the ray.
+    pub distance: f32,
+}
+
+/// Cast `ray` against the terrain, up to `max_dist`. Tiles missing from a sparse map
+/// are flat at `void_height`; tiles that fail to load are skipped, and so are tiles that
+/// aren't resident yet (they are requested, never read on the calling thread).
+pub fn raycast(
+    ray: Ray3d,
+    max_dist: f32,
+    data: &HeightmapData,
+    cache: &HeightTileCache,
+) -> Option<TerrainHit> {
+    let origin = ray.origin;
+    let dir = *ray.direction;
+
+    // Clip to the terrain box; nothing can be hit outside it
+    let y_lo = data.void_height.min(0.0);
+    let y_hi = data.void_height.max(data.height_scale);
+    let box_min = Vec3::new(data.origin.x, y_lo, data.origin.y);
+    let box_max = Vec3::new(data.origin.x + data.size.x, y_hi, data.origin.y + data.size.y);
+    let (t_start, t_end) = clip_to_box(origin, dir, box_min, box_max, max_dist)?;
+
+    let origin_xz = Vec2::new(origin.x, origin.z);
+    let dir_xz = Vec2::new(dir.x, dir.z);
+    let chunks = IVec2::new(
+        (data.size.x / data.chunk_size.x).ceil() as i32,
+        (data.size.y / data.chunk_size.y).ceil() as i32,
+    );
+
+    let mut hit = None;
+    grid_walk(
+        origin_xz - data.origin,
+        dir_xz,
+        data.chunk_size,
+        chunks,
+        t_start,
+        t_end,
+        |cell, t0, t1| {
+            let key = (cell.x, cell.y);
+            hit = match cache.request_tile(key.0, key.1) {
+                TileStatus::Ready(tile) => raycast_tile(origin, dir, key, &tile, data, t0, t1),
+                TileStatus::Unavailable(e) if e.is_missing() => raycast_void(origin, dir, key, data, t0, t1),
+                _ => None,
+            };
+            hit.is_some()
+        },
+    );
+    hit
+}
+
+/// Texel-level walk inside one tile, restricted to `[t0, t1]`.
+fn raycast_tile(
+    origin: Vec3,
+    dir: Vec3,
+    key: (i32, i32),
+    tile: &Tile16,
+    data: &HeightmapData,
+    t0: f32,
+    t1: f32,
+) -> Option<TerrainHit> {
+    let cells = IVec2::new(tile.res.x as i32 - 1, tile.res.y as i32 - 1);
+    if cells.x < 1 || cells.y < 1 {
+        return None;
+    }
+    let spacing = data.chunk_size / cells.as_vec2();
+    let tile_origin = data.origin + Vec2::new(key.0 as f32, key.1 as f32) * data.chunk_size;
+
+    let (rmin, rmax) = data.raw_minmax;
+    let inv_span = if rmax > rmin { 1.0 / (rmax - rmin) } else { 0.0 };
+    let height = |x: i32, z: i32| -> f32 {
+        let raw = tile.get_clamped(x, z) as f32;
+        ((raw - rmin) * inv_span).clamp(0.0, 1.0) * data.height_scale
+    };
+
+    let mut hit = None;
+    grid_walk(
+        Vec2::new(origin.x, origin.z) - tile_origin,
+        Vec2::new(dir.x, dir.z),
+        spacing,
+        cells,
+        t0,
+        t1,
+        |cell, c0, c1| {
+            let (h00, h10) = (height(cell.x, cell.y), height(cell.x + 1, cell.y));
+            let (h01, h11) = (height(cell.x, cell.y + 1), height(cell.x + 1, cell.y + 1));
+
+            // Skip cells whose height range the ray segment doesn't overlap
+            let (ya, yb) = (origin.y + dir.y * c0, origin.y + dir.y * c1);
+            if ya.min(yb) > h00.max(h10).max(h01).max(h11) + 1e-3
+                || ya.max(yb) < h00.min(h10).min(h01).min(h11) - 1e-3
+            {
+                return false;
+            }
+
+            let corner = |i: i32, j: i32, h: f32| {
+                let p = tile_origin + Vec2::new(i as f32, j as f32) * spacing;
+                Vec3::new(p.x, h, p.y)
+            };
+            let p00 = corner(cell.x, cell.y, h00);
+            let p10 = corner(cell.x + 1, cell.y, h10);
+            let p01 = corner(cell.x, cell.y + 1, h01);
+            let p11 = corner(cell.x + 1, cell.y + 1, h11);
+
+            let best = [(p00, p01, p10), (p10, p01, p11)]
+                .into_iter()
+                .filter_map(|(a, b, c)| {
+                    let t = ray_triangle(origin, dir, a, b, c)?;
+                    // Small slack so hits exactly on a cell border aren't lost
+                    (t >= c0 - 1e-3 && t <= c1 + 1e-3).then_some((t, a, b, c))
+                })
+                .min_by(|x, y| x.0.total_cmp(&y.0));
+
+            if let Some((t, a, b, c)) = best {
+                let mut normal = (b - a).cross(c - a).normalize_or_zero();
+                if normal.y < 0.0 {
+                    normal = -normal;
+                }
+                hit = Some(TerrainHit { position: origin + dir * t, normal, chunk: key, distance: t });
+            }
+            hit.is_some()
+        },
+    );
+    hit
+}
+
+/// Missing tile: a flat plane at `void_height`.
+fn raycast_void(
+    origin: Vec3,
+    dir: Vec3,
+    key: (i32, i32),
+    data: &HeightmapData,
+    t0: f32,
+    t1: f32,
+) -> Option<TerrainHit> {
+    if dir.y.abs() < f32::EPSILON {
+        return None;
+    }
+    let t = (data.void_height - origin.y) / dir.y;
+    (t >= t0 && t <= t1).then(|| TerrainHit {
+        position: origin + dir * t,
+        normal: Vec3::Y,
+        chunk: key,
+        distance: t,
+    })
+}
+
+/// 2D DDA (Amanatides & Woo) over a grid of `cells` cells of size `cell_size` starting at
+/// the local origin. Calls `visit(cell, t_in, t_out)` front to back until it returns true.
+fn grid_walk(
+    origin: Vec2,
+    dir: Vec2,
+    cell_size: Vec2,
+    cells: IVec2,
+    t_start: f32,
+    t_end: f32,
+    mut visit: impl FnMut(IVec2, f32, f32) -> bool,
+) {
+    let p = (origin + dir * t_start) / cell_size;
+    let mut cell = p.floor().as_ivec2().clamp(IVec2::ZERO, cells - IVec2::ONE);
+
+    let step = IVec2::new(dir.x.signum() as i32, dir.y.signum() as i32);
+    let axis = |o: f32, d: f32, size: f32, c: i32, s: i32| -> (f32, f32) {
+        if d.abs() < 1e-12 {
+            return (f32::INFINITY, f32::INFINITY);
+        }
+        let boundary = (c + if s > 0 { 1 } else { 0 }) as f32 * size;
+        ((boundary - o) / d, size / d.abs())
+    };
+    let (mut next_x, delta_x) = axis(origin.x, dir.x, cell_size.x, cell.x, step.x);
+    let (mut next_z, delta_z) = axis(origin.y, dir.y, cell_size.y, cell.y, step.y);
+
+    let mut t_in = t_start;
+    while t_in <= t_end {
+        let t_out = next_x.min(next_z).min(t_end);
+        if visit(cell, t_in, t_out) {
+            return;
+        }
+        if next_x < next_z {
+            cell.x += step.x;
+            t_in = next_x;
+            next_x += delta_x;
+        } else {
+            cell.y += step.y;
+            t_in = next_z;
+            next_z += delta_z;
+        }
+        if t_in >= t_end || cell.x < 0 || cell.y < 0 || cell.x >= cells.x || cell.y >= cells.y {
+            return;
+        }
+    }
+}
+
+/// Slab test; returns the `[t0, t1]` overlap with `[0, max_dist]`.
+fn clip_to_box(origin: Vec3, dir: Vec3, min: Vec3, max: Vec3, max_dist: f32) -> Option<(f32, f32)> {
+    let mut t0 = 0.0f32;
+    let mut t1 = max_dist;
+    for k in 0..3 {
+        if dir[k].abs() < 1e-12 {
+            if origin[k] < min[k] || origin[k] > max[k] {
+                return None;
+            }
+            continue;
+        }
+        let inv = 1.0 / dir[k];
+        let (a, b) = ((min[k] - origin[k]) * inv, (max[k] - origin[k]) * inv);
+        t0 = t0.max(a.min(b));
+        t1 = t1.min(a.max(b));
+    }
+    (t0 <= t1).then_some((t0, t1))
+}
+
+/// Möller–Trumbore; two-sided, returns the ray parameter.
+fn ray_triangle(origin: Vec3, dir: Vec3, a: Vec3, b: Vec3, c: Vec3) -> Option<f32> {
+    let e1 = b - a;
+    let e2 = c - a;
+    let p = dir.cross(e2);
+    let det = e1.dot(p);
+    if det.abs() < 1e-9 {
+        return None;
+    }
+    let inv = 1.0 / det;
+    let s = origin - a;
+    let u = s.dot(p) * inv;
+    if !(-1e-5..=1.0 + 1e-5).contains(&u) {
+        return None;
+    }
+    let q = s.cross(e1);
+    let v = dir.dot(q) * inv;
+    if v < -1e-5 || u + v > 1.0 + 1e-5 {
+        return None;
+    }
+    Some(e2.dot(q) * inv)
+}
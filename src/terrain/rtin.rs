@@ -0,0 +1,192 @@
+// src/terrain/rtin.rs
+//! Adaptive chunk triangulation: right-triangulated irregular networks (RTIN, as in
+//! Mapbox's Martini). The chunk's vertex lattice is split into a binary tree of right
+//! triangles; a triangle is only subdivided where dropping its hypotenuse midpoint would
+//! move the surface by more than `max_error` meters, so flat ground ends up as a few large
+//! triangles.
+//!
+//! Every border vertex is always kept. Shared edges are then identical to the uniform
+//! mesher's (including LoD stitching), so adaptive chunks line up with any neighbour.
+
+use serde::{Deserialize, Serialize};
+
+/// How chunk meshes are triangulated.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Serialize, Deserialize)]
+pub enum ChunkMesher {
+    /// Full `grid_res` lattice.
+    #[default]
+    Uniform,
+    /// RTIN over the same lattice, dropping vertices that stay within `max_error` meters.
+    /// Needs a `2^k + 1` square grid (all `LodLevel`s are); other grids fall back to uniform.
+    Adaptive { max_error: f32 },
+}
+
+/// Per-vertex errors of an `n` x `n` heightfield (`n = 2^k + 1`).
+pub struct Rtin {
+    n: usize,
+    errors: Vec<f32>,
+}
+
+impl Rtin {
+    /// Whether an `nx` x `nz` lattice can be triangulated.
+    pub fn supports(nx: usize, nz: usize) -> bool {
+        nx == nz && nx >= 3 && (nx - 1).is_power_of_two()
+    }
+
+    /// Compute errors for row-major `heights` (`heights[j * n + i]`).
+    pub fn new(heights: &[f32], n: usize) -> Self {
+        debug_assert!(Self::supports(n, n) && heights.len() == n * n);
+        let size = n - 1;
+        let num_triangles = size * size * 2 - 2;
+        let num_parents = num_triangles - size * size;
+
+        // Border vertices are never dropped; the max below carries that up to their ancestors
+        let mut errors = vec![0.0f32; n * n];
+        for k in 0..n {
+            for idx in [k, size * n + k, k * n, k * n + size] {
+                errors[idx] = f32::INFINITY;
+            }
+        }
+
+        // Smallest triangles first, so children are final when their parent is visited
+        for t in (0..num_triangles).rev() {
+            let [a, b, c] = triangle_coords(t, size);
+            let m = midpoint(a, b);
+            let mi = m.1 * n + m.0;
+            let interpolated = 0.5 * (heights[a.1 * n + a.0] + heights[b.1 * n + b.0]);
+            let mut e = errors[mi].max((interpolated - heights[mi]).abs());
+            if t < num_parents {
+                let l = midpoint(a, c);
+                let r = midpoint(b, c);
+                e = e.max(errors[l.1 * n + l.0]).max(errors[r.1 * n + r.0]);
+            }
+            errors[mi] = e;
+        }
+        Self { n, errors }
+    }
+
+    /// Triangle indices into the lattice (front faces up, same winding as the uniform mesher).
+    pub fn indices(&self, max_error: f32) -> Vec<u32> {
+        let s = self.n - 1;
+        let mut out = Vec::new();
+        self.split((0, 0), (s, s), (s, 0), max_error, &mut out);
+        self.split((s, s), (0, 0), (0, s), max_error, &mut out);
+        out
+    }
+
+    fn split(&self, a: (usize, usize), b: (usize, usize), c: (usize, usize), max_error: f32, out: &mut Vec<u32>) {
+        let m = midpoint(a, b);
+        let leaf = a.0.abs_diff(c.0) + a.1.abs_diff(c.1) <= 1;
+        if !leaf && self.errors[m.1 * self.n + m.0] > max_error {
+            self.split(c, a, m, max_error, out);
+            self.split(b, c, m, max_error, out);
+            return;
+        }
+
+        let index = |p: (usize, usize)| (p.1 * self.n + p.0) as u32;
+        // Faces up when the (x, z) cross product is negative
+        let cross = (b.0 as i64 - a.0 as i64) * (c.1 as i64 - a.1 as i64)
+            - (b.1 as i64 - a.1 as i64) * (c.0 as i64 - a.0 as i64);
+        if cross < 0 {
+            out.extend_from_slice(&[index(a), index(b), index(c)]);
+        } else {
+            out.extend_from_slice(&[index(a), index(c), index(b)]);
+        }
+    }
+}
+
+fn midpoint(a: (usize, usize), b: (usize, usize)) -> (usize, usize) {
+    ((a.0 + b.0) / 2, (a.1 + b.1) / 2)
+}
+
+/// Corners (a, b, c) of triangle `t` in the implicit RTIN tree; `a`-`b` is the hypotenuse.
+fn triangle_coords(t: usize, size: usize) -> [(usize, usize); 3] {
+    let mut id = t + 2;
+    let (mut a, mut b, mut c) = if id & 1 == 1 {
+        // bottom-left half of the square
+        ((0, 0), (size, size), (size, 0))
+    } else {
+        // top-right half
+        ((size, size), (0, 0), (0, size))
+    };
+    loop {
+        id >>= 1;
+        if id <= 1 {
+            break;
+        }
+        let m = midpoint(a, b);
+        if id & 1 == 1 {
+            b = a;
+            a = c;
+        } else {
+            a = b;
+            b = c;
+        }
+        c = m;
+    }
+    [a, b, c]
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::HashSet;
+
+    use super::*;
+
+    const N: usize = 33;
+
+    /// Deterministic bumpy heights in [0, 10).
+    fn bumpy(n: usize) -> Vec<f32> {
+        let mut state = 0x9E37_79B9_7F4A_7C15u64;
+        (0..n * n)
+            .map(|_| {
+                state ^= state << 13;
+                state ^= state >> 7;
+                state ^= state << 17;
+                (state >> 40) as f32 / (1u64 << 24) as f32 * 10.0
+            })
+            .collect()
+    }
+
+    fn is_border(k: u32, n: usize) -> bool {
+        let (i, j) = (k as usize % n, k as usize / n);
+        i == 0 || j == 0 || i == n - 1 || j == n - 1
+    }
+
+    #[test]
+    fn flat_tile_reduces_to_its_border() {
+        // Kept border vertices pin their ancestors, so the coarsest possible mesh is the
+        // one any tile gets with an unbounded error
+        for n in [3, 5, 17, N] {
+            let flat = Rtin::new(&vec![4.0; n * n], n).indices(0.0);
+            let coarsest = Rtin::new(&bumpy(n), n).indices(f32::MAX);
+            assert_eq!(flat, coarsest, "n = {n}");
+        }
+        // Under a quarter of the full lattice's triangles
+        assert!(Rtin::new(&vec![4.0; N * N], N).indices(0.0).len() / 3 < (N - 1) * (N - 1) / 2);
+    }
+
+    #[test]
+    fn border_vertices_are_always_kept() {
+        let heights = bumpy(N);
+        for max_error in [0.0, 1.0, 100.0, f32::MAX] {
+            let kept: HashSet<u32> = Rtin::new(&heights, N).indices(max_error).into_iter().collect();
+            for k in (0..(N * N) as u32).filter(|&k| is_border(k, N)) {
+                assert!(kept.contains(&k), "border vertex {k} dropped at max_error {max_error}");
+            }
+        }
+    }
+
+    #[test]
+    fn raising_max_error_never_adds_triangles() {
+        let rtin = Rtin::new(&bumpy(N), N);
+        let full = rtin.indices(0.0).len();
+        assert_eq!(full / 3, 2 * (N - 1) * (N - 1));
+        let mut last = full;
+        for max_error in [0.1, 0.5, 1.0, 2.0, 5.0, 10.0, 100.0] {
+            let count = rtin.indices(max_error).len();
+            assert!(count <= last, "{count} > {last} indices at max_error {max_error}");
+            last = count;
+        }
+    }
+}
@@ -0,0 +1,207 @@
+// src/terrain/seams.rs
+//! Seam checks for height tile sets.
+//!
+//! Neighbouring tiles must agree on their shared edge texels (a tile's last column is its
+//! right neighbour's column 0, its last row is its upper neighbour's row 0). The chunk
+//! mesher borrows those samples from the neighbours, so any disagreement shows up as a
+//! crack or a slope spike along the chunk border.
+
+use crate::heightmap_data::{HeightTileCache, HeightTileError, Tile16, TileSet};
+
+/// Which edge two tiles share.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum SeamAxis {
+    /// `a`'s last column against the first column of `a + (1, 0)`.
+    X,
+    /// `a`'s last row against the first row of `a + (0, 1)`.
+    Z,
+}
+
+/// Edge agreement between one pair of adjacent tiles.
+#[derive(Clone, Debug)]
+pub struct SeamReport {
+    pub a: (i32, i32),
+    pub axis: SeamAxis,
+    /// Largest |a - b| along the edge, in raw tile units.
+    pub max_mismatch: u16,
+    pub mean_mismatch: f32,
+    /// The tiles have different edge lengths; only the common part was compared.
+    pub length_mismatch: bool,
+}
+
+impl SeamReport {
+    pub fn b(&self) -> (i32, i32) {
+        match self.axis {
+            SeamAxis::X => (self.a.0 + 1, self.a.1),
+            SeamAxis::Z => (self.a.0, self.a.1 + 1),
+        }
+    }
+
+    pub fn is_clean(&self, tolerance: u16) -> bool {
+        !self.length_mismatch && self.max_mismatch <= tolerance
+    }
+}
+
+/// Result of walking a whole tile set.
+#[derive(Clone, Debug, Default)]
+pub struct SeamValidation {
+    pub tiles_checked: usize,
+    pub seams: Vec<SeamReport>,
+    /// Tiles in the set that could not be loaded (missing, truncated, undecodable).
+    pub broken: Vec<((i32, i32), HeightTileError)>,
+}
+
+impl SeamValidation {
+    pub fn cracked(&self, tolerance: u16) -> impl Iterator<Item = &SeamReport> {
+        self.seams.iter().filter(move |s| !s.is_clean(tolerance))
+    }
+
+    pub fn is_clean(&self, tolerance: u16) -> bool {
+        self.broken.is_empty() && self.cracked(tolerance).next().is_none()
+    }
+}
+
+/// Compare every adjacent pair of tiles in `tiles`. Tiles are read through `cache`
+/// (row by row, so the LRU budget is respected on large maps).
+pub fn validate_tile_seams(cache: &HeightTileCache, tiles: &TileSet) -> SeamValidation {
+    let mut out = SeamValidation::default();
+
+    for (cx, cz) in row_major_keys(tiles) {
+        let a = match cache.try_fetch_tile(cx, cz) {
+            Ok(t) => t,
+            Err(e) => {
+                out.broken.push(((cx, cz), e));
+                continue;
+            }
+        };
+        out.tiles_checked += 1;
+
+        for (axis, (bx, bz)) in [(SeamAxis::X, (cx + 1, cz)), (SeamAxis::Z, (cx, cz + 1))] {
+            if !tiles.contains(bx, bz) {
+                continue;
+            }
+            // A broken neighbour is reported when its own key comes up
+            let Ok(b) = cache.try_fetch_tile(bx, bz) else { continue };
+            out.seams.push(compare_seam((cx, cz), axis, &a, &b));
+        }
+    }
+    out
+}
+
+/// Make every shared edge texel agree by averaging the tiles that contain it
+/// (two along an edge, up to four at a corner), then write the changed tiles back
+/// through `cache` (filesystem sources only). Neighbours with a different resolution
+/// are left alone. Returns how many tiles were rewritten.
+pub fn fix_tile_seams(cache: &HeightTileCache, tiles: &TileSet) -> Result<usize, HeightTileError> {
+    let mut rewritten = 0;
+
+    // Row-major: neighbours below / to the left are already fixed, so their shared texels
+    // are copied; texels shared only with unprocessed tiles are averaged from the originals.
+    for (cx, cz) in row_major_keys(tiles) {
+        let Ok(tile) = cache.try_fetch_tile(cx, cz) else { continue };
+        let (w, h) = (tile.res.x as i32, tile.res.y as i32);
+
+        // 3x3 neighbourhood indexed [dz + 1][dx + 1]; only same-resolution tiles take part
+        let mut nbs: [[Option<Tile16>; 3]; 3] = Default::default();
+        for dz in -1..=1 {
+            for dx in -1..=1 {
+                let (nx, nz) = (cx + dx, cz + dz);
+                if (dx, dz) == (0, 0) || !tiles.contains(nx, nz) {
+                    continue;
+                }
+                if let Ok(n) = cache.try_fetch_tile(nx, nz) {
+                    if n.res == tile.res {
+                        nbs[(dz + 1) as usize][(dx + 1) as usize] = Some(n);
+                    }
+                }
+            }
+        }
+
+        let mut data = tile.data.as_ref().clone();
+        let mut changed = false;
+        for j in 0..h {
+            for i in 0..w {
+                if i != 0 && i != w - 1 && j != 0 && j != h - 1 {
+                    continue;
+                }
+                let dxs: &[i32] = match (i == 0, i == w - 1) {
+                    (true, _) => &[-1, 0],
+                    (_, true) => &[0, 1],
+                    _ => &[0],
+                };
+                let dzs: &[i32] = match (j == 0, j == h - 1) {
+                    (true, _) => &[-1, 0],
+                    (_, true) => &[0, 1],
+                    _ => &[0],
+                };
+
+                let own = tile.get_clamped(i, j);
+                let mut fixed: Option<u16> = None;
+                let (mut sum, mut count) = (own as u32, 1u32);
+                for &dz in dzs {
+                    for &dx in dxs {
+                        if (dx, dz) == (0, 0) {
+                            continue;
+                        }
+                        let Some(n) = &nbs[(dz + 1) as usize][(dx + 1) as usize] else { continue };
+                        let ni = match dx { -1 => w - 1, 1 => 0, _ => i };
+                        let nj = match dz { -1 => h - 1, 1 => 0, _ => j };
+                        let v = n.get_clamped(ni, nj);
+                        if dz < 0 || (dz == 0 && dx < 0) {
+                            fixed.get_or_insert(v);
+                        } else {
+                            sum += v as u32;
+                            count += 1;
+                        }
+                    }
+                }
+
+                let v = fixed.unwrap_or(((sum + count / 2) / count) as u16);
+                if v != own {
+                    data[(j * w + i) as usize] = v;
+                    changed = true;
+                }
+            }
+        }
+
+        if changed {
+            cache.write_tile(cx, cz, Tile16 { res: tile.res, data: data.into() })?;
+            rewritten += 1;
+        }
+    }
+    Ok(rewritten)
+}
+
+fn row_major_keys(tiles: &TileSet) -> Vec<(i32, i32)> {
+    let mut keys = tiles.keys();
+    keys.sort_by_key(|&(cx, cz)| (cz, cx));
+    keys
+}
+
+fn compare_seam(a_key: (i32, i32), axis: SeamAxis, a: &Tile16, b: &Tile16) -> SeamReport {
+    let (len_a, len_b) = match axis {
+        SeamAxis::X => (a.res.y, b.res.y),
+        SeamAxis::Z => (a.res.x, b.res.x),
+    };
+    let len = len_a.min(len_b) as i32;
+
+    let mut max = 0u16;
+    let mut total = 0u64;
+    for k in 0..len {
+        let (va, vb) = match axis {
+            SeamAxis::X => (a.get_clamped(a.res.x as i32 - 1, k), b.get_clamped(0, k)),
+            SeamAxis::Z => (a.get_clamped(k, a.res.y as i32 - 1), b.get_clamped(k, 0)),
+        };
+        let d = va.abs_diff(vb);
+        max = max.max(d);
+        total += d as u64;
+    }
+
+    SeamReport {
+        a: a_key,
+        axis,
+        max_mismatch: max,
+        mean_mismatch: if len > 0 { total as f32 / len as f32 } else { 0.0 },
+        length_mismatch: len_a != len_b,
+    }
+}
@@ -1,101 +1,189 @@
 use bevy::prelude::*;
 
-use crate::heightmap_data::{HeightTileCache, HeightmapData};
+use bevy::ecs::system::SystemParam;
+use bevy::tasks::{futures::check_ready, IoTaskPool, Task};
+
+use crate::heightmap_data::{HeightTileCache, HeightTileError, TileSet, TileSource};
+use crate::props::core::{ChunkCoord, WorldSeed, DEFAULT_WORLD_SEED};
 use crate::terrain::chunking::ChunkManager;
 use crate::terrain::async_chunk_loader::AsyncChunkLoader;
-use crate::terrain::water::WaterLevel;
+use crate::terrain::deform::DirtyChunks;
+use crate::terrain::events::TerrainChunkUnloaded;
+use crate::terrain::manifest::TerrainManifest;
+use crate::terrain::mesh_cache::ChunkMeshCache;
+use crate::terrain::quadtree::QuadtreeTerrain;
+use crate::terrain::water::{SettledWaterLevel, WaterLevel};
 
 /// Vertex grid per chunk (X,Z). Use odd counts so edges align.
 pub const GRID_RES: UVec2 = UVec2::new(65, 65);
 
-/// World-space size of one chunk (X,Z).
+/// World-space size of one chunk (X,Z). Default for manifests that don't set `chunk_size`.
 pub const CHUNK_SIZE: Vec2 = Vec2::new(256.0, 256.0);
 
-pub fn init_terrain_params(mut commands: Commands, mut hmd: ResMut<HeightmapData>) {
-    hmd.chunk_size = CHUNK_SIZE;
-    commands.insert_resource(ChunkManager::new());
-}
-
-/// All the knobs that used to be `const` in plugin.rs
+/// Which map to stream. Change `manifest_path` at runtime to switch maps.
 #[derive(Resource, Clone)]
-pub struct TerrainConfig {
-    pub raw_folder: &'static str,
-    pub filename_prefix: &'static str,
-    pub filename_ext: &'static str,
-
-    pub color_folder: &'static str,
-    pub color_prefix: &'static str,
-    pub color_ext: &'static str,
-
-    pub tile_res_x: u32,
-    pub tile_res_z: u32,
-    pub tiles_x: i32,
-    pub tiles_z: i32,
-
-    pub raw_min: f32,
-    pub raw_max: f32,
-
-    pub origin_x: f32,
-    pub origin_z: f32,
-    pub height_scale_m: f32,
-
-    pub default_water_level: f32,
+pub struct TerrainSettings {
+    pub manifest_path: String,
 }
-
-impl Default for TerrainConfig {
+impl Default for TerrainSettings {
     fn default() -> Self {
         Self {
-            raw_folder: "assets/heightmaps",
-            filename_prefix: "Heightmap",
-            filename_ext: ".r16",
+            manifest_path: "terrain/chasma.terrain.ron".to_string(),
+        }
+    }
+}
 
-            color_folder: "textures",
-            color_prefix: "Texture",
-            color_ext: ".png",
+#[derive(Resource, Default)]
+pub struct TerrainManifestHandle(pub Handle<TerrainManifest>);
 
-            tile_res_x: 1024,
-            tile_res_z: 1024,
-            tiles_x: 16,
-            tiles_z: 16,
+/// (Re)load the manifest whenever `TerrainSettings` changes (including at startup).
+pub fn load_terrain_manifest(
+    settings: Res<TerrainSettings>,
+    mut handle_res: ResMut<TerrainManifestHandle>,
+    assets: Res<AssetServer>,
+) {
+    if !settings.is_changed() {
+        return;
+    }
+    handle_res.0 = assets.load(settings.manifest_path.as_str());
+    info!("Terrain: loading manifest '{}'", settings.manifest_path);
+}
 
-            raw_min: 0.0,
-            raw_max: 65535.0,
+/// A map's tile cache being set up on the IO task pool (`Discover` scans the tile folder).
+type PendingMap = (TerrainManifest, Task<Result<HeightTileCache, HeightTileError>>);
 
-            origin_x: 0.0,
-            origin_z: 0.0,
-            height_scale_m: 600.0,
+/// The active manifest asset and its load/edit events.
+#[derive(SystemParam)]
+pub struct ActiveManifest<'w, 's> {
+    events: EventReader<'w, 's, AssetEvent<TerrainManifest>>,
+    handle: Res<'w, TerrainManifestHandle>,
+    manifests: Res<'w, Assets<TerrainManifest>>,
+}
 
-            default_water_level: 40.0,
+impl ActiveManifest<'_, '_> {
+    /// The manifest, if it finished loading or was edited since the last call.
+    fn changed(&mut self) -> Option<&TerrainManifest> {
+        let mut dirty = false;
+        for ev in self.events.read() {
+            match ev {
+                AssetEvent::LoadedWithDependencies { id } | AssetEvent::Modified { id } => {
+                    dirty |= *id == self.handle.0.id();
+                }
+                _ => {}
+            }
+        }
+        if dirty {
+            self.manifests.get(&self.handle.0)
+        } else {
+            None
         }
     }
 }
 
-/// Startup: build and insert core terrain resources (HeightmapData, cache, loader, water level).
-pub fn init_terrain_resources(
+/// The map currently streamed, which a new manifest replaces.
+#[derive(SystemParam)]
+pub struct CurrentMap<'w> {
+    chunk_mgr: Option<Res<'w, ChunkManager>>,
+    cache: Option<Res<'w, HeightTileCache>>,
+    quadtree: Option<Res<'w, QuadtreeTerrain>>,
+    evw_unloaded: EventWriter<'w, TerrainChunkUnloaded>,
+}
+
+/// Build and (re)insert core terrain resources (HeightmapData, cache, loader, water level)
+/// whenever the active manifest finishes loading or is edited on disk. The tile cache is
+/// resolved on the IO task pool; the previous map stays up until it is ready.
+pub fn apply_terrain_manifest(
     mut commands: Commands,
-    cfg: Res<TerrainConfig>,
+    mut pending: Local<Option<PendingMap>>,
+    mut active: ActiveManifest,
+    asset_server: Res<AssetServer>,
+    seed: Option<Res<WorldSeed>>,
+    current: CurrentMap,
 ) {
-    // Compute total world size from chunk size and tile counts
-    let world_size = Vec2::new(CHUNK_SIZE.x * cfg.tiles_x as f32,
-                               CHUNK_SIZE.y * cfg.tiles_z as f32);
-
-    // Global heightmap metadata
-    let hmd = HeightmapData {
-        size: world_size,
-        origin: Vec2::new(cfg.origin_x, cfg.origin_z),
-        height_scale: cfg.height_scale_m,
-        chunk_size: CHUNK_SIZE,
-        raw_minmax: (cfg.raw_min, cfg.raw_max),
+    let CurrentMap { chunk_mgr, cache: old_cache, quadtree, mut evw_unloaded } = current;
+    let world_seed = seed.map_or(DEFAULT_WORLD_SEED, |s| s.0);
+    let start = |manifest: TerrainManifest| {
+        let old = old_cache.as_deref().cloned();
+        let source = TileSource::Assets(asset_server.clone());
+        let def = manifest.clone();
+        let task = IoTaskPool::get().spawn(async move {
+            // Don't lose edits that are still waiting for the autosave; saved before the
+            // new cache exists, so it reads them back
+            if let Some(old) = old {
+                if let Err(e) = old.save_edits() {
+                    error!("Terrain: could not save terrain edits: {}", e);
+                }
+            }
+            def.tile_cache(source, world_seed)
+        });
+        (manifest, task)
+    };
+    if let Some(manifest) = active.changed() {
+        // Replaces (and so cancels) a map still being set up
+        *pending = Some(start(manifest.clone()));
+    }
+    let Some((_, task)) = pending.as_mut() else { return };
+    let Some(result) = check_ready(task) else { return };
+    let Some((manifest, _)) = pending.take() else { return };
+    // Edited again while that ran: save those too and set up again
+    if old_cache.as_ref().is_some_and(|c| c.edits.as_ref().is_some_and(|l| l.has_unsaved())) {
+        *pending = Some(start(manifest));
+        return;
+    }
+    let cache = match result {
+        Ok(c) => c,
+        Err(e) => {
+            error!("Terrain: cannot resolve tiles for map '{}': {}", manifest.name, e);
+            return;
+        }
     };
 
-    // RAW16 tile cache
-    let mut cache = HeightTileCache::new(cfg.raw_folder, UVec2::new(cfg.tile_res_x, cfg.tile_res_z));
-    cache.filename_prefix = cfg.filename_prefix.to_string();
-    cache.filename_ext = cfg.filename_ext.to_string();
+    // Drop chunks from the previous map; their meshes came from the old tiles.
+    if let Some(mgr) = chunk_mgr {
+        for (&(x, z), (ent, _)) in mgr.loaded.iter() {
+            commands.entity(*ent).despawn();
+            evw_unloaded.write(TerrainChunkUnloaded(ChunkCoord { x, z }));
+        }
+    }
+    if let Some(tree) = quadtree {
+        for ent in tree.entities() {
+            commands.entity(ent).despawn();
+        }
+        for (x, z) in tree.shown_leaves() {
+            evw_unloaded.write(TerrainChunkUnloaded(ChunkCoord { x, z }));
+        }
+    }
+
+    let tiles = cache.tile_set.clone().unwrap_or_else(|| TileSet::full(IVec2::ONE));
+    if tiles.is_empty() {
+        warn!("Terrain: map '{}' has no tiles; everything samples as void", manifest.name);
+    }
+    let hmd = manifest.heightmap_data(&tiles);
+
+    info!(
+        "Terrain: map '{}' {} tiles in a {}x{} grid{}, {:.0}x{:.0} m, height scale {:.0} m",
+        manifest.name,
+        tiles.len(),
+        tiles.extent.x,
+        tiles.extent.y,
+        if tiles.is_sparse() { " (sparse)" } else { "" },
+        hmd.size.x,
+        hmd.size.y,
+        hmd.height_scale
+    );
 
     // Insert resources used by terrain pipeline
     commands.insert_resource(hmd);
     commands.insert_resource(cache);
-    commands.insert_resource(WaterLevel(cfg.default_water_level));
+    commands.insert_resource(WaterLevel(manifest.water_level));
+    commands.insert_resource(SettledWaterLevel(manifest.water_level));
+    commands.insert_resource(ChunkManager::new());
     commands.insert_resource(AsyncChunkLoader::default());
-}
\ No newline at end of file
+    commands.insert_resource(DirtyChunks::default());
+    commands.insert_resource(QuadtreeTerrain::default());
+    match manifest.chunk_mesh_cache(TileSource::Assets(asset_server.clone())) {
+        Some(mesh_cache) => commands.insert_resource(mesh_cache),
+        None => commands.remove_resource::<ChunkMeshCache>(),
+    }
+    commands.insert_resource(manifest);
+}
@@ -0,0 +1,87 @@
+// src/terrain/tile_asset.rs
+//! Height tiles as Bevy assets (`.r16` / `.raw16` / `.f32`, or PNG/TIFF via typed loads).
+
+use bevy::asset::{io::Reader, AssetLoader, LoadContext};
+use bevy::prelude::*;
+use serde::{Deserialize, Serialize};
+use std::sync::Arc;
+
+use crate::heightmap_data::{HeightTileCache, HeightTileError, Tile16};
+use crate::terrain::height_format::HeightFormat;
+
+pub struct HeightTileAssetPlugin;
+
+impl Plugin for HeightTileAssetPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_asset::<HeightTile>()
+            .register_asset_loader(HeightTileLoader)
+            .add_systems(Update, reload_changed_height_tiles);
+    }
+}
+
+/// One decoded height tile.
+#[derive(Asset, TypePath, Clone)]
+pub struct HeightTile(pub Tile16);
+
+/// `.meta` / `load_with_settings` knobs for height tiles.
+#[derive(Clone, Debug, Default, Serialize, Deserialize)]
+pub struct HeightTileLoaderSettings {
+    pub format: HeightFormat,
+    /// Texel resolution for RAW formats. `None` = infer a square tile from the file size.
+    pub resolution: Option<UVec2>,
+    /// Don't read the file; the asset only keeps its path watched for hot reload and
+    /// holds an empty tile. `HeightTileCache` watches its tiles this way.
+    pub watch_only: bool,
+}
+
+#[derive(Default)]
+pub struct HeightTileLoader;
+
+impl AssetLoader for HeightTileLoader {
+    type Asset = HeightTile;
+    type Settings = HeightTileLoaderSettings;
+    type Error = HeightTileError;
+
+    fn extensions(&self) -> &[&str] {
+        &["r16", "raw16", "f32"]
+    }
+
+    async fn load(
+        &self,
+        reader: &mut dyn Reader,
+        settings: &Self::Settings,
+        load_context: &mut LoadContext<'_>,
+    ) -> Result<Self::Asset, Self::Error> {
+        if settings.watch_only {
+            return Ok(HeightTile(Tile16 { res: UVec2::ZERO, data: Arc::new(Vec::new()) }));
+        }
+        let path = load_context.path().to_path_buf();
+        let mut bytes = Vec::new();
+        reader
+            .read_to_end(&mut bytes)
+            .await
+            .map_err(|e| HeightTileError::Io { path: path.clone(), reason: e.to_string() })?;
+        settings.format.decode(&path, &bytes, settings.resolution).map(HeightTile)
+    }
+}
+
+/// Push edited tile files into the shared tile store.
+fn reload_changed_height_tiles(
+    mut events: EventReader<AssetEvent<HeightTile>>,
+    asset_server: Res<AssetServer>,
+    cache: Option<Res<HeightTileCache>>,
+) {
+    let Some(cache) = cache else { return };
+    for ev in events.read() {
+        let (AssetEvent::Added { id } | AssetEvent::Modified { id }) = ev else { continue };
+        let Some(path) = asset_server.get_path(*id) else { continue };
+        let Some((cx, cz)) = cache.key_for_path(path.path()) else { continue };
+        if matches!(ev, AssetEvent::Added { .. }) {
+            // A watched file (re)appeared: retry it on the next fetch, nothing to swap in yet
+            cache.clear_failure(cx, cz);
+            continue;
+        }
+        info!("Terrain: hot-reloading height tile ({}, {}) from '{}'", cx, cz, path);
+        cache.reload_tile(cx, cz);
+    }
+}
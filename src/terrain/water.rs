@@ -9,14 +9,49 @@ use crate::heightmap_data::HeightmapData;
 #[derive(Resource)]
 pub struct WaterLevel(pub f32);
 
-/// Spawn a big, semi-transparent “water slab” across the heightmap.
+/// Seconds `WaterLevel` must hold still before `SettledWaterLevel` follows it.
+const WATER_LEVEL_SETTLE_SECS: f32 = 0.5;
+
+/// `WaterLevel` once it has held still for a moment. The map-wide analyses (drainage,
+/// distance to water) follow this one, so dragging the level doesn't restart them every frame.
+#[derive(Resource, Clone, Copy, Debug, PartialEq)]
+pub struct SettledWaterLevel(pub f32);
+
+/// Move `SettledWaterLevel` to `WaterLevel` after it has stopped changing.
+pub fn settle_water_level(
+    time: Res<Time>,
+    mut still: Local<f32>,
+    water: Res<WaterLevel>,
+    mut settled: ResMut<SettledWaterLevel>,
+) {
+    if water.is_changed() {
+        *still = 0.0;
+    } else {
+        *still += time.delta_secs();
+    }
+    if *still >= WATER_LEVEL_SETTLE_SECS {
+        settled.set_if_neq(SettledWaterLevel(water.0));
+    }
+}
+
+/// Marker for the water slab (so a map switch can replace it).
+#[derive(Component)]
+pub struct Water;
+
+/// Spawn a big, semi-transparent “water slab” across the heightmap,
+/// replacing the previous one when the level or map changes.
 pub fn spawn_water(
     mut commands: Commands,
+    old: Query<Entity, With<Water>>,
     mut meshes: ResMut<Assets<Mesh>>,
     mut materials: ResMut<Assets<StandardMaterial>>,
     heightmap: Res<HeightmapData>,
     water: Res<WaterLevel>,
 ) {
+    for e in &old {
+        commands.entity(e).despawn();
+    }
+
     // World extents in X/Z. Your map is centered at (0,0), so a cuboid of this size
     // will cover from -size/2..+size/2 in both axes, matching terrain bounds.
     let size_x = heightmap.size.x;
@@ -41,6 +76,7 @@ pub fn spawn_water(
         MeshMaterial3d(mat_h),
         Transform::from_translation(Vec3::new(0.0, water.0, 0.0)),
         GlobalTransform::default(),
+        Water,
         Name::new("Water"),
     ));
 }
@@ -1,5 +1,7 @@
 use bevy::prelude::*;
 
+use crate::terrain::TerrainAttributes;
+
 #[derive(Component)]
 pub struct Unit {
     /// How far above the sampled height the unit’s “feet” sit.
@@ -8,6 +10,23 @@ pub struct Unit {
     pub max_slope: f32,
 }
 
+impl Unit {
+    pub fn can_stand_on(&self, ground: &TerrainAttributes) -> bool {
+        ground.slope_deg <= self.max_slope.to_degrees()
+    }
+
+    /// How much slower than on flat, smooth ground the unit moves along `dir` (1 = full
+    /// speed): climbing costs in proportion to the grade, broken ground to its roughness.
+    /// Downhill is as cheap as flat. `None` where the unit can't stand.
+    pub fn movement_cost(&self, ground: &TerrainAttributes, dir: Vec2) -> Option<f32> {
+        if !self.can_stand_on(ground) {
+            return None;
+        }
+        let climb = ground.grade_along(dir).max(0.0) / self.max_slope.tan().max(f32::EPSILON);
+        Some(1.0 + climb + ground.roughness)
+    }
+}
+
 #[derive(Component)]
 pub struct MoveTo(pub Vec3);
 
@@ -1,5 +1,5 @@
 use bevy::prelude::*;
-use bevy::ecs::schedule::common_conditions::resource_exists;
+use bevy::ecs::schedule::common_conditions::{resource_added, resource_exists};
 use crate::heightmap_data::{HeightmapData, HeightTileCache};
 use crate::unit::systems::{
     spawn_unit, click_to_move, move_units, grounding_system, record_previous_system, collision_system,
@@ -11,11 +11,11 @@ pub struct UnitPlugin;
 impl Plugin for UnitPlugin {
     fn build(&self, app: &mut App) {
         app
-            // Run once when both resources are present (inserted by TerrainPlugin)
+            // Run once when the first map's resources appear (inserted by TerrainPlugin from its manifest)
             .add_systems(
-                Startup,
+                Update,
                 spawn_unit
-                    .run_if(resource_exists::<HeightmapData>)
+                    .run_if(resource_added::<HeightmapData>)
                     .run_if(resource_exists::<HeightTileCache>),
             )
             .add_systems(
@@ -26,7 +26,8 @@ impl Plugin for UnitPlugin {
                     move_units.after(click_to_move).run_if(in_state(GameState::Running)),
                     grounding_system.after(move_units).run_if(in_state(GameState::Running)),
                     collision_system.after(grounding_system).run_if(in_state(GameState::Running)),
-                ),
+                )
+                    .run_if(resource_exists::<HeightmapData>),
             );
     }
 }
@@ -3,20 +3,21 @@ use bevy::input::mouse::MouseButton;
 use bevy::input::ButtonInput;
 use bevy::window::{Window, PrimaryWindow};
 
-use crate::heightmap_data::{HeightmapData, HeightTileCache, sample_height};
+use crate::heightmap_data::{HeightmapData, HeightTileCache, sample_height_ready};
 use crate::unit::components::{Unit, MoveTo, Grounded, PreviousPosition};
-use crate::terrain::{ChunkCoords, LocalOffset, world_to_chunk_and_local};
+use crate::terrain::{ChunkCoords, Hydrology, LocalOffset, raycast, sample_terrain_ready, world_to_chunk_and_local};
 
 /// Spawns your pill-shaped unit, now chunked for seamless streaming
 pub fn spawn_unit(
     mut commands: Commands,
     mut meshes: ResMut<Assets<Mesh>>,
     mut materials: ResMut<Assets<StandardMaterial>>,
-    mut cache: ResMut<HeightTileCache>,
+    cache: Res<HeightTileCache>,
     heightmap: Res<HeightmapData>,
 ) {
     let desired_xz = Vec2::new(5.0, 5.0).round();
-    let ground_y = sample_height(desired_xz.x, desired_xz.y, &heightmap, &mut cache).unwrap_or(0.0);
+    // 0 until the tile is in; `grounding_system` snaps the unit once it is
+    let ground_y = sample_height_ready(desired_xz.x, desired_xz.y, &heightmap, &cache).unwrap_or(0.0);
 
     let scale = Vec3::new(0.5, 1.0, 0.5);
     let half_h = scale.y * 0.5;
@@ -61,19 +62,28 @@ pub fn spawn_unit(
     ));
 }
 
-/// Moves each `Unit` toward its `MoveTo.x/z` only.
+/// Moves each `Unit` toward its `MoveTo.x/z` only, slowed by the terrain's movement cost
+/// and by wading through rivers.
 pub fn move_units(
     time: Res<Time>,
-    mut query: Query<(&mut Transform, &MoveTo), With<Unit>>,
+    cache: Res<HeightTileCache>,
+    heightmap: Res<HeightmapData>,
+    hydrology: Option<Res<Hydrology>>,
+    mut query: Query<(&mut Transform, &MoveTo, &Unit)>,
 ) {
     const SPEED: f32 = 50.0;
     let dt = time.delta_secs();
 
-    for (mut tf, target) in query.iter_mut() {
+    for (mut tf, target, unit) in query.iter_mut() {
         let current = Vec2::new(tf.translation.x, tf.translation.z);
         let goal = Vec2::new(target.0.x, target.0.z);
         let dir = (goal - current).normalize_or_zero();
-        let step = dir * SPEED * dt;
+        // Too steep (or not loaded yet): `collision_system` decides, so don't slow down here
+        let cost = sample_terrain_ready(current.x, current.y, &heightmap, &cache)
+            .and_then(|ground| unit.movement_cost(&ground, dir))
+            .unwrap_or(1.0)
+            * hydrology.as_ref().map_or(1.0, |h| h.wading_cost(current.x, current.y));
+        let step = dir * SPEED / cost * dt;
 
         if current.distance(goal) > step.length() {
             tf.translation.x += step.x;
@@ -87,25 +97,28 @@ pub fn move_units(
 
 /// Snaps any `Grounded` entity to the heightmap each frame.
 pub fn grounding_system(
-    mut cache: ResMut<HeightTileCache>,
+    cache: Res<HeightTileCache>,
     heightmap: Res<HeightmapData>,
     mut query: Query<(&Grounded, &mut Transform)>,
 ) {
     for (grounded, mut transform) in &mut query {
         let x = transform.translation.x;
         let z = transform.translation.z;
-        if let Some(y) = sample_height(x, z, &heightmap, &mut cache) {
+        if let Some(y) = sample_height_ready(x, z, &heightmap, &cache) {
             transform.translation.y = y + grounded.offset;
         }
     }
 }
 
+/// How far the cursor ray looks for terrain.
+const PICK_MAX_DIST: f32 = 20_000.0;
+
 /// Click to move
 pub fn click_to_move(
     windows: Query<&Window, With<PrimaryWindow>>,
     buttons: Res<ButtonInput<MouseButton>>,
     cameras: Query<(&Camera, &GlobalTransform), With<Camera3d>>,
-    mut cache: ResMut<HeightTileCache>,
+    cache: Res<HeightTileCache>,
     heightmap: Res<HeightmapData>,
     mut movers: Query<&mut MoveTo, With<Unit>>,
 ) {
@@ -131,35 +144,13 @@ pub fn click_to_move(
         Ok(r) => r,
         Err(_) => return,
     };
-    let origin = ray.origin;
-    let dir = ray.direction;
-
-    if dir.y.abs() < f32::EPSILON {
+    let Some(hit) = raycast(ray, PICK_MAX_DIST, &heightmap, &cache) else {
         return;
-    }
-    let t_plane = -origin.y / dir.y;
-    if t_plane <= 0.0 {
-        return;
-    }
-
-    let mut t_low = 0.0;
-    let mut t_high = t_plane;
-    let mut hit = origin + dir * t_plane;
-    for _ in 0..6 {
-        let t_mid = (t_low + t_high) * 0.5;
-        let p = origin + dir * t_mid;
-        let ground_h = sample_height(p.x, p.z, &heightmap, &mut cache).unwrap_or(0.0);
-        if p.y > ground_h {
-            t_low = t_mid;
-        } else {
-            t_high = t_mid;
-            hit = p;
-        }
-    }
+    };
 
     for mut mv in movers.iter_mut() {
-        mv.0.x = hit.x;
-        mv.0.z = hit.z;
+        mv.0.x = hit.position.x;
+        mv.0.z = hit.position.z;
     }
 }
 
@@ -174,34 +165,36 @@ pub fn record_previous_system(
 
 /// Collision + slope checks
 pub fn collision_system(
-    mut cache: ResMut<HeightTileCache>,
+    cache: Res<HeightTileCache>,
     heightmap: Res<HeightmapData>,
     mut query: Query<(&Unit, &PreviousPosition, &mut Transform)>,
 ) {
     for (unit, prev, mut t) in &mut query {
         let pos = t.translation;
-        let ground_y = sample_height(pos.x, pos.z, &heightmap, &mut cache).unwrap_or(0.0) + unit.grounded_offset;
+        // Ground not loaded yet: nothing to collide with this frame
+        let Some(height) = sample_height_ready(pos.x, pos.z, &heightmap, &cache) else { continue };
+        let ground_y = height + unit.grounded_offset;
 
         if pos.y < ground_y {
             t.translation = **prev;
             continue;
         }
 
-        // Use actual tile resolution from cache (not a hardcoded const)
-        let dx = heightmap.chunk_size.x / cache.tile_resolution.x as f32;
-        let dz = heightmap.chunk_size.y / cache.tile_resolution.y as f32;
-
-        let h_x0 = sample_height(pos.x - dx, pos.z, &heightmap, &mut cache).unwrap_or(ground_y);
-        let h_x1 = sample_height(pos.x + dx, pos.z, &heightmap, &mut cache).unwrap_or(ground_y);
-        let dhdx = (h_x1 - h_x0) / (2.0 * dx);
-
-        let h_z0 = sample_height(pos.x, pos.z - dz, &heightmap, &mut cache).unwrap_or(ground_y);
-        let h_z1 = sample_height(pos.x, pos.z + dz, &heightmap, &mut cache).unwrap_or(ground_y);
-        let dhdz = (h_z1 - h_z0) / (2.0 * dz);
-
-        let slope = (dhdx * dhdx + dhdz * dhdz).sqrt();
-        let max_tan = unit.max_slope.tan();
-        if slope > max_tan {
+        // The tile's analysis came with it; without one (analysis off) difference the heights
+        let too_steep = match sample_terrain_ready(pos.x, pos.z, &heightmap, &cache) {
+            Some(ground) => !unit.can_stand_on(&ground),
+            None => {
+                // Use actual tile resolution from cache (not a hardcoded const)
+                let dx = heightmap.chunk_size.x / cache.tile_resolution.x as f32;
+                let dz = heightmap.chunk_size.y / cache.tile_resolution.y as f32;
+                let h = |x, z| sample_height_ready(x, z, &heightmap, &cache).unwrap_or(height);
+
+                let dhdx = (h(pos.x + dx, pos.z) - h(pos.x - dx, pos.z)) / (2.0 * dx);
+                let dhdz = (h(pos.x, pos.z + dz) - h(pos.x, pos.z - dz)) / (2.0 * dz);
+                (dhdx * dhdx + dhdz * dhdz).sqrt() > unit.max_slope.tan()
+            }
+        };
+        if too_steep {
             t.translation = **prev;
             continue;
         }